@@ -0,0 +1,51 @@
+use crate::rules::Turn;
+use serde::{Deserialize, Serialize};
+
+/// Represents all actions a player can take in a game.
+///
+/// Network layers and user interfaces can pass any action to
+/// [`Game::submit`](`crate::Game::submit`), which validates it against the state of the game.
+/// Actions serialize as objects tagged with a snake case `action` field, e.g.
+/// `{"action":"move","dice":3,"from":12}`. Wrap them in [`Versioned`](`crate::Versioned`) to
+/// exchange them with clients.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlayerAction {
+    /// Roll the dices
+    Roll,
+    /// Move a checker from a field by the value of a dice
+    Move {
+        /// The dice value to use
+        dice: u8,
+        /// The field to move from, numbered from the player's perspective
+        from: u8,
+    },
+    /// Move a checker from the bar by the value of a dice
+    MoveFromBar {
+        /// The dice value to use
+        dice: u8,
+    },
+    /// Play a complete turn with the dices rolled, see [`Game::play`](`crate::Game::play`)
+    PlayTurn(Turn),
+    /// Offer the doubling cube to the opponent
+    OfferDouble,
+    /// Accept the doubling cube offered by the opponent
+    Take,
+    /// Decline the doubling cube offered by the opponent, losing the game
+    Drop,
+    /// Give up the game
+    Resign,
+    /// Choose the doubles played after the bonus roll of a variant, see
+    /// [`Game::choose_doubles`](`crate::Game::choose_doubles`)
+    ChooseDoubles {
+        /// The dice value of the doubles
+        dice: u8,
+    },
+    /// Ask the opponent to take back the last turn played, before the opponent rolls
+    RequestTakeback,
+    /// Accept the takeback requested by the opponent, who plays the dices of the turn again, see
+    /// [`Game::takeback_requested`](`crate::Game::takeback_requested`)
+    AcceptTakeback,
+    /// Decline the takeback requested by the opponent
+    DeclineTakeback,
+}
@@ -0,0 +1,142 @@
+use crate::rules::{Board, Cube, Player};
+use crate::Error;
+
+/// Makes cube decisions from pip counts only, using the Keith count.
+///
+/// In a race, the Keith count adjusts each player's pip count for wastage: two pips for each
+/// checker beyond the first on the 1-point, one pip for each checker beyond the first on the
+/// 2-point and beyond the third on the 3-point, and one pip for each empty 4-, 5-, or 6-point.
+/// The count of the player on roll is then increased by one seventh. The player on roll doubles
+/// if the count exceeds the opponent's count by no more than 4, redoubles if by no more than 3,
+/// and the opponent takes if it exceeds their count by at least 2.
+///
+/// This needs no evaluator and is cheap, but only sane in races. As long as there is contact,
+/// the agent never doubles and always takes.
+///
+/// ```
+/// use backgammon::RaceCubeAgent;
+/// use backgammon::rules::{Board, Cube, Player};
+///
+/// let agent = RaceCubeAgent::new();
+/// let board = Board::new();
+/// assert!(!agent.double(&board, Player::Player0, &Cube::default()).unwrap());
+/// assert!(agent.take(&board, Player::Player1).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RaceCubeAgent;
+
+impl RaceCubeAgent {
+    /// Create a new agent
+    pub fn new() -> Self {
+        RaceCubeAgent
+    }
+
+    /// Decide if the player on roll should double, or redouble if the player owns the cube
+    pub fn double(&self, board: &Board, player: Player, cube: &Cube) -> Result<bool, Error> {
+        if board.has_contact() {
+            return Ok(false);
+        }
+        let limit = if cube.owner() == player { 3 } else { 4 };
+        Ok(difference(board, player)? <= 7 * limit)
+    }
+
+    /// Decide if the player offered the cube should take it
+    pub fn take(&self, board: &Board, player: Player) -> Result<bool, Error> {
+        if board.has_contact() {
+            return Ok(true);
+        }
+        Ok(difference(board, player.other())? >= 7 * 2)
+    }
+}
+
+/// Get seven times the amount by which the raised count of the player on roll exceeds the count
+/// of the opponent
+fn difference(board: &Board, roller: Player) -> Result<i32, Error> {
+    let roller_count = keith_count(board, roller)? as i32;
+    let opponent_count = keith_count(board, roller.other())? as i32;
+    Ok(8 * roller_count - 7 * opponent_count)
+}
+
+/// Get the Keith count of a player, i.e. the pip count adjusted for wastage
+fn keith_count(board: &Board, player: Player) -> Result<u32, Error> {
+    let (fields, _, _) = board.raw(player)?;
+    let pips = board.pip_count(player)? as u32;
+    let excess = |field: usize, keep: u8| fields[field].saturating_sub(keep) as u32;
+    let gaps = fields[3..6].iter().filter(|&&x| x == 0).count() as u32;
+    Ok(pips + 2 * excess(0, 1) + excess(1, 1) + excess(2, 3) + gaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Race position with the given checkers of both players, all others borne off
+    fn race(player0: &[(usize, u8)], player1: &[(usize, u8)]) -> Board {
+        let raw = |checkers: &[(usize, u8)]| {
+            let mut fields = [0; 24];
+            for &(field, amount) in checkers {
+                fields[field] = amount;
+            }
+            (fields, 0, 15 - checkers.iter().map(|(_, x)| x).sum::<u8>())
+        };
+        Board::from_raw(raw(player0), raw(player1))
+    }
+
+    #[test]
+    fn keith_counts() -> Result<(), Error> {
+        // 3 checkers on the 1-point, 2 on the 2-point, 5 on the 3-point, 4- and 5-point empty
+        let board = race(&[(0, 3), (1, 2), (2, 5), (5, 1)], &[(5, 1)]);
+        assert_eq!(
+            keith_count(&board, Player::Player0)?,
+            3 + 4 + 15 + 6 + 4 + 1 + 2 + 2
+        );
+        assert_eq!(keith_count(&board, Player::Player1)?, 6 + 2);
+        Ok(())
+    }
+
+    #[test]
+    fn contact() -> Result<(), Error> {
+        let agent = RaceCubeAgent::new();
+        let board = Board::new();
+        assert!(!agent.double(&board, Player::Player0, &Cube::default())?);
+        assert!(agent.take(&board, Player::Player0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn race_decisions() -> Result<(), Error> {
+        let agent = RaceCubeAgent::new();
+        let cube = Cube::default();
+
+        // equal long race: 8/7 of 74 exceeds 74 by more than 4, no double
+        let even = race(&[(3, 5), (4, 5), (5, 2)], &[(3, 5), (4, 5), (5, 2)]);
+        assert!(!agent.double(&even, Player::Player0, &cube)?);
+        assert!(agent.take(&even, Player::Player1)?);
+
+        // player 0 far ahead: double, and player 1 passes
+        let ahead = race(&[(3, 3)], &[(3, 5), (4, 5), (5, 2)]);
+        assert!(agent.double(&ahead, Player::Player0, &cube)?);
+        assert!(!agent.take(&ahead, Player::Player1)?);
+
+        // equal counts of 22: 8/7 exceed by 3 1/7, a double but no redouble, and a take
+        let close = race(&[(3, 4), (4, 1)], &[(3, 4), (4, 1)]);
+        assert_eq!(keith_count(&close, Player::Player0)?, 22);
+        let mut owned = Cube::default();
+        owned.set_owner(Player::Player0);
+        assert!(agent.double(&close, Player::Player0, &cube)?);
+        assert!(!agent.double(&close, Player::Player0, &owned)?);
+        assert!(agent.take(&close, Player::Player1)?);
+
+        // against a count of 23, the excess is 2 1/7: redouble and still a take
+        let closer = race(&[(3, 4), (4, 1)], &[(0, 1), (3, 4), (4, 1)]);
+        assert_eq!(keith_count(&closer, Player::Player1)?, 23);
+        assert!(agent.double(&closer, Player::Player0, &owned)?);
+        assert!(agent.take(&closer, Player::Player1)?);
+
+        // a count of 29 each exceeds by 4 1/7, no double
+        let long = race(&[(3, 4), (5, 2)], &[(3, 4), (5, 2)]);
+        assert_eq!(keith_count(&long, Player::Player0)?, 29);
+        assert!(!agent.double(&long, Player::Player0, &cube)?);
+        Ok(())
+    }
+}
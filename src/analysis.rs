@@ -0,0 +1,319 @@
+use crate::rules::{Board, MoveChecker, Player, Turn};
+use crate::sgf::{location, parse_trees, Node};
+use crate::{Error, GameRecord};
+use serde::{Deserialize, Serialize};
+
+/// Represents the judgement of an action, as marked in GNU Backgammon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mark {
+    /// A very good action, `TE[2]`
+    VeryGood,
+    /// A good action, `TE[1]`
+    Good,
+    /// An interesting action, `IT[]`
+    Interesting,
+    /// A doubtful action, `DO[]`
+    Doubtful,
+    /// A bad action, `BM[1]`
+    Bad,
+    /// A very bad action, `BM[2]`
+    VeryBad,
+}
+
+/// Represents the figures of an evaluation or a rollout, from the point of view of the player
+/// acting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// Whether the figures come from a rollout rather than an evaluation
+    pub rollout: bool,
+    /// The probabilities to win, to win a gammon, to win a backgammon, to lose a gammon and to
+    /// lose a backgammon
+    pub probabilities: [f64; 5],
+    /// The equity, if given
+    pub equity: Option<f64>,
+}
+
+/// Represents a candidate play of a turn with its evaluation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveAnalysis {
+    /// The candidate play, a legal play of the dices rolled
+    pub turn: Turn,
+    /// The evaluation of the play
+    pub evaluation: Evaluation,
+}
+
+/// Represents the analysis of an action of a game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// The player acting
+    pub player: Player,
+    /// The index of the turn played in [`GameRecord::turns`], none for a cube action
+    pub turn: Option<usize>,
+    /// The candidate plays of the turn, best first, `A[]`
+    pub candidates: Vec<MoveAnalysis>,
+    /// The evaluation of the cube decision, `DA[]`
+    pub cube: Option<Evaluation>,
+    /// The luck of the roll, `LU[]`
+    pub luck: Option<f64>,
+    /// The judgement of the action
+    pub mark: Option<Mark>,
+    /// The comment on the action, `C[]`
+    pub comment: Option<String>,
+}
+
+/// Represents a game together with the analysis of its actions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotatedGame {
+    /// The game played
+    pub record: GameRecord,
+    /// The analysis of each action, turns and cube actions, in the order they were played
+    pub annotations: Vec<Annotation>,
+}
+
+/// Represents a match analysed by GNU Backgammon, see [`AnnotatedMatch::from_sgf`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotatedMatch {
+    /// The length of the match, if given, `MI[length:]` of the first game
+    pub points: Option<u32>,
+    /// The games of the match, in the order they were played
+    pub games: Vec<AnnotatedGame>,
+}
+
+/// Parse the figures of an evaluation, `E` or `R` followed by the probabilities and the
+/// equity. The settings GNU Backgammon writes after them are ignored.
+fn evaluation(figures: &[&str]) -> Result<Evaluation, Error> {
+    let mut figures = figures.iter().copied();
+    let rollout = match figures.next() {
+        Some("E") => false,
+        Some("R") => true,
+        _ => return Err(Error::SgfInvalid),
+    };
+    let mut figure = || figures.next().map(str::parse::<f64>).transpose();
+    let mut probabilities = [0.0; 5];
+    for probability in &mut probabilities {
+        *probability = figure()
+            .ok()
+            .flatten()
+            .filter(|p| (0.0..=1.0).contains(p))
+            .ok_or(Error::SgfInvalid)?;
+    }
+    let equity = figure().map_err(|_| Error::SgfInvalid)?;
+    Ok(Evaluation {
+        rollout,
+        probabilities,
+        equity,
+    })
+}
+
+/// Parse the analysis of an action node which played the turn at the given index from the board
+fn annotation(
+    node: &Node,
+    player: Player,
+    played: Option<(usize, &Board, &Turn)>,
+) -> Result<Annotation, Error> {
+    let values = |id: &str| {
+        node.iter()
+            .find(|(property, _)| property == id)
+            .map(|(_, values)| values)
+    };
+    let single = |id: &str| values(id).map(|values| values[0].as_str());
+
+    let mut candidates = Vec::new();
+    for value in values("A").into_iter().flatten() {
+        let mut figures: Vec<_> = value.split_whitespace().collect();
+        // GNU Backgammon writes the index of the play chosen first
+        if figures.first().is_some_and(|f| f.parse::<usize>().is_ok()) {
+            continue;
+        }
+        let (_, board, played) = played.ok_or(Error::SgfInvalid)?;
+        let letters = match figures.first() {
+            Some(f) if f.bytes().all(|b| b.is_ascii_lowercase()) => figures.remove(0),
+            _ => "",
+        };
+        if letters.len() % 2 != 0 {
+            return Err(Error::SgfInvalid);
+        }
+        let moves = letters
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| MoveChecker::new(player, location(pair[0])?, location(pair[1])?))
+            .collect::<Result<Vec<_>, _>>()?;
+        candidates.push(MoveAnalysis {
+            turn: Turn::new(board, player, played.dices(), moves)?,
+            evaluation: evaluation(&figures)?,
+        });
+    }
+    let cube = single("DA")
+        .map(|value| evaluation(&value.split_whitespace().collect::<Vec<_>>()))
+        .transpose()?;
+    let luck = single("LU")
+        .map(|value| value.trim().parse().map_err(|_| Error::SgfInvalid))
+        .transpose()?;
+
+    let mark = if let Some(value) = single("BM") {
+        Some(match value {
+            "2" => Mark::VeryBad,
+            _ => Mark::Bad,
+        })
+    } else if let Some(value) = single("TE") {
+        Some(match value {
+            "2" => Mark::VeryGood,
+            _ => Mark::Good,
+        })
+    } else if single("DO").is_some() {
+        Some(Mark::Doubtful)
+    } else {
+        single("IT").map(|_| Mark::Interesting)
+    };
+    Ok(Annotation {
+        player,
+        turn: played.map(|(turn, _, _)| turn),
+        candidates,
+        cube,
+        luck,
+        mark,
+        comment: single("C").map(String::from),
+    })
+}
+
+impl AnnotatedGame {
+    /// Parse a game with its analysis from the nodes of its game tree
+    fn from_nodes(nodes: &[Node]) -> Result<AnnotatedGame, Error> {
+        let record = GameRecord::from_nodes(nodes)?;
+        let boards = record.boards()?;
+        let mut annotations = Vec::new();
+        // the record holds the plays of the nodes in order
+        let mut turns = 0;
+        for node in nodes.iter().skip(1) {
+            let Some((id, values)) = node.iter().find(|(id, _)| id == "W" || id == "B") else {
+                continue;
+            };
+            let player = match id.as_str() {
+                "W" => Player::Player0,
+                _ => Player::Player1,
+            };
+            let played = match values[0].as_str() {
+                "double" | "take" | "drop" => None,
+                _ => {
+                    let index = turns;
+                    turns += 1;
+                    Some((index, &boards[index], &record.turns[index]))
+                }
+            };
+            annotations.push(annotation(node, player, played)?);
+        }
+        Ok(AnnotatedGame {
+            record,
+            annotations,
+        })
+    }
+}
+
+impl AnnotatedMatch {
+    /// Parse a match with its analysis from an SGF file written by GNU Backgammon, which holds
+    /// one game tree per game.
+    ///
+    /// The analysis is read from the properties GNU Backgammon writes on the nodes of the
+    /// actions: the candidate plays `A`, the cube decision `DA`, the luck `LU`, the marks `TE`,
+    /// `BM`, `DO` and `IT`, and comments `C`. Candidate plays have to be legal plays of the dices
+    /// rolled. Match files in the `.mat` text format carry no analysis, so only SGF is read.
+    ///
+    /// ```
+    /// use backgammon::{AnnotatedMatch, Mark};
+    ///
+    /// let sgf = "(;FF[4]GM[6]MI[length:5][game:0][ws:0][bs:0]
+    ///   ;W[31qtst]A[0][qtst E 0.55 0.16 0.01 0.12 0.005 0.17][lost E 0.53 0.15 0.01 0.13 0.006 0.11]TE[1])";
+    /// let m = AnnotatedMatch::from_sgf(sgf).unwrap();
+    /// assert_eq!(m.points, Some(5));
+    /// let annotation = &m.games[0].annotations[0];
+    /// assert_eq!(annotation.candidates[1].turn.to_string(), "13/10 6/5");
+    /// assert_eq!(annotation.mark, Some(Mark::Good));
+    /// ```
+    pub fn from_sgf(sgf: &str) -> Result<AnnotatedMatch, Error> {
+        let trees = parse_trees(sgf)?;
+        let points = match trees[0].first().and_then(|root| {
+            root.iter()
+                .find(|(id, _)| id == "MI")
+                .and_then(|(_, values)| values.iter().find_map(|v| v.strip_prefix("length:")))
+        }) {
+            Some(length) => Some(length.parse().map_err(|_| Error::SgfInvalid)?),
+            None => None,
+        };
+        let games = trees
+            .iter()
+            .map(|nodes| AnnotatedGame::from_nodes(nodes))
+            .collect::<Result<_, _>>()?;
+        Ok(AnnotatedMatch { points, games })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analysis() -> Result<(), Error> {
+        let sgf = "(;FF[4]GM[6]MI[length:3][game:0][ws:0][bs:0]RE[B+1]
+                   ;W[31qtst]A[0][qtst E 0.55 0.16 0.01 0.12 0.005 0.170 2 1 0.0 0]
+                     [lost R 0.53 0.15 0.01 0.13 0.006 0.112]LU[0.412]C[the best opening]
+                   ;B[double]DA[E 0.45 0.12 0.005 0.16 0.01]BM[2]
+                   ;W[drop]DO[])
+                   (;FF[4]GM[6]RE[B+1R];W[31qtst];B[64xrmi]IT[])";
+        let m = AnnotatedMatch::from_sgf(sgf)?;
+        assert_eq!(m.points, Some(3));
+        assert_eq!(m.games.len(), 2);
+
+        let game = &m.games[0];
+        assert_eq!(game.record.turns.len(), 1);
+        assert_eq!(
+            (game.record.winner, game.record.points),
+            (Player::Player1, 1)
+        );
+        assert_eq!(game.annotations.len(), 3);
+        let turn = &game.annotations[0];
+        assert_eq!((turn.player, turn.turn), (Player::Player0, Some(0)));
+        assert_eq!(turn.candidates.len(), 2);
+        assert_eq!(turn.candidates[0].turn, game.record.turns[0]);
+        assert_eq!(
+            turn.candidates[0].evaluation,
+            Evaluation {
+                rollout: false,
+                probabilities: [0.55, 0.16, 0.01, 0.12, 0.005],
+                equity: Some(0.17),
+            }
+        );
+        assert_eq!(turn.candidates[1].turn.to_string(), "13/10 6/5");
+        assert!(turn.candidates[1].evaluation.rollout);
+        assert_eq!(turn.luck, Some(0.412));
+        assert_eq!(turn.comment.as_deref(), Some("the best opening"));
+
+        let double = &game.annotations[1];
+        assert_eq!((double.player, double.turn), (Player::Player1, None));
+        assert_eq!(double.cube.map(|cube| cube.equity), Some(None));
+        assert_eq!(double.mark, Some(Mark::VeryBad));
+        assert_eq!(game.annotations[2].mark, Some(Mark::Doubtful));
+
+        let second = &m.games[1].annotations;
+        assert_eq!(second[1].turn, Some(1));
+        assert_eq!(second[1].mark, Some(Mark::Interesting));
+        assert!(second[0].candidates.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for sgf in [
+            "(;GM[6];W[31qtst]A[qtst E 0.5])",
+            "(;GM[6];W[31qtst]A[qtst X 0.5 0.1 0.0 0.1 0.0])",
+            "(;GM[6];W[31qtst]A[qtst E 1.5 0.1 0.0 0.1 0.0])",
+            "(;GM[6];W[31qtst]A[aaaa E 0.5 0.1 0.0 0.1 0.0])",
+            "(;GM[6];W[31qtst]A[qts E 0.5 0.1 0.0 0.1 0.0])",
+            "(;GM[6];W[double]A[qtst E 0.5 0.1 0.0 0.1 0.0])",
+            "(;GM[6];W[31qtst]LU[lucky])",
+            "(;GM[6]MI[length:x];W[31qtst])",
+            "(;GM[6];W[31qtst])(;GM[1])",
+        ] {
+            assert!(AnnotatedMatch::from_sgf(sgf).is_err(), "{}", sgf);
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use crate::record::{Double, GameRecord};
+use crate::rules::{Board, Player};
+use crate::Error;
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// The magic bytes at the start of a compact match
+const MAGIC: [u8; 4] = *b"BGCM";
+
+/// The version of the compact format, changing with the order of the plays generated
+const VERSION: u8 = 1;
+
+/// Rolls the dices of a match deterministically from a seed, as expected by [`CompactMatch`].
+///
+/// Each game starts with the [`opening`](MatchDices::opening) roll, followed by one
+/// [`roll`](MatchDices::roll) for each further turn.
+#[derive(Debug, Clone)]
+pub struct MatchDices {
+    rng: StdRng,
+}
+
+impl MatchDices {
+    /// Create the dices of a match from a seed
+    pub fn new(seed: [u8; 32]) -> Self {
+        MatchDices {
+            rng: StdRng::from_seed(seed),
+        }
+    }
+
+    /// Roll one dice
+    fn dice(&mut self) -> u8 {
+        Uniform::new_inclusive(1, 6).sample(&mut self.rng)
+    }
+
+    /// Roll the opening roll of a game: each player rolls one dice, the first one of player 0,
+    /// until they differ. The player with the higher dice plays both.
+    pub fn opening(&mut self) -> (u8, u8) {
+        loop {
+            let dices = (self.dice(), self.dice());
+            if dices.0 != dices.1 {
+                return dices;
+            }
+        }
+    }
+
+    /// Roll the dices of a turn
+    pub fn roll(&mut self) -> (u8, u8) {
+        (self.dice(), self.dice())
+    }
+}
+
+/// Get the player playing the opening roll
+fn opener(dices: (u8, u8)) -> Player {
+    if dices.0 > dices.1 {
+        Player::Player0
+    } else {
+        Player::Player1
+    }
+}
+
+/// Append a number as LEB128 variable-length integer
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads the bytes of a compact match
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    /// Read a byte
+    fn byte(&mut self) -> Result<u8, Error> {
+        let (&byte, rest) = self.bytes.split_first().ok_or(Error::CompactMatchInvalid)?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    /// Read a LEB128 variable-length integer
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f)
+                .checked_shl(shift)
+                .ok_or(Error::CompactMatchInvalid)?;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::CompactMatchInvalid)
+    }
+
+    /// Read a variable-length integer as index or length
+    fn index(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.varint()?).map_err(|_| Error::CompactMatchInvalid)
+    }
+}
+
+/// Represents a match in a compact binary format for storage and network sync, holding the seed
+/// of the dices and for each turn only the index of the play chosen.
+///
+/// The dices are rolled with [`MatchDices`] from the seed, and each choice is the index into the
+/// plays generated for the position and dices, which are listed once per position reached in a
+/// deterministic order. A turn of a few moves hence takes a single byte. Turns are decoded in the
+/// order generated, which may differ from the order the moves were played in.
+///
+/// The format starts with the magic bytes `BGCM`, the version and the seed. Each game follows
+/// as the amount of turns and their choices, the doubles offered, and the winner with the points
+/// won, all numbers as LEB128 variable-length integers.
+///
+/// ```
+/// use backgammon::{CompactMatch, GameRecord, MatchDices};
+/// use backgammon::rules::{Board, Player};
+///
+/// let seed = [7; 32];
+/// let dices = MatchDices::new(seed).opening();
+/// let player = if dices.0 > dices.1 { Player::Player0 } else { Player::Player1 };
+/// let turn = Board::new().legal_turns(player, dices).unwrap().pop().unwrap();
+/// let record = GameRecord { turns: vec![turn], ..GameRecord::default() };
+///
+/// let compact = CompactMatch { seed, games: vec![record] };
+/// let bytes = compact.encode().unwrap();
+/// assert!(bytes.len() < 48);
+/// assert_eq!(CompactMatch::decode(&bytes).unwrap().games, compact.games);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompactMatch {
+    /// The seed the dices of the match are rolled from
+    pub seed: [u8; 32],
+    /// The games of the match in the order they were played
+    pub games: Vec<GameRecord>,
+}
+
+impl CompactMatch {
+    /// Encode the match, checking that the turns were played with the dices of the seed and are
+    /// legal plays
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed);
+        push_varint(&mut bytes, self.games.len() as u64);
+
+        let mut dices = MatchDices::new(self.seed);
+        for game in &self.games {
+            let mut board = Board::new();
+            let mut rolled = dices.opening();
+            let mut player = opener(rolled);
+            push_varint(&mut bytes, game.turns.len() as u64);
+            for (i, turn) in game.turns.iter().enumerate() {
+                if i > 0 {
+                    rolled = dices.roll();
+                }
+                if turn.player() != player || turn.dices() != rolled {
+                    return Err(Error::DiceInvalid);
+                }
+                let mut after = board.clone();
+                turn.apply(&mut after)?;
+                let index = board
+                    .generate_plays(player, rolled)?
+                    .iter()
+                    .position(|(_, reached)| *reached == after)
+                    .ok_or(Error::MoveInvalid)?;
+                push_varint(&mut bytes, index as u64);
+                board = after;
+                player = player.other();
+            }
+
+            push_varint(&mut bytes, game.doubles.len() as u64);
+            for double in &game.doubles {
+                let player = match double.player {
+                    Player::Player0 => 0,
+                    Player::Player1 => 1,
+                    Player::Nobody => return Err(Error::PlayerInvalid),
+                };
+                push_varint(&mut bytes, double.turn as u64);
+                bytes.push(player | u8::from(double.taken) << 1);
+            }
+            bytes.push(match game.winner {
+                Player::Player0 => 0,
+                Player::Player1 => 1,
+                Player::Nobody => 2,
+            });
+            push_varint(&mut bytes, game.points);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a match encoded by [`CompactMatch::encode`], replaying the plays chosen
+    pub fn decode(bytes: &[u8]) -> Result<CompactMatch, Error> {
+        let mut reader = Reader { bytes };
+        let mut header = [0; 5];
+        for byte in header.iter_mut() {
+            *byte = reader.byte()?;
+        }
+        if header[..4] != MAGIC || header[4] != VERSION {
+            return Err(Error::CompactMatchInvalid);
+        }
+        let mut seed = [0; 32];
+        for byte in seed.iter_mut() {
+            *byte = reader.byte()?;
+        }
+
+        let mut dices = MatchDices::new(seed);
+        let mut games = Vec::new();
+        for _ in 0..reader.index()? {
+            let mut board = Board::new();
+            let mut rolled = dices.opening();
+            let mut player = opener(rolled);
+            let mut game = GameRecord::default();
+            for i in 0..reader.index()? {
+                if i > 0 {
+                    rolled = dices.roll();
+                }
+                let mut plays = board.generate_plays(player, rolled)?;
+                let index = reader.index()?;
+                if index >= plays.len() {
+                    return Err(Error::CompactMatchInvalid);
+                }
+                let (turn, after) = plays.swap_remove(index);
+                game.turns.push(turn);
+                board = after;
+                player = player.other();
+            }
+
+            for _ in 0..reader.index()? {
+                let turn = reader.index()?;
+                let flags = reader.byte()?;
+                if turn > game.turns.len() || flags > 3 {
+                    return Err(Error::CompactMatchInvalid);
+                }
+                let player = match flags & 1 {
+                    0 => Player::Player0,
+                    _ => Player::Player1,
+                };
+                game.doubles.push(Double {
+                    turn,
+                    player,
+                    taken: flags & 2 != 0,
+                });
+            }
+            game.winner = match reader.byte()? {
+                0 => Player::Player0,
+                1 => Player::Player1,
+                2 => Player::Nobody,
+                _ => return Err(Error::CompactMatchInvalid),
+            };
+            game.points = reader.varint()?;
+            games.push(game);
+        }
+        if !reader.bytes.is_empty() {
+            return Err(Error::CompactMatchInvalid);
+        }
+        Ok(CompactMatch { seed, games })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Play games to their end with the dices of the seed, choosing the play by the ply
+    fn games(seed: [u8; 32], amount: usize) -> Result<Vec<GameRecord>, Error> {
+        let mut dices = MatchDices::new(seed);
+        let mut games = Vec::new();
+        for _ in 0..amount {
+            let mut board = Board::new();
+            let mut rolled = dices.opening();
+            let mut player = opener(rolled);
+            let mut game = GameRecord::default();
+            while !board.is_finished() {
+                if !game.turns.is_empty() {
+                    rolled = dices.roll();
+                }
+                let plays = board.generate_plays(player, rolled)?;
+                let (turn, after) = plays[game.turns.len() % plays.len()].clone();
+                game.turns.push(turn);
+                board = after;
+                player = player.other();
+            }
+            game.winner = player.other();
+            game.points = 1;
+            games.push(game);
+        }
+        Ok(games)
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let mut compact = CompactMatch {
+            seed: [3; 32],
+            games: games([3; 32], 3)?,
+        };
+        compact.games[1].doubles.push(Double {
+            turn: 4,
+            player: Player::Player1,
+            taken: true,
+        });
+        let bytes = compact.encode()?;
+        let turns: usize = compact.games.iter().map(|game| game.turns.len()).sum();
+        // a byte for most turns
+        assert!(bytes.len() < 64 + 2 * turns);
+        assert_eq!(CompactMatch::decode(&bytes)?, compact);
+        Ok(())
+    }
+
+    #[test]
+    fn dices_of_another_seed() -> Result<(), Error> {
+        let compact = CompactMatch {
+            seed: [4; 32],
+            games: games([3; 32], 1)?,
+        };
+        assert!(matches!(compact.encode(), Err(Error::DiceInvalid)));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() -> Result<(), Error> {
+        let dices = MatchDices::new([3; 32]).opening();
+        let (turn, _) = Board::new().generate_plays(opener(dices), dices)?[0].clone();
+        let game = GameRecord {
+            turns: vec![turn],
+            ..GameRecord::default()
+        };
+        let compact = CompactMatch {
+            seed: [3; 32],
+            games: vec![game],
+        };
+        let bytes = compact.encode()?;
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let mut version = bytes.clone();
+        version[4] = 2;
+        // the first choice out of the range of the plays
+        let mut choice = bytes.clone();
+        choice[39] = 0x7f;
+        for bytes in [&bytes[..bytes.len() - 1], &trailing, &version, &choice] {
+            assert!(matches!(
+                CompactMatch::decode(bytes),
+                Err(Error::CompactMatchInvalid)
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a collection of labeled positions for regression suites
+///
+/// A corpus is read from a simple text format with one position per line. Each line holds three
+/// fields separated by `;`: an identifier, the position (as GNU Backgammon Position ID or XGID)
+/// and the expected result, which is either an equity or the expected best move in standard
+/// notation. Empty lines and lines starting with `#` are ignored.
+///
+/// ```
+/// use backgammon::{Corpus, Expectation};
+///
+/// let corpus: Corpus = "# opening moves
+/// opening-31; 4HPwATDgc/ABMA; 8/5 6/5
+/// opening-equity; 4HPwATDgc/ABMA; 0.012"
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(corpus.entries.len(), 2);
+/// assert_eq!(corpus.entries[1].expected, Expectation::Equity(0.012));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Corpus {
+    /// The labeled positions in the order they appear in the source
+    pub entries: Vec<CorpusEntry>,
+}
+
+/// Represents one labeled position of a corpus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorpusEntry {
+    /// Identifier of the position
+    pub id: String,
+    /// The position as GNU Backgammon Position ID or XGID, kept verbatim
+    pub position: String,
+    /// The expected result for the position
+    pub expected: Expectation,
+}
+
+/// Represents the expected result for a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expectation {
+    /// The expected best move in standard notation, e.g. "8/5 6/5"
+    Move(String),
+    /// The expected equity of the position
+    Equity(f64),
+}
+
+impl FromStr for Corpus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            entries.push(line.parse()?);
+        }
+        Ok(Corpus { entries })
+    }
+}
+
+impl FromStr for CorpusEntry {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split(';').map(str::trim).collect();
+        match fields[..] {
+            [id, position, expected]
+                if !id.is_empty() && !position.is_empty() && !expected.is_empty() =>
+            {
+                let expected = match expected.parse::<f64>() {
+                    Ok(equity) => Expectation::Equity(equity),
+                    Err(_) => Expectation::Move(expected.to_string()),
+                };
+                Ok(CorpusEntry {
+                    id: id.to_string(),
+                    position: position.to_string(),
+                    expected,
+                })
+            }
+            _ => Err(Error::CorpusInvalid),
+        }
+    }
+}
+
+// implement Display trait
+impl fmt::Display for Corpus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+// implement Display trait
+impl fmt::Display for CorpusEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.expected {
+            Expectation::Move(m) => write!(f, "{}; {}; {}", self.id, self.position, m),
+            Expectation::Equity(e) => write!(f, "{}; {}; {}", self.id, self.position, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_corpus() -> Result<(), Error> {
+        let corpus: Corpus = "
+            # comment
+            a; 4HPwATDgc/ABMA; 24/18 13/8
+
+            b; XGID=-b----E-C---eE---c-e----B-:0:0:1:00:0:0:0:0:10; -0.5
+        "
+        .parse()?;
+        assert_eq!(
+            corpus.entries,
+            vec![
+                CorpusEntry {
+                    id: "a".to_string(),
+                    position: "4HPwATDgc/ABMA".to_string(),
+                    expected: Expectation::Move("24/18 13/8".to_string()),
+                },
+                CorpusEntry {
+                    id: "b".to_string(),
+                    position: "XGID=-b----E-C---eE---c-e----B-:0:0:1:00:0:0:0:0:10".to_string(),
+                    expected: Expectation::Equity(-0.5),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("a; 4HPwATDgc/ABMA".parse::<Corpus>().is_err());
+        assert!("a; ; 0.5".parse::<Corpus>().is_err());
+        assert!("a; b; c; d".parse::<Corpus>().is_err());
+    }
+
+    #[test]
+    fn display_round_trip() -> Result<(), Error> {
+        let corpus: Corpus = "a; 4HPwATDgc/ABMA; 8/5 6/5\nb; 4HPwATDgc/ABMA; 0.25".parse()?;
+        assert_eq!(corpus.to_string().parse::<Corpus>()?, corpus);
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use crate::rules::Player;
+use crate::{Error, Game, GamePhase, PlayerAction};
+use serde::{Deserialize, Serialize};
+
+/// The seconds of a day
+const DAY: u64 = 24 * 60 * 60;
+
+/// Represents an event of a game played by correspondence, see [`CorrespondenceGame::poll`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorrespondenceEvent {
+    /// The player is reminded to act before the deadline
+    Reminder {
+        /// The player to act
+        player: Player,
+        /// The deadline, in seconds since the Unix epoch
+        deadline: u64,
+    },
+    /// The player missed the deadline and lost the game by resignation
+    Expired {
+        /// The player who missed the deadline
+        player: Player,
+        /// The deadline missed, in seconds since the Unix epoch
+        deadline: u64,
+    },
+}
+
+/// Represents a long-lived game played by correspondence, in which each action has to be taken
+/// within a number of days.
+///
+/// The deadline starts anew with every action accepted. Times are passed in by the caller as
+/// seconds since the Unix epoch, so the game can be serialized and stored between actions, and
+/// the service decides when to [`poll`](CorrespondenceGame::poll) it for reminders and
+/// expired deadlines. The opening roll has no deadline, as either player may roll it.
+///
+/// ```
+/// use backgammon::{CorrespondenceEvent, CorrespondenceGame, Game};
+/// use backgammon::rules::{Board, Cube, Player};
+///
+/// let game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+/// let start = 1_700_000_000;
+/// let mut game = CorrespondenceGame::new(game, 3, start);
+/// assert_eq!(game.deadline(), Some(start + 3 * 24 * 60 * 60));
+///
+/// // a reminder a day before the deadline, and the loss once it has passed
+/// assert!(game.poll(start + 24 * 60 * 60).is_empty());
+/// let deadline = start + 3 * 24 * 60 * 60;
+/// let events = game.poll(start + 2 * 24 * 60 * 60);
+/// assert_eq!(events, [CorrespondenceEvent::Reminder { player: Player::Player0, deadline }]);
+/// let events = game.poll(deadline + 1);
+/// assert_eq!(events, [CorrespondenceEvent::Expired { player: Player::Player0, deadline }]);
+/// assert_eq!(game.game.result(), Some((Player::Player1, 1)));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrespondenceGame {
+    /// The game played
+    pub game: Game,
+    /// The days allowed for each action
+    pub days_per_action: u32,
+    /// The hours before the deadline at which the player to act is reminded
+    pub reminders: Vec<u32>,
+    /// The time the last action was accepted
+    since: u64,
+    /// The amount of reminders sent for the current action
+    reminded: usize,
+}
+
+impl CorrespondenceGame {
+    /// Start playing a game by correspondence at the given time, with a reminder one day before
+    /// each deadline
+    pub fn new(game: Game, days_per_action: u32, now: u64) -> Self {
+        CorrespondenceGame {
+            game,
+            days_per_action,
+            reminders: vec![24],
+            since: now,
+            reminded: 0,
+        }
+    }
+
+    /// Set the hours before the deadline at which the player to act is reminded
+    pub fn with_reminders(mut self, mut hours: Vec<u32>) -> Self {
+        hours.sort_unstable_by(|a, b| b.cmp(a));
+        self.reminders = hours;
+        self
+    }
+
+    /// Get the player who has to act next, if the game has a deadline
+    fn to_act(&self) -> Option<Player> {
+        match self.game.phase() {
+            GamePhase::OpeningRoll | GamePhase::Finished => None,
+            GamePhase::DoubleOffered => Some(self.game.who_plays.other()),
+            _ => Some(self.game.who_plays),
+        }
+    }
+
+    /// Get the deadline for the next action, in seconds since the Unix epoch, or `None` if no
+    /// player has to act
+    pub fn deadline(&self) -> Option<u64> {
+        self.to_act().map(|_| {
+            self.since
+                .saturating_add(u64::from(self.days_per_action).saturating_mul(DAY))
+        })
+    }
+
+    /// Apply an action of a player at the given time, see [`Game::submit_at`]. Actions after
+    /// the deadline of the player are refused.
+    pub fn submit(&mut self, player: Player, action: PlayerAction, now: u64) -> Result<(), Error> {
+        if self.to_act() == Some(player) && self.deadline().is_some_and(|deadline| now > deadline) {
+            return Err(Error::DeadlineExpired);
+        }
+        self.game
+            .submit_at(player, action, now.saturating_mul(1000))?;
+        self.since = now;
+        self.reminded = 0;
+        Ok(())
+    }
+
+    /// Get the reminders due and the deadline expired at the given time. Each reminder is only
+    /// returned once, and a player who missed the deadline resigns the game.
+    pub fn poll(&mut self, now: u64) -> Vec<CorrespondenceEvent> {
+        let (Some(player), Some(deadline)) = (self.to_act(), self.deadline()) else {
+            return Vec::new();
+        };
+        if now > deadline {
+            self.reminded = self.reminders.len();
+            if self
+                .game
+                .submit_at(player, PlayerAction::Resign, now.saturating_mul(1000))
+                .is_err()
+            {
+                return Vec::new();
+            }
+            return vec![CorrespondenceEvent::Expired { player, deadline }];
+        }
+        let mut events = Vec::new();
+        while let Some(&hours) = self.reminders.get(self.reminded) {
+            if now.saturating_add(u64::from(hours).saturating_mul(60 * 60)) < deadline {
+                break;
+            }
+            self.reminded += 1;
+            // several reminders due at once are sent as one
+            if events.is_empty() {
+                events.push(CorrespondenceEvent::Reminder { player, deadline });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube};
+
+    const START: u64 = 1_000_000;
+
+    fn game() -> Result<CorrespondenceGame, Error> {
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        Ok(CorrespondenceGame::new(game, 2, START).with_reminders(vec![1, 24]))
+    }
+
+    #[test]
+    fn deadline_restarts() -> Result<(), Error> {
+        let mut game = game()?;
+        game.submit(Player::Player0, PlayerAction::OfferDouble, START + DAY)?;
+        assert_eq!(game.deadline(), Some(START + 3 * DAY));
+        assert!(matches!(
+            game.submit(Player::Player1, PlayerAction::Take, START + 4 * DAY),
+            Err(Error::DeadlineExpired)
+        ));
+        // the opponent may still act, e.g. after the deadline was missed
+        assert!(matches!(
+            game.submit(Player::Player0, PlayerAction::Roll, START + 4 * DAY),
+            Err(Error::CubeReceived)
+        ));
+        game.submit(Player::Player1, PlayerAction::Take, START + 3 * DAY)?;
+        assert_eq!(game.game.cube.value(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn reminders() -> Result<(), Error> {
+        let mut game = game()?;
+        let deadline = START + 2 * DAY;
+        let reminder = CorrespondenceEvent::Reminder {
+            player: Player::Player0,
+            deadline,
+        };
+        assert!(game.poll(START).is_empty());
+        assert_eq!(game.poll(deadline - DAY), [reminder]);
+        assert!(game.poll(deadline - DAY + 1).is_empty());
+        assert_eq!(game.poll(deadline - 60), [reminder]);
+        assert!(game.poll(deadline).is_empty());
+
+        // the reminders start anew with the next action
+        game.submit(Player::Player0, PlayerAction::OfferDouble, deadline)?;
+        let events = game.poll(deadline + 2 * DAY);
+        assert_eq!(
+            events,
+            [CorrespondenceEvent::Reminder {
+                player: Player::Player1,
+                deadline: deadline + 2 * DAY
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn far_deadline() -> Result<(), Error> {
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        let mut game =
+            CorrespondenceGame::new(game, u32::MAX, u64::MAX - 1).with_reminders(vec![u32::MAX]);
+        assert_eq!(game.deadline(), Some(u64::MAX));
+        assert_eq!(
+            game.poll(u64::MAX),
+            [CorrespondenceEvent::Reminder {
+                player: Player::Player0,
+                deadline: u64::MAX
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize() -> Result<(), Box<dyn std::error::Error>> {
+        let mut game = game()?;
+        game.submit(Player::Player0, PlayerAction::OfferDouble, START + DAY)?;
+        let _ = game.poll(START + 2 * DAY + 1);
+        let json = serde_json::to_string(&game)?;
+        let mut stored: CorrespondenceGame = serde_json::from_str(&json)?;
+        assert_eq!(stored.deadline(), game.deadline());
+        // the reminder already sent is not sent again
+        assert!(stored.poll(START + 2 * DAY + 2).is_empty());
+        stored.submit(Player::Player1, PlayerAction::Take, START + 2 * DAY + 3)?;
+        Ok(())
+    }
+
+    #[test]
+    fn no_deadline_before_the_opening_roll() {
+        let mut game = CorrespondenceGame::new(Game::new(), 1, START);
+        assert_eq!(game.deadline(), None);
+        assert!(game.poll(START + 10 * DAY).is_empty());
+        assert!(game.game.result().is_none());
+    }
+}
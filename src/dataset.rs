@@ -0,0 +1,292 @@
+use crate::rules::{Board, MoveChecker, Player, Turn};
+use crate::Error;
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of every dataset stream
+const MAGIC: [u8; 4] = *b"BGTD";
+
+/// Version of the dataset format
+const VERSION: u8 = 1;
+
+/// Byte marking an unused move slot
+const NO_MOVE: u8 = 0xff;
+
+/// Size of a record in bytes
+const RECORD_SIZE: usize = 37;
+
+/// Represents one decision in a game, used as a sample for training
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingRecord {
+    /// The position before the move
+    pub board: Board,
+    /// The turn played from the position
+    pub turn: Turn,
+    /// The points won (or lost, if negative) by the player on turn at the end of the game
+    pub outcome: i8,
+}
+
+/// Writes training records in the binary dataset format.
+///
+/// A dataset starts with the magic bytes `BGTD` and a version byte, currently 1, followed by any
+/// amount of records of 37 bytes each:
+///
+/// | Bytes | Content |
+/// |-------|---------|
+/// | 0-25  | the position, packed with four bits per field, bar and off, 13 bytes for player 0 followed by 13 bytes for player 1 |
+/// | 26    | the player on turn, 0 or 1 |
+/// | 27    | the dices, the first one in the higher four bits |
+/// | 28-35 | up to four moves of two bytes, origin and destination; fields are 0 to 23 from the perspective of the player on turn, the bar is 24, the off 25, unused moves are `0xff 0xff` |
+/// | 36    | the outcome as signed byte |
+///
+/// ```
+/// use backgammon::{DatasetReader, DatasetWriter, TrainingRecord};
+/// use backgammon::rules::{Board, Player};
+///
+/// let board = Board::new();
+/// let turn = board.legal_turns(Player::Player0, (6, 1)).unwrap()[0].clone();
+/// let record = TrainingRecord {
+///     board,
+///     turn,
+///     outcome: 2,
+/// };
+///
+/// let mut writer = DatasetWriter::new(Vec::new()).unwrap();
+/// writer.write(&record).unwrap();
+/// let bytes = writer.into_inner();
+///
+/// let records: Vec<TrainingRecord> = DatasetReader::new(&bytes[..])
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(records, vec![record]);
+/// ```
+#[derive(Debug)]
+pub struct DatasetWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> DatasetWriter<W> {
+    /// Start a new dataset, writing the header
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&MAGIC)?;
+        inner.write_all(&[VERSION])?;
+        Ok(DatasetWriter { inner })
+    }
+
+    /// Append a record
+    pub fn write(&mut self, record: &TrainingRecord) -> io::Result<()> {
+        let player = match record.turn.player() {
+            Player::Player0 => 0,
+            Player::Player1 => 1,
+            Player::Nobody => return Err(invalid(Error::PlayerInvalid)),
+        };
+        let dices = record.turn.dices();
+
+        let mut bytes = [NO_MOVE; RECORD_SIZE];
+        bytes[..26].copy_from_slice(&record.board.to_packed());
+        bytes[26] = player;
+        bytes[27] = (dices.0 << 4) | dices.1;
+        for (i, m) in record.turn.moves().iter().enumerate() {
+            bytes[28 + 2 * i..30 + 2 * i].copy_from_slice(&m.checker().to_bytes());
+        }
+        bytes[36] = record.outcome as u8;
+        self.inner.write_all(&bytes)
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Get the underlying writer back
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads training records written by [`DatasetWriter`], one record per iteration.
+///
+/// Records are refused as invalid data if their board breaks the invariants checked by
+/// [`Board::validate`], or their moves are no legal play of the board.
+#[derive(Debug)]
+pub struct DatasetReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> DatasetReader<R> {
+    /// Open a dataset, checking its header
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut header = [0; 5];
+        inner.read_exact(&mut header)?;
+        if header[..4] != MAGIC || header[4] != VERSION {
+            return Err(invalid(Error::DatasetInvalid));
+        }
+        Ok(DatasetReader { inner })
+    }
+
+    /// Read the next record, if any
+    fn read_record(&mut self) -> io::Result<Option<TrainingRecord>> {
+        let mut bytes = [0; RECORD_SIZE];
+        let mut read = 0;
+        while read < RECORD_SIZE {
+            match self.inner.read(&mut bytes[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let player = match bytes[26] {
+            0 => Player::Player0,
+            1 => Player::Player1,
+            _ => return Err(invalid(Error::PlayerInvalid)),
+        };
+        let dices = (bytes[27] >> 4, bytes[27] & 0x0f);
+        let mut moves = Vec::new();
+        for pair in bytes[28..36].chunks(2) {
+            if pair == [NO_MOVE, NO_MOVE] {
+                break;
+            }
+            moves.push(
+                MoveChecker::from_bytes(player, [pair[0], pair[1]])
+                    .ok_or_else(|| invalid(Error::MoveInvalid))?,
+            );
+        }
+
+        let mut packed = [0; 26];
+        packed.copy_from_slice(&bytes[..26]);
+        let board = Board::from_packed(&packed);
+        board.validate().map_err(invalid)?;
+        let turn = Turn::new(&board, player, dices, moves).map_err(invalid)?;
+        Ok(Some(TrainingRecord {
+            board,
+            turn,
+            outcome: bytes[36] as i8,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for DatasetReader<R> {
+    type Item = io::Result<TrainingRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Wrap an error as invalid data
+fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InvariantError;
+
+    fn records() -> Result<Vec<TrainingRecord>, Error> {
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set_bar(Player::Player1, 1)?;
+        board.set(Player::Player0, 5, -2)?;
+        board.set_off(Player::Player0, 2)?;
+        let (entering, _) = board.generate_plays(Player::Player1, (5, 2))?[0].clone();
+        let (doubles, _) = Board::new().generate_plays(Player::Player0, (2, 2))?[0].clone();
+
+        // player 0 on the bar against a closed board can not move
+        let mut player0 = Board::new().raw(Player::Player0)?;
+        player0.0[23] = 0;
+        player0.1 = 2;
+        let mut player1 = ([2; 24], 0, 0);
+        player1.0[6..].fill(0);
+        player1.0[12] = 3;
+        let closed = Board::from_raw(player0, player1);
+        let empty = Turn::new(&closed, Player::Player0, (6, 6), vec![])?;
+        Ok(vec![
+            TrainingRecord {
+                board,
+                turn: entering,
+                outcome: -3,
+            },
+            TrainingRecord {
+                board: Board::new(),
+                turn: doubles,
+                outcome: 1,
+            },
+            TrainingRecord {
+                board: closed,
+                turn: empty,
+                outcome: 0,
+            },
+        ])
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let records = records()?;
+        let mut writer = DatasetWriter::new(Vec::new())?;
+        for record in &records {
+            writer.write(record)?;
+        }
+        let bytes = writer.into_inner();
+        assert_eq!(bytes.len(), 5 + 3 * RECORD_SIZE);
+
+        let read = DatasetReader::new(&bytes[..])?.collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(read, records);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(DatasetReader::new(&b"BGTX\x01"[..]).is_err());
+        assert!(DatasetReader::new(&b"BGTD\x02"[..]).is_err());
+        assert!(DatasetReader::new(&b"BG"[..]).is_err());
+    }
+
+    #[test]
+    fn truncated_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = DatasetWriter::new(Vec::new())?;
+        writer.write(&records()?[0])?;
+        let bytes = writer.into_inner();
+        let mut reader = DatasetReader::new(&bytes[..bytes.len() - 1])?;
+        assert!(reader.next().unwrap().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = DatasetWriter::new(Vec::new())?;
+        writer.write(&records()?[1])?;
+        let mut bytes = writer.into_inner();
+
+        // the moves do not match the dices anymore
+        bytes[5 + 27] = 0x11;
+        assert!(DatasetReader::new(&bytes[..])?.next().unwrap().is_err());
+        bytes[5 + 27] = 0x77;
+        assert!(DatasetReader::new(&bytes[..])?.next().unwrap().is_err());
+        bytes[5 + 26] = 2;
+        assert!(DatasetReader::new(&bytes[..])?.next().unwrap().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_board() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = DatasetWriter::new(Vec::new())?;
+        writer.write(&records()?[1])?;
+        let mut bytes = writer.into_inner();
+
+        // a 16th checker of player 0 on the first field
+        bytes[5] = 0x10;
+        let error = DatasetReader::new(&bytes[..])?.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            error
+                .get_ref()
+                .and_then(|e| e.downcast_ref::<InvariantError>()),
+            Some(InvariantError::CheckerCount { count: 16, .. })
+        ));
+        Ok(())
+    }
+}
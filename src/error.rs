@@ -1,4 +1,5 @@
 /// This module contains the error definition for the Backgammon game.
+use crate::rules::{Player, Variant};
 use std::fmt;
 
 /// Holds all possible errors that can occur during a Backgammon game.
@@ -32,6 +33,46 @@ pub enum Error {
     RollFirst,
     /// Dice Invalid
     DiceInvalid,
+    /// Dice already consumed
+    DiceAlreadyConsumed,
+    /// Invalid position corpus
+    CorpusInvalid,
+    /// Invalid training dataset
+    DatasetInvalid,
+    /// Invalid match length
+    MatchLengthInvalid,
+    /// Too many pending actions
+    ActionQueueFull,
+    /// Invalid position
+    PositionInvalid,
+    /// Invalid match score
+    ScoreInvalid,
+    /// Invalid match ID
+    MatchIdInvalid,
+    /// Invalid move notation
+    NotationInvalid,
+    /// Invalid save name
+    SaveNameInvalid,
+    /// Invalid saved game
+    SaveInvalid,
+    /// Invalid FIBS board state
+    BoardStateInvalid,
+    /// Invalid SGF game record
+    SgfInvalid,
+    /// Deadline expired
+    DeadlineExpired,
+    /// Engine self-consistency check failed
+    AuditFailed,
+    /// Invalid compact match
+    CompactMatchInvalid,
+    /// Choose the doubles of the bonus first
+    ChooseDoublesFirst,
+    /// Game ID not available
+    GameIdUnavailable,
+    /// Takeback not possible
+    TakebackInvalid,
+    /// Actions sent too fast
+    RateLimited,
 }
 
 // implement Error trait
@@ -59,7 +100,114 @@ impl fmt::Display for Error {
             Error::MoveFirst => write!(f, "Move first"),
             Error::RollFirst => write!(f, "Roll first"),
             Error::DiceInvalid => write!(f, "Invalid dice"),
+            Error::DiceAlreadyConsumed => write!(f, "Dice already consumed"),
             Error::MoveInvalidBar => write!(f, "Invalid move, checker on bar"),
+            Error::CorpusInvalid => write!(f, "Invalid position corpus"),
+            Error::DatasetInvalid => write!(f, "Invalid training dataset"),
+            Error::MatchLengthInvalid => write!(f, "Invalid match length"),
+            Error::ActionQueueFull => write!(f, "Too many pending actions"),
+            Error::PositionInvalid => write!(f, "Invalid position"),
+            Error::ScoreInvalid => write!(f, "Invalid match score"),
+            Error::MatchIdInvalid => write!(f, "Invalid match ID"),
+            Error::NotationInvalid => write!(f, "Invalid move notation"),
+            Error::SaveNameInvalid => write!(f, "Invalid save name"),
+            Error::SaveInvalid => write!(f, "Invalid saved game"),
+            Error::BoardStateInvalid => write!(f, "Invalid FIBS board state"),
+            Error::SgfInvalid => write!(f, "Invalid SGF game record"),
+            Error::DeadlineExpired => write!(f, "Deadline expired"),
+            Error::AuditFailed => write!(f, "Engine self-consistency check failed"),
+            Error::CompactMatchInvalid => write!(f, "Invalid compact match"),
+            Error::ChooseDoublesFirst => write!(f, "Choose doubles first"),
+            Error::GameIdUnavailable => write!(f, "Game ID not available"),
+            Error::TakebackInvalid => write!(f, "Takeback not possible"),
+            Error::RateLimited => write!(f, "Actions sent too fast"),
+        }
+    }
+}
+
+/// Holds all possible violations of the invariants of a game state, e.g. found when
+/// deserializing untrusted input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// A player does not have the checkers of a variant on the board, the bar and off the board,
+    /// or not as many as the opponent
+    CheckerCount {
+        /// The player
+        player: Player,
+        /// The amount of checkers found
+        count: u32,
+        /// The amount of checkers expected
+        expected: u32,
+    },
+    /// Both players have checkers on the same field, numbered from player 0's perspective
+    FieldShared {
+        /// The field
+        field: usize,
+    },
+    /// The cube value exceeds the technical limit of 2^63
+    CubeValue {
+        /// The exponent of the cube value
+        exponent: u8,
+    },
+    /// A dice value is not between 1 and 6, or only one dice has been rolled
+    DiceValue {
+        /// The dice values
+        values: (u8, u8),
+    },
+    /// A message uses a schema version this crate does not understand
+    SchemaVersion {
+        /// The schema version of the message
+        version: u32,
+    },
+    /// A board given as the start of a game is not the starting position of its variant
+    StartPosition {
+        /// The variant
+        variant: Variant,
+    },
+    /// An action of the history of a game does not start from the state the action before left,
+    /// or the game is not in the state the last action left
+    HistoryChain {
+        /// The index of the action, the amount of actions for the state of the game
+        action: usize,
+    },
+}
+
+// implement Error trait
+impl std::error::Error for InvariantError {}
+
+// implement Display trait
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantError::CheckerCount {
+                player,
+                count,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "{} has {} checkers instead of {}",
+                    player, count, expected
+                )
+            }
+            InvariantError::FieldShared { field } => {
+                write!(f, "Both players have checkers on field {}", field)
+            }
+            InvariantError::CubeValue { exponent } => {
+                write!(f, "Cube value 2^{} exceeds the limit of 2^63", exponent)
+            }
+            InvariantError::DiceValue { values } => {
+                write!(f, "Invalid dice values {:?}", values)
+            }
+            InvariantError::SchemaVersion { version } => {
+                write!(f, "Unsupported schema version {}", version)
+            }
+            InvariantError::StartPosition { variant } => {
+                write!(f, "Not the starting position of {:?}", variant)
+            }
+            InvariantError::HistoryChain { action } => {
+                write!(f, "History broken at action {}", action)
+            }
         }
     }
 }
@@ -92,9 +240,109 @@ mod tests {
         assert_eq!(format!("{}", Error::MoveFirst), "Move first");
         assert_eq!(format!("{}", Error::RollFirst), "Roll first");
         assert_eq!(format!("{}", Error::DiceInvalid), "Invalid dice");
+        assert_eq!(
+            format!("{}", Error::DiceAlreadyConsumed),
+            "Dice already consumed"
+        );
         assert_eq!(
             format!("{}", Error::MoveInvalidBar),
             "Invalid move, checker on bar"
         );
+        assert_eq!(
+            format!("{}", Error::CorpusInvalid),
+            "Invalid position corpus"
+        );
+        assert_eq!(
+            format!("{}", Error::DatasetInvalid),
+            "Invalid training dataset"
+        );
+        assert_eq!(
+            format!("{}", Error::MatchLengthInvalid),
+            "Invalid match length"
+        );
+        assert_eq!(
+            format!("{}", Error::ActionQueueFull),
+            "Too many pending actions"
+        );
+        assert_eq!(format!("{}", Error::PositionInvalid), "Invalid position");
+        assert_eq!(format!("{}", Error::ScoreInvalid), "Invalid match score");
+        assert_eq!(format!("{}", Error::MatchIdInvalid), "Invalid match ID");
+        assert_eq!(
+            format!("{}", Error::NotationInvalid),
+            "Invalid move notation"
+        );
+        assert_eq!(format!("{}", Error::SaveNameInvalid), "Invalid save name");
+        assert_eq!(format!("{}", Error::SaveInvalid), "Invalid saved game");
+        assert_eq!(
+            format!("{}", Error::BoardStateInvalid),
+            "Invalid FIBS board state"
+        );
+        assert_eq!(format!("{}", Error::SgfInvalid), "Invalid SGF game record");
+        assert_eq!(format!("{}", Error::DeadlineExpired), "Deadline expired");
+        assert_eq!(
+            format!("{}", Error::AuditFailed),
+            "Engine self-consistency check failed"
+        );
+        assert_eq!(
+            format!("{}", Error::CompactMatchInvalid),
+            "Invalid compact match"
+        );
+        assert_eq!(
+            format!("{}", Error::ChooseDoublesFirst),
+            "Choose doubles first"
+        );
+        assert_eq!(
+            format!("{}", Error::GameIdUnavailable),
+            "Game ID not available"
+        );
+        assert_eq!(
+            format!("{}", Error::TakebackInvalid),
+            "Takeback not possible"
+        );
+        assert_eq!(format!("{}", Error::RateLimited), "Actions sent too fast");
+    }
+
+    #[test]
+    fn test_invariant_error_display() {
+        assert_eq!(
+            format!(
+                "{}",
+                InvariantError::CheckerCount {
+                    player: Player::Player1,
+                    count: 40,
+                    expected: 15
+                }
+            ),
+            "Player 1 has 40 checkers instead of 15"
+        );
+        assert_eq!(
+            format!("{}", InvariantError::FieldShared { field: 3 }),
+            "Both players have checkers on field 3"
+        );
+        assert_eq!(
+            format!("{}", InvariantError::CubeValue { exponent: 70 }),
+            "Cube value 2^70 exceeds the limit of 2^63"
+        );
+        assert_eq!(
+            format!("{}", InvariantError::DiceValue { values: (0, 7) }),
+            "Invalid dice values (0, 7)"
+        );
+        assert_eq!(
+            format!("{}", InvariantError::SchemaVersion { version: 2 }),
+            "Unsupported schema version 2"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                InvariantError::StartPosition {
+                    variant: Variant::Nackgammon
+                }
+            ),
+            "Not the starting position of Nackgammon"
+        );
+        assert_eq!(
+            format!("{}", InvariantError::HistoryChain { action: 4 }),
+            "History broken at action 4"
+        );
     }
 }
@@ -1,16 +1,40 @@
 //! # Play a Backgammon Game
-use crate::rules::{Cube, Players, Player};
 use crate::rules::{Board, Move};
+use crate::rules::{Cube, Player, Players};
 use crate::rules::{Dices, Roll};
-use crate::rules::{GameRules, Rules};
-use crate::Error;
+use crate::rules::{GameRules, Rules, Turn, Variant, WinKind};
+use crate::{Error, InvariantError, PlayerAction};
 use rand::seq::SliceRandom; // Import SliceRandom to get the choose method on slices
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// Implements the FIBS board state format
+mod fibs;
+pub use fibs::FibsBoard;
+/// Implements the self-consistency checks of applied actions
+mod audit;
+/// Implements the rules of speed play
+mod blitz;
+pub use blitz::Blitz;
+use blitz::Clock;
+/// Implements the undo and redo of actions
+mod history;
+use history::State;
+pub use history::{CubeAction, CubeEvent, History, HistoryEvent, ReplayStep, TimingStats};
+/// Implements the GNU Backgammon match ID
+mod match_id;
+/// Implements the delayed and hidden views of spectators
+mod spectator;
+pub use spectator::{BroadcastDelay, SpectatorView, Spectators};
+
 /// Represents a Backgammon game
+///
+/// Games serialize together with their [`History`], so undo, replay and
+/// [`Game::to_record`] keep working for stored games. Deserializing checks that the board has
+/// the checkers of the variant and that the history leads to the state of the game.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(try_from = "UncheckedGame")]
 pub struct Game {
     /// rules of the game
     pub rules: Rules,
@@ -30,6 +54,123 @@ pub struct Game {
     roll_first: bool,
     /// if cube was offered, player has to accept first and only then can move on
     cube_received: bool,
+    /// winner and points won, once the game has ended
+    result: Option<(Player, u64)>,
+    /// automatic doubles of the cube by ties of the opening roll
+    #[serde(default)]
+    automatic_doubles: u8,
+    /// the variant played
+    #[serde(default)]
+    variant: Variant,
+    /// the bonus of a roll in progress, see [`Variant::is_bonus_roll`]
+    #[serde(default)]
+    bonus: Bonus,
+    /// whether every action is checked against the legal moves generated, see [`Game::with_audit`]
+    #[serde(default)]
+    audit: bool,
+    /// the player asking to take back their last turn, see [`Game::takeback_requested`]
+    #[serde(default)]
+    takeback: Option<Player>,
+    /// what spectators see of the game, see [`Game::with_spectators`]
+    #[serde(default)]
+    spectators: Spectators,
+    /// the clocks of speed play, see [`Game::with_blitz`]
+    #[serde(default)]
+    clock: Option<Clock>,
+    /// actions applied, to be undone and redone
+    #[serde(default)]
+    history: History,
+}
+
+/// Represents a deserialized game before its board and history have been validated
+#[derive(Deserialize)]
+struct UncheckedGame {
+    rules: Rules,
+    dices: Dices,
+    who_plays: Player,
+    board: Board,
+    cube: Cube,
+    crawford: bool,
+    since_crawford: u8,
+    roll_first: bool,
+    cube_received: bool,
+    result: Option<(Player, u64)>,
+    #[serde(default)]
+    automatic_doubles: u8,
+    #[serde(default)]
+    variant: Variant,
+    #[serde(default)]
+    bonus: Bonus,
+    #[serde(default)]
+    audit: bool,
+    #[serde(default)]
+    takeback: Option<Player>,
+    #[serde(default)]
+    spectators: Spectators,
+    #[serde(default)]
+    clock: Option<Clock>,
+    #[serde(default)]
+    history: History,
+}
+
+impl TryFrom<UncheckedGame> for Game {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedGame) -> Result<Self, Self::Error> {
+        unchecked.board.validate_variant(unchecked.variant)?;
+        let game = Game {
+            rules: unchecked.rules,
+            dices: unchecked.dices,
+            who_plays: unchecked.who_plays,
+            board: unchecked.board,
+            cube: unchecked.cube,
+            crawford: unchecked.crawford,
+            since_crawford: unchecked.since_crawford,
+            roll_first: unchecked.roll_first,
+            cube_received: unchecked.cube_received,
+            result: unchecked.result,
+            automatic_doubles: unchecked.automatic_doubles,
+            variant: unchecked.variant,
+            bonus: unchecked.bonus,
+            audit: unchecked.audit,
+            takeback: unchecked.takeback,
+            spectators: unchecked.spectators,
+            clock: unchecked.clock,
+            history: unchecked.history,
+        };
+        game.check_history()?;
+        Ok(game)
+    }
+}
+
+/// Represents the progress of the bonus of a roll, e.g. a roll of 1-2 in Acey-deucey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+enum Bonus {
+    /// No bonus is in progress
+    #[default]
+    None,
+    /// The dices of the bonus roll have been played, the player has to choose doubles
+    Choosing,
+    /// The player plays the doubles chosen, and rolls again afterwards
+    Doubles,
+}
+
+/// Represents the phase of a game, determining which actions are possible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamePhase {
+    /// Nobody is on turn yet, the opening roll decides who starts
+    OpeningRoll,
+    /// The player on turn may double or has to roll
+    Rolling,
+    /// The player on turn has to move the dices rolled
+    Moving,
+    /// The player on turn has played a bonus roll and has to choose the doubles to play, see
+    /// [`Game::choose_doubles`]
+    ChoosingDoubles,
+    /// The player on turn offered the cube, the opponent has to take or drop it
+    DoubleOffered,
+    /// The game has ended
+    Finished,
 }
 
 // implement Display trait
@@ -55,13 +196,39 @@ pub struct GameWithPlayerDices {
     pub board: Board,
     /// last dice pair rolled
     pub players: Players,
+    /// The cube, which may have been doubled automatically in the opening roll
+    pub cube: Cube,
 }
 
 impl GameWithPlayerDices {
     /// Create a new game with player dices
     pub fn new(first_seed: [u8; 32], second_seed: [u8; 32]) -> Self {
         let players = Players::new(first_seed, second_seed);
-        Self { board: Board::new(), players}
+        Self {
+            board: Board::new(),
+            players,
+            cube: Cube::default(),
+        }
+    }
+
+    /// Create a new game with player dices, doubling the cube automatically on each tie of the
+    /// opening roll up to `limit` times, see [`Players::with_automatic_doubles`]
+    pub fn with_automatic_doubles(
+        first_seed: [u8; 32],
+        second_seed: [u8; 32],
+        limit: u8,
+    ) -> Result<Self, Error> {
+        let (players, doubles) = Players::with_automatic_doubles(first_seed, second_seed, limit);
+        let mut cube = Cube::default();
+        cube.set(
+            1u64.checked_shl(doubles.into())
+                .ok_or(Error::CubeValueInvalid)?,
+        )?;
+        Ok(Self {
+            board: Board::new(),
+            players,
+            cube,
+        })
     }
 
     /// make a move
@@ -69,45 +236,68 @@ impl GameWithPlayerDices {
         if let Some(ref mut dice) = self.players.current.dices {
             match dice.consumed {
                 (false, _, _, _) => {
-                    let moves = self.board.generate_a_possible_moves(self.players.current.player, dice.values.0 as usize)?;
-                    let r#move = moves.choose(&mut self.players.current.rng).ok_or(Error::MoveInvalid)?;
-                    self.board.apply_move(r#move)?;
+                    let moves = self.board.generate_a_possible_moves(
+                        self.players.current.player,
+                        dice.values.0 as usize,
+                    )?;
+                    let r#move = moves
+                        .choose(&mut self.players.current.rng)
+                        .ok_or(Error::MoveInvalid)?;
+                    let _ = self.board.apply_move(r#move)?;
                     dice.consumed.0 = true;
                     Ok(())
                 }
                 (true, false, true, true) => {
-                    let moves = self.board.generate_a_possible_moves(self.players.current.player, dice.values.1 as usize)?;
-                    let r#move = moves.choose(&mut self.players.current.rng).ok_or(Error::MoveInvalid)?;
-                    self.board.apply_move(r#move)?;
+                    let moves = self.board.generate_a_possible_moves(
+                        self.players.current.player,
+                        dice.values.1 as usize,
+                    )?;
+                    let r#move = moves
+                        .choose(&mut self.players.current.rng)
+                        .ok_or(Error::MoveInvalid)?;
+                    let _ = self.board.apply_move(r#move)?;
                     dice.consumed.1 = true;
                     self.players.switch();
                     Ok(())
                 }
                 (true, false, _, _) => {
-                    let moves = self.board.generate_a_possible_moves(self.players.current.player, dice.values.1 as usize)?;
-                    let r#move = moves.choose(&mut self.players.current.rng).ok_or(Error::MoveInvalid)?;
-                    self.board.apply_move(r#move)?;
+                    let moves = self.board.generate_a_possible_moves(
+                        self.players.current.player,
+                        dice.values.1 as usize,
+                    )?;
+                    let r#move = moves
+                        .choose(&mut self.players.current.rng)
+                        .ok_or(Error::MoveInvalid)?;
+                    let _ = self.board.apply_move(r#move)?;
                     dice.consumed.1 = true;
                     Ok(())
                 }
                 (true, true, false, _) => {
-                    let moves = self.board.generate_a_possible_moves(self.players.current.player, dice.values.0 as usize)?;
-                    let r#move = moves.choose(&mut self.players.current.rng).ok_or(Error::MoveInvalid)?;
-                    self.board.apply_move(r#move)?;
+                    let moves = self.board.generate_a_possible_moves(
+                        self.players.current.player,
+                        dice.values.0 as usize,
+                    )?;
+                    let r#move = moves
+                        .choose(&mut self.players.current.rng)
+                        .ok_or(Error::MoveInvalid)?;
+                    let _ = self.board.apply_move(r#move)?;
                     dice.consumed.2 = true;
                     Ok(())
                 }
                 (true, true, true, false) => {
-                    let moves = self.board.generate_a_possible_moves(self.players.current.player, dice.values.0 as usize)?;
-                    let r#move = moves.choose(&mut self.players.current.rng).ok_or(Error::MoveInvalid)?;
-                    self.board.apply_move(r#move)?;
+                    let moves = self.board.generate_a_possible_moves(
+                        self.players.current.player,
+                        dice.values.0 as usize,
+                    )?;
+                    let r#move = moves
+                        .choose(&mut self.players.current.rng)
+                        .ok_or(Error::MoveInvalid)?;
+                    let _ = self.board.apply_move(r#move)?;
                     dice.consumed.3 = true;
                     self.players.switch();
                     Ok(())
                 }
-                _ => {
-                    Err(Error::RollFirst)
-                }
+                _ => Err(Error::RollFirst),
             }
         } else {
             Err(Error::RollFirst)
@@ -120,93 +310,638 @@ impl Game {
     pub fn new() -> Self {
         Game::default()
     }
+
+    /// Create a new game of a variant, starting from the position of the variant
+    ///
+    /// ```
+    /// use backgammon::Game;
+    /// use backgammon::rules::Variant;
+    ///
+    /// let game = Game::new_variant(Variant::Nackgammon);
+    /// assert_eq!(game.board, Variant::Nackgammon.board());
+    /// assert_eq!(game.variant(), Variant::Nackgammon);
+    /// ```
+    pub fn new_variant(variant: Variant) -> Self {
+        Game {
+            board: variant.board(),
+            variant,
+            ..Game::default()
+        }
+    }
+
+    /// Get the variant played
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Resume a game in progress from an external source, with the given player about to roll.
+    ///
+    /// No doubling is allowed if `crawford` is set, hence the cube has to be centered.
+    ///
+    /// ```
+    /// use backgammon::Game;
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut cube = Cube::default();
+    /// cube.set(2).unwrap();
+    /// cube.set_owner(Player::Player1);
+    /// let game = Game::resume(Board::new(), cube, Player::Player0, false).unwrap();
+    /// assert_eq!(game.cube.value(), 2);
+    /// ```
+    pub fn resume(
+        board: Board,
+        cube: Cube,
+        who_plays: Player,
+        crawford: bool,
+    ) -> Result<Self, Error> {
+        if who_plays == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        if board.validate().is_err() {
+            return Err(Error::PositionInvalid);
+        }
+        if board.is_finished() {
+            return Err(Error::GameEnded);
+        }
+        if crawford && (cube.value() != 1 || cube.owner() != Player::Nobody) {
+            return Err(Error::CubeValueInvalid);
+        }
+        Ok(Game {
+            board,
+            cube,
+            who_plays,
+            crawford,
+            roll_first: true,
+            ..Game::default()
+        })
+    }
+
+    /// Create the next game of a match, which may be the Crawford game. The given player makes
+    /// the first roll of the game instead of the opening roll, unless it is `Player::Nobody`.
+    pub(crate) fn for_match(rules: Rules, crawford: bool, first: Player) -> Self {
+        Game {
+            rules,
+            crawford,
+            who_plays: first,
+            roll_first: first != Player::Nobody,
+            ..Game::default()
+        }
+    }
+
+    /// Check if this is the Crawford game of a match, in which doubling is not allowed
+    pub fn is_crawford(&self) -> bool {
+        self.crawford
+    }
+
+    /// Get the player asking the opponent to take back their last turn, if any. The request
+    /// lapses with the next action of either player.
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit(Player::Player0, PlayerAction::Roll).unwrap();
+    /// let dices = game.dices.values;
+    /// let turn = game.board.legal_turns(Player::Player0, dices).unwrap().remove(0);
+    /// game.submit(Player::Player0, PlayerAction::PlayTurn(turn)).unwrap();
+    ///
+    /// game.submit(Player::Player0, PlayerAction::RequestTakeback).unwrap();
+    /// assert_eq!(game.takeback_requested(), Some(Player::Player0));
+    /// game.submit(Player::Player1, PlayerAction::AcceptTakeback).unwrap();
+    /// assert_eq!(game.board, Board::new());
+    /// assert_eq!(game.phase(), GamePhase::Moving);
+    /// assert_eq!(game.dices.values, dices);
+    /// ```
+    pub fn takeback_requested(&self) -> Option<Player> {
+        self.takeback
+    }
+
+    /// Get the current phase of the game
+    pub fn phase(&self) -> GamePhase {
+        if self.result.is_some() {
+            GamePhase::Finished
+        } else if self.who_plays == Player::Nobody {
+            GamePhase::OpeningRoll
+        } else if self.cube_received {
+            GamePhase::DoubleOffered
+        } else if self.bonus == Bonus::Choosing {
+            GamePhase::ChoosingDoubles
+        } else if self.roll_first {
+            GamePhase::Rolling
+        } else {
+            GamePhase::Moving
+        }
+    }
+
+    /// Play a complete turn with the dices rolled, e.g. one of [`Board::legal_turns`].
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    /// use backgammon::rules::Roll;
+    ///
+    /// let mut game = Game::new();
+    /// while game.phase() == GamePhase::OpeningRoll {
+    ///     game.roll().unwrap();
+    /// }
+    /// let values = game.dices.values;
+    /// let turn = game.board.legal_turns(game.who_plays, values).unwrap()[0].clone();
+    /// game.play(&turn).unwrap();
+    /// assert_eq!(game.phase(), GamePhase::Rolling);
+    /// ```
+    pub fn play(&mut self, turn: &Turn) -> Result<(), Error> {
+        self.check_turn(turn)?;
+        let before = self.state();
+        turn.apply(&mut self.board)?;
+        self.audit_turn(turn, &before)?;
+        self.dices.consumed = (true, true, true, true);
+        self.check_winner(turn.player());
+        if !self.continue_bonus() {
+            self.end_turn();
+        }
+        self.record(HistoryEvent::Turn(turn.clone()), before);
+        Ok(())
+    }
+
+    /// Check if a turn can be played now with [`Game::play`], without playing it
+    pub fn check_turn(&self, turn: &Turn) -> Result<(), Error> {
+        self.moving(turn.player())?;
+        let values = self.dices.values;
+        if turn.dices() != values && turn.dices() != (values.1, values.0) {
+            return Err(Error::DiceInvalid);
+        }
+        if self.dices.consumed.0 || self.dices.consumed.1 {
+            return Err(Error::MoveInvalid);
+        }
+
+        // the turn may have been deserialized, so check it against the board of this game
+        let moves = turn.moves().iter().map(|m| m.checker().clone()).collect();
+        let (player, dices) = (turn.player(), turn.dices());
+        if Turn::new_variant(&self.board, player, dices, moves, self.variant)? != *turn {
+            return Err(Error::MoveInvalid);
+        }
+        Ok(())
+    }
+
+    /// Check several turns predicted by a client against the current state of the game in one
+    /// call, see [`Game::check_turn`]. The verdicts are in the order of the turns, and each turn
+    /// is checked on its own as an alternative to play now.
+    ///
+    /// ```
+    /// use backgammon::{Error, Game};
+    /// use backgammon::rules::{Board, Cube, Player, Roll};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.roll().unwrap();
+    /// let values = game.dices.values;
+    /// let turns = game.board.legal_turns(Player::Player0, values).unwrap();
+    /// assert!(game.check_turns(&turns).iter().all(|verdict| verdict.is_ok()));
+    ///
+    /// let other = Board::new().legal_turns(Player::Player1, values).unwrap();
+    /// let verdicts = game.check_turns(&other[..1]);
+    /// assert!(matches!(verdicts[0], Err(Error::NotYourTurn)));
+    /// ```
+    pub fn check_turns(&self, turns: &[Turn]) -> Vec<Result<(), Error>> {
+        turns.iter().map(|turn| self.check_turn(turn)).collect()
+    }
+
+    /// Check if a player has a legal move with the dices, i.e. is neither dancing on the bar nor
+    /// fully blocked
+    pub fn has_legal_move(&self, player: Player, dices: (u8, u8)) -> bool {
+        [dices.0, dices.1].into_iter().any(|dice| {
+            (1..=6).contains(&dice)
+                && self
+                    .board
+                    .generate_variant_moves(player, dice as usize, self.variant)
+                    .is_ok_and(|moves| !moves.is_empty())
+        })
+    }
+
+    /// Get the only legal turn of the player on turn, if the turn is forced. This is the empty turn
+    /// if the player can not move, so callers can play forced turns automatically with
+    /// [`Game::play`]. Turns generated elsewhere tell the same with [`Turn::is_forced`].
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    /// use backgammon::rules::{Board, Cube, Player, Roll};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.roll().unwrap();
+    /// // there is always a choice in the starting position
+    /// assert!(game.forced_turn().is_none());
+    /// ```
+    pub fn forced_turn(&self) -> Option<Turn> {
+        if self.phase() != GamePhase::Moving || self.dices.consumed.0 || self.dices.consumed.1 {
+            return None;
+        }
+        let mut turns = self
+            .board
+            .legal_variant_turns(self.who_plays, self.dices.values, self.variant)
+            .ok()?;
+        if turns.len() == 1 {
+            turns.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Pass the rest of the turn, which is only possible if no remaining dice can be played
+    pub fn pass(&mut self, player: Player) -> Result<(), Error> {
+        self.moving(player)?;
+        for dice in 1..=6 {
+            if self.dices.is_available(dice)
+                && !self
+                    .board
+                    .generate_variant_moves(player, dice as usize, self.variant)?
+                    .is_empty()
+            {
+                return Err(Error::MoveFirst);
+            }
+        }
+        // passing forfeits the rest of a bonus
+        let before = self.state();
+        self.dices.consumed = (true, true, true, true);
+        self.bonus = Bonus::None;
+        self.end_turn();
+        self.record(HistoryEvent::Pass { player }, before);
+        Ok(())
+    }
+
+    /// Check that the game is in the moving phase and the player is on turn
+    fn moving(&self, player: Player) -> Result<(), Error> {
+        match self.phase() {
+            GamePhase::Finished => Err(Error::GameEnded),
+            GamePhase::DoubleOffered => Err(Error::CubeReceived),
+            GamePhase::ChoosingDoubles if player == self.who_plays => {
+                Err(Error::ChooseDoublesFirst)
+            }
+            GamePhase::OpeningRoll | GamePhase::Rolling if player == self.who_plays => {
+                Err(Error::RollFirst)
+            }
+            _ if player != self.who_plays => Err(Error::NotYourTurn),
+            _ => Ok(()),
+        }
+    }
+
+    /// Keep the dices with the player once all dices have been played, if the player earned the
+    /// bonus of the roll or played the doubles chosen for it and rolls again
+    fn continue_bonus(&mut self) -> bool {
+        if self.result.is_some() {
+            return false;
+        }
+        match self.bonus {
+            Bonus::None if self.variant.is_bonus_roll(self.dices.values) => {
+                self.bonus = Bonus::Choosing;
+                true
+            }
+            Bonus::Doubles => {
+                self.bonus = Bonus::None;
+                self.roll_first = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Choose the doubles to play after the dices of a bonus roll have been played, e.g. after a
+    /// roll of 1-2 in Acey-deucey. The player rolls again after playing the doubles, unless
+    /// passing the rest of them.
+    ///
+    /// ```
+    /// use backgammon::Game;
+    /// use backgammon::rules::{Player, Variant};
+    ///
+    /// let mut game = Game::new_variant(Variant::AceyDeucey);
+    /// // the doubles can only be chosen once a roll of 1-2 has been played
+    /// assert!(game.choose_doubles(Player::Player0, 6).is_err());
+    /// ```
+    pub fn choose_doubles(&mut self, player: Player, dice: u8) -> Result<(), Error> {
+        if self.result.is_some() {
+            return Err(Error::GameEnded);
+        }
+        if player != self.who_plays {
+            return Err(Error::NotYourTurn);
+        }
+        if self.bonus != Bonus::Choosing {
+            return Err(Error::MoveInvalid);
+        }
+        if !(1..=6).contains(&dice) {
+            return Err(Error::DiceInvalid);
+        }
+        let before = self.state();
+        self.dices.values = (dice, dice);
+        self.dices.consumed = (false, false, false, false);
+        self.bonus = Bonus::Doubles;
+        self.record(HistoryEvent::ChooseDoubles { player, dice }, before);
+        Ok(())
+    }
+
+    /// Pass the dices to the opponent, unless the game has ended
+    fn end_turn(&mut self) {
+        if self.result.is_none() {
+            self.who_plays = self.who_plays.other();
+            self.roll_first = true;
+        }
+    }
+
+    /// Get the winner and the points won, once the game has ended. The points include the
+    /// automatic doubles of the cube, see [`Game::automatic_doubles`]. The winner of a voided
+    /// game is `Player::Nobody`.
+    pub fn result(&self) -> Option<(Player, u64)> {
+        self.result
+    }
+
+    /// Get how often the cube has been doubled automatically by ties of the opening roll, if the
+    /// automatic doubles rule is enabled with [`GameRules::with_murphy`].
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    /// use backgammon::rules::{GameRules, Roll};
+    ///
+    /// let mut game = Game::new().with_murphy(2);
+    /// while game.phase() == GamePhase::OpeningRoll {
+    ///     game.roll().unwrap();
+    /// }
+    /// assert!(game.automatic_doubles() <= 2);
+    /// assert_eq!(game.cube.value(), 1 << game.automatic_doubles());
+    /// ```
+    pub fn automatic_doubles(&self) -> u8 {
+        self.automatic_doubles
+    }
+
+    /// Double the centered cube after a tie of the opening roll, if the automatic doubles rule
+    /// is enabled and its limit not yet reached
+    fn double_automatically(&mut self) -> Result<(), Error> {
+        let limit = self.rules.murphy_limit;
+        if !self.rules.murphy || (limit != 0 && self.automatic_doubles >= limit) {
+            return Ok(());
+        }
+        let value = self.cube.value().checked_mul(2);
+        self.cube.set(value.ok_or(Error::CubeValueInvalid)?)?;
+        self.automatic_doubles += 1;
+        Ok(())
+    }
+
+    /// Submit an action of a player.
+    ///
+    /// This is the uniform entry point for all actions, validating that it is the player's turn
+    /// and that the action is permitted in the current state of the game.
+    ///
+    /// ```
+    /// use backgammon::{Game, PlayerAction};
+    /// use backgammon::rules::Player;
+    ///
+    /// let mut game = Game::new();
+    /// while game.who_plays == Player::Nobody {
+    ///     game.submit(Player::Player0, PlayerAction::Roll).unwrap();
+    /// }
+    /// let other = game.who_plays.other();
+    /// assert!(game.submit(other, PlayerAction::Roll).is_err());
+    /// ```
+    pub fn submit(&mut self, player: Player, action: PlayerAction) -> Result<(), Error> {
+        if self.result.is_some() {
+            return Err(Error::GameEnded);
+        }
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+
+        match action {
+            PlayerAction::Roll => {
+                // the opening roll may be triggered by any player
+                if self.who_plays != Player::Nobody && player != self.who_plays {
+                    return Err(Error::NotYourTurn);
+                }
+                let _ = self.roll()?;
+            }
+            PlayerAction::Move { dice, from } => {
+                let _ = self.move_checker(player, dice, usize::from(from))?;
+            }
+            PlayerAction::MoveFromBar { dice } => {
+                let _ = self.move_checker_from_bar(player, dice)?;
+            }
+            PlayerAction::PlayTurn(turn) => {
+                if turn.player() != player {
+                    return Err(Error::NotYourTurn);
+                }
+                self.play(&turn)?;
+            }
+            PlayerAction::OfferDouble => self.offer_double(player)?,
+            PlayerAction::Take => self.take_cube(player)?,
+            PlayerAction::Drop => self.drop_cube(player)?,
+            PlayerAction::Resign => self.resign(player)?,
+            PlayerAction::ChooseDoubles { dice } => self.choose_doubles(player, dice)?,
+            PlayerAction::RequestTakeback => self.request_takeback(player)?,
+            PlayerAction::AcceptTakeback => self.answer_takeback(player, true)?,
+            PlayerAction::DeclineTakeback => self.answer_takeback(player, false)?,
+        }
+        self.play_forced()
+    }
+
+    /// Offer the doubling cube to the opponent. This is only possible before rolling.
+    fn offer_double(&mut self, player: Player) -> Result<(), Error> {
+        if player != self.who_plays {
+            return Err(Error::NotYourTurn);
+        }
+        if self.cube_received {
+            return Err(Error::CubeReceived);
+        }
+        if !self.roll_first || self.crawford || self.is_cubeless() {
+            return Err(Error::DoublingNotPermitted);
+        }
+        let _ = self.cube.offer(player.other())?;
+        let before = self.state();
+        self.cube_received = true;
+        self.record(HistoryEvent::OfferDouble { player }, before);
+        Ok(())
+    }
+
+    /// Accept the doubling cube, which doubles the value of the game and passes the cube to the
+    /// accepting player
+    fn take_cube(&mut self, player: Player) -> Result<(), Error> {
+        self.cube_response(player)?;
+        let before = self.state();
+        self.cube.accept(player)?;
+        self.cube_received = false;
+        self.record(HistoryEvent::Take { player }, before);
+        Ok(())
+    }
+
+    /// Decline the doubling cube, which ends the game at its current value
+    fn drop_cube(&mut self, player: Player) -> Result<(), Error> {
+        self.cube_response(player)?;
+        let before = self.state();
+        self.cube_received = false;
+        self.result = Some((player.other(), self.cube.value()));
+        self.record(HistoryEvent::Drop { player }, before);
+        Ok(())
+    }
+
+    /// Check that the player has been offered the cube
+    fn cube_response(&self, player: Player) -> Result<(), Error> {
+        if !self.cube_received {
+            return Err(Error::MoveInvalid);
+        }
+        if player != self.who_plays.other() {
+            return Err(Error::NotYourTurn);
+        }
+        Ok(())
+    }
+
+    /// Give up the game, the opponent wins the current value of the game
+    fn resign(&mut self, player: Player) -> Result<(), Error> {
+        if self.who_plays == Player::Nobody {
+            return Err(Error::RollFirst);
+        }
+        let before = self.state();
+        self.result = Some((player.other(), self.cube.value()));
+        self.record(HistoryEvent::Resign { player }, before);
+        Ok(())
+    }
+
+    /// Void the game, e.g. after a server crash, a mutual abort, or a rules dispute.
+    ///
+    /// A voided game is finished without a winner: its result is `Player::Nobody` with no points.
+    /// Matches replay or skip voided games, see
+    /// [`Match::with_void_policy`](crate::Match::with_void_policy).
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    ///
+    /// let mut game = Game::new();
+    /// game.void().unwrap();
+    /// assert_eq!(game.phase(), GamePhase::Finished);
+    /// assert!(game.is_void());
+    /// assert!(game.void().is_err());
+    /// ```
+    pub fn void(&mut self) -> Result<(), Error> {
+        if self.result.is_some() {
+            return Err(Error::GameEnded);
+        }
+        let before = self.state();
+        self.cube_received = false;
+        self.result = Some((Player::Nobody, 0));
+        self.record(HistoryEvent::Void, before);
+        Ok(())
+    }
+
+    /// Check if the game has been voided, see [`Game::void`]
+    pub fn is_void(&self) -> bool {
+        self.result
+            .is_some_and(|(winner, _)| winner == Player::Nobody)
+    }
+
+    /// End the game if the player has borne off all checkers
+    ///
+    /// Gammons and backgammons count as single wins under the Jacoby rule as long as the cube has
+    /// not been turned.
+    fn check_winner(&mut self, player: Player) {
+        if let Some(kind) = self.board.win_kind(player) {
+            let unturned = self.cube.owner() == Player::Nobody;
+            let kind = if self.rules.jacoby && unturned {
+                WinKind::Single
+            } else {
+                kind
+            };
+            self.result = Some((player, kind.points(self.cube.value())));
+        }
+    }
 }
 
 impl Roll for Game {
     fn roll(&mut self) -> Result<&mut Self, Error> {
-        if !self.dices.consumed.0
-            && !self.dices.consumed.1
-            && !self.dices.consumed.2
-            && !self.dices.consumed.3
-        {
-            return Err(Error::MoveFirst);
+        if self.result.is_some() {
+            return Err(Error::GameEnded);
         }
         if self.cube_received {
             return Err(Error::CubeReceived);
         }
+        if self.bonus == Bonus::Choosing {
+            return Err(Error::ChooseDoublesFirst);
+        }
+        if self.who_plays != Player::Nobody && !self.roll_first {
+            return Err(Error::MoveFirst);
+        }
 
+        let (player, before) = (self.who_plays, self.state());
         self.dices = self.dices.roll();
         if self.who_plays == Player::Nobody {
-            let diff = self.dices.values.0 - self.dices.values.1;
-            match diff {
-                0 => {
-                    self.who_plays = Player::Nobody;
-                }
-                _ if diff > 0 => {
-                    self.who_plays = Player::Player0;
-                }
-                _ => {
-                    self.who_plays = Player::Player1;
-                }
+            self.who_plays = match self.dices.values.0.cmp(&self.dices.values.1) {
+                std::cmp::Ordering::Equal => Player::Nobody,
+                std::cmp::Ordering::Greater => Player::Player0,
+                std::cmp::Ordering::Less => Player::Player1,
+            };
+            if self.who_plays == Player::Nobody {
+                self.double_automatically()?;
             }
         }
+        self.roll_first = false;
+        let dices = self.dices.values;
+        self.record(HistoryEvent::Roll { player, dices }, before);
         Ok(self)
     }
 }
 
 impl Move for Game {
     fn move_checker(&mut self, player: Player, dice: u8, from: usize) -> Result<&mut Self, Error> {
+        if from > 23 {
+            return Err(Error::FieldInvalid);
+        }
+
         // check if move is permitted
         let _ = self.move_permitted(player, dice)?;
 
         // check if player has to move checker from bar first
-        if ((player == Player::Player0) && (self.board.get().bar.0 > 0))
-            || ((player == Player::Player1) && (self.board.get().bar.1 > 0))
-        {
+        let (_, bar, _) = self.board.raw(player)?;
+        if bar > 0 && !self.variant.enters_freely() {
             return Err(Error::MoveInvalidBar);
         }
 
         // check if the dice value has been consumed
-        if (dice == self.dices.values.0 && self.dices.consumed.0)
-            || (dice == self.dices.values.1 && self.dices.consumed.1)
-            || (dice == self.dices.values.1 && self.dices.consumed.2)
-            || (dice == self.dices.values.1 && self.dices.consumed.3)
-        {
-            return Err(Error::MoveInvalid);
+        if !self.dices.is_available(dice) {
+            return Err(Error::DiceAlreadyConsumed);
         }
 
-        // remove checker from old position
-        self.board.set(player, from, -1)?;
-
-        // move checker to new position, in case it is reaching the off position, set it off
-        let new_position = from as i8 - dice as i8;
+        // bearing off requires all checkers in the home board, and a higher dice than needed
+        // can only be used for the checker on the highest field
+        let new_position = from as i16 - i16::from(dice);
         if new_position < 0 {
-            self.board.set_off(player, 1)?;
-        } else {
-            self.board.set(player, new_position as usize, 1)?;
+            if !self.board.can_bear_off(player)? {
+                return Err(Error::MoveInvalid);
+            }
+            let (fields, _, _) = self.board.raw(player)?;
+            if new_position < -1 && fields[from + 1..].iter().any(|&x| x > 0) {
+                return Err(Error::MoveInvalid);
+            }
         }
 
+        // remove checker from old position and move it to the new position, in case it is
+        // reaching the off position, set it off
+        let before = self.state();
+        let moved = self.board.set(player, from, -1).and_then(|()| {
+            if new_position < 0 {
+                self.board.set_off(player, 1).map(|()| 25)
+            } else {
+                let to = new_position as usize;
+                self.board.set(player, to, 1).map(|()| to as u8)
+            }
+        });
+        let to = self.refused(moved, &before)?;
+        self.audit_move(player, dice, &before)?;
+
         // set dice value to consumed
-        if dice == self.dices.values.0 && !self.dices.consumed.0 {
-            self.dices.consumed.0 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.1 {
-            self.dices.consumed.1 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.2 {
-            self.dices.consumed.2 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.3 {
-            self.dices.consumed.3 = true;
-        }
-
-        // switch to other player if all dices have been consumed
-        if self.dices.consumed.0
-            && self.dices.consumed.1
-            && self.dices.consumed.2
-            && self.dices.consumed.3
-        {
+        self.dices.consume(dice)?;
+
+        // end the game if all checkers have been borne off
+        self.check_winner(player);
+
+        // switch to other player if all dices have been consumed, unless the bonus goes on
+        if self.dices.all_consumed() && !self.continue_bonus() {
             self.who_plays = self.who_plays.other();
             self.roll_first = true;
         }
 
+        self.record_move(player, [from as u8, to], before);
         Ok(self)
     }
 
@@ -215,44 +950,42 @@ impl Move for Game {
         let _ = self.move_permitted(player, dice)?;
 
         // check if the dice value has been consumed
-        if (dice == self.dices.values.0 && self.dices.consumed.0)
-            || (dice == self.dices.values.1 && self.dices.consumed.1)
-            || (dice == self.dices.values.1 && self.dices.consumed.2)
-            || (dice == self.dices.values.1 && self.dices.consumed.3)
-        {
-            return Err(Error::MoveInvalid);
+        if !self.dices.is_available(dice) {
+            return Err(Error::DiceAlreadyConsumed);
         }
 
         // set the checker from bar
-        self.board.set_bar(player, -1)?;
-        self.board.set(player, 24 - dice as usize, 1)?;
+        let before = self.state();
+        let entered = self
+            .board
+            .set_bar(player, -1)
+            .and_then(|()| self.board.set(player, 24 - dice as usize, 1));
+        self.refused(entered, &before)?;
+        self.audit_move(player, dice, &before)?;
 
         // set dice value to consumed
-        if dice == self.dices.values.0 && !self.dices.consumed.0 {
-            self.dices.consumed.0 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.1 {
-            self.dices.consumed.1 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.2 {
-            self.dices.consumed.2 = true;
-        } else if dice == self.dices.values.1 && !self.dices.consumed.3 {
-            self.dices.consumed.3 = true;
-        }
-
-        // switch to other player if all dices have been consumed
-        if self.dices.consumed.0
-            && self.dices.consumed.1
-            && self.dices.consumed.2
-            && self.dices.consumed.3
-        {
+        self.dices.consume(dice)?;
+
+        // end the game if all checkers have been borne off
+        self.check_winner(player);
+
+        // switch to other player if all dices have been consumed, unless the bonus goes on
+        if self.dices.all_consumed() && !self.continue_bonus() {
             self.who_plays = self.who_plays.other();
             self.roll_first = true;
         }
 
+        self.record_move(player, [24, 24 - dice], before);
         Ok(self)
     }
 
     /// Implements checks to validate if the player is allowed to move
     fn move_permitted(&mut self, player: Player, dice: u8) -> Result<&mut Self, Error> {
+        // check if game has already ended
+        if self.result.is_some() {
+            return Err(Error::GameEnded);
+        }
+
         // check if player is allowed to move
         if player != self.who_plays {
             return Err(Error::NotYourTurn);
@@ -268,6 +1001,11 @@ impl Move for Game {
             return Err(Error::CubeReceived);
         }
 
+        // check if player has to choose the doubles of a bonus first
+        if self.bonus == Bonus::Choosing {
+            return Err(Error::ChooseDoublesFirst);
+        }
+
         // check if player has to roll first
         if self.roll_first {
             return Err(Error::RollFirst);
@@ -282,6 +1020,17 @@ impl Move for Game {
     }
 }
 
+impl Game {
+    /// Restore the state before an action if one of its steps failed, so a refused action leaves
+    /// the game unchanged
+    fn refused<T>(&mut self, result: Result<T, Error>, before: &State) -> Result<T, Error> {
+        if result.is_err() {
+            self.restore(before.clone());
+        }
+        result
+    }
+}
+
 /// Implements SetRules for Game
 impl GameRules for Game {
     fn with_beaver(mut self) -> Self {
@@ -314,6 +1063,7 @@ impl GameRules for Game {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::Location;
 
     // Test Display trait for Game
     #[test]
@@ -341,4 +1091,517 @@ mod tests {
         assert!(g.rules.jacoby);
         assert!(g.rules.holland);
     }
+
+    // Test that corrupt game states are rejected
+    #[test]
+    fn test_deserialize_invalid() {
+        let json = serde_json::to_string(&Game::new()).unwrap();
+        assert!(serde_json::from_str::<Game>(&json).is_ok());
+        let corrupt = json.replace("\"off\":0", "\"off\":25");
+        assert!(serde_json::from_str::<Game>(&corrupt).is_err());
+    }
+
+    /// Game where player 0 is about to roll
+    fn before_roll() -> Game {
+        Game {
+            who_plays: Player::Player0,
+            roll_first: true,
+            ..Game::new()
+        }
+    }
+
+    #[test]
+    fn test_resume() -> Result<(), Error> {
+        let mut cube = Cube::default();
+        cube.set(2)?;
+        cube.set_owner(Player::Player1);
+        let mut g = Game::resume(Board::new(), cube.clone(), Player::Player1, false)?;
+        assert!(g.submit(Player::Player0, PlayerAction::Roll).is_err());
+        assert!(g.submit(Player::Player1, PlayerAction::OfferDouble).is_ok());
+
+        assert!(Game::resume(Board::new(), cube.clone(), Player::Player1, true).is_err());
+        assert!(Game::resume(Board::new(), cube, Player::Nobody, false).is_err());
+
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -2)?;
+        assert!(matches!(
+            Game::resume(board, Cube::default(), Player::Player0, true),
+            Err(Error::PositionInvalid)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_roll() -> Result<(), Error> {
+        let mut g = Game::new();
+        while g.who_plays == Player::Nobody {
+            g.submit(Player::Player1, PlayerAction::Roll)?;
+        }
+        let player = g.who_plays;
+        assert!(!g.roll_first);
+        assert!(g.submit(player.other(), PlayerAction::Roll).is_err());
+        assert!(g.submit(player, PlayerAction::Roll).is_err());
+        assert!(g.submit(player, PlayerAction::OfferDouble).is_err());
+        assert!(g.submit(Player::Nobody, PlayerAction::Roll).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_move() -> Result<(), Error> {
+        let mut g = before_roll();
+        g.submit(Player::Player0, PlayerAction::Roll)?;
+        let dice = g.dices.values.0;
+        assert!(g
+            .submit(Player::Player1, PlayerAction::Move { dice, from: 23 })
+            .is_err());
+        g.submit(Player::Player0, PlayerAction::Move { dice, from: 12 })
+            .or_else(|_| g.submit(Player::Player0, PlayerAction::Move { dice, from: 23 }))?;
+        assert!(g.dices.consumed.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_play_turn() -> Result<(), Error> {
+        let mut g = before_roll();
+        g.submit(Player::Player0, PlayerAction::Roll)?;
+        let turn = g
+            .board
+            .legal_turns(Player::Player0, g.dices.values)?
+            .remove(0);
+        assert!(matches!(
+            g.submit(Player::Player1, PlayerAction::PlayTurn(turn.clone())),
+            Err(Error::NotYourTurn)
+        ));
+        g.submit(Player::Player0, PlayerAction::PlayTurn(turn.clone()))?;
+        assert_eq!(g.who_plays, Player::Player1);
+        assert!(g
+            .submit(Player::Player0, PlayerAction::PlayTurn(turn))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_take() -> Result<(), Error> {
+        let mut g = before_roll();
+        assert!(g
+            .submit(Player::Player1, PlayerAction::OfferDouble)
+            .is_err());
+        assert!(g.submit(Player::Player1, PlayerAction::Take).is_err());
+        g.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        assert!(g.submit(Player::Player0, PlayerAction::Roll).is_err());
+        assert!(g.submit(Player::Player0, PlayerAction::Take).is_err());
+        g.submit(Player::Player1, PlayerAction::Take)?;
+        assert_eq!(g.cube.value(), 2);
+        assert_eq!(g.cube.owner(), Player::Player1);
+
+        // player 0 does not own the cube anymore
+        assert!(g
+            .submit(Player::Player0, PlayerAction::OfferDouble)
+            .is_err());
+        g.submit(Player::Player0, PlayerAction::Roll)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_drop() -> Result<(), Error> {
+        let mut g = before_roll();
+        g.cube.set(2)?;
+        g.cube.set_owner(Player::Player0);
+        g.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        g.submit(Player::Player1, PlayerAction::Drop)?;
+        assert_eq!(g.result(), Some((Player::Player0, 2)));
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::Roll),
+            Err(Error::GameEnded)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_pending_cube() -> Result<(), Error> {
+        let mut g = before_roll();
+        g.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::Move { dice: 1, from: 5 }),
+            Err(Error::CubeReceived)
+        ));
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::OfferDouble),
+            Err(Error::CubeReceived)
+        ));
+        g.submit(Player::Player1, PlayerAction::Take)?;
+
+        // the redouble is declined at the value of the game before doubling
+        g.who_plays = Player::Player1;
+        g.roll_first = true;
+        g.submit(Player::Player1, PlayerAction::OfferDouble)?;
+        g.submit(Player::Player0, PlayerAction::Drop)?;
+        assert_eq!(g.result(), Some((Player::Player1, 2)));
+        Ok(())
+    }
+
+    /// Game where player 0 bears off with the given dices, all checkers on the fields 0 to 5
+    fn bearing_off(values: (u8, u8)) -> Result<Game, Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 7, -3)?;
+        board.set(Player::Player0, 12, -5)?;
+        board.set(Player::Player0, 23, -2)?;
+        board.set(Player::Player0, 2, 10)?;
+        Ok(Game {
+            who_plays: Player::Player0,
+            board,
+            dices: Dices {
+                values,
+                consumed: (false, false, true, true),
+            },
+            ..Game::new()
+        })
+    }
+
+    #[test]
+    fn test_bear_off() -> Result<(), Error> {
+        // a higher dice bears off from the highest field only
+        let mut g = bearing_off((6, 3))?;
+        assert!(g
+            .submit(Player::Player0, PlayerAction::Move { dice: 6, from: 2 })
+            .is_err());
+        g.submit(Player::Player0, PlayerAction::Move { dice: 6, from: 5 })?;
+        g.submit(Player::Player0, PlayerAction::Move { dice: 3, from: 2 })?;
+        assert_eq!(g.board.get().off.0, 2);
+        assert_eq!(g.who_plays, Player::Player1);
+
+        let mut g = bearing_off((6, 3))?;
+        g.submit(Player::Player0, PlayerAction::Move { dice: 3, from: 2 })?;
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::Move { dice: 3, from: 2 }),
+            Err(Error::DiceAlreadyConsumed)
+        ));
+
+        // no bearing off with a checker outside the home board
+        let mut g = bearing_off((6, 3))?;
+        g.board.set(Player::Player0, 5, -1)?;
+        g.board.set(Player::Player0, 8, 1)?;
+        assert!(g
+            .submit(Player::Player0, PlayerAction::Move { dice: 3, from: 2 })
+            .is_err());
+        assert_eq!(g.board.get().off.0, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_phase() -> Result<(), Error> {
+        let mut g = Game::new();
+        assert_eq!(g.phase(), GamePhase::OpeningRoll);
+        assert!(g.pass(Player::Player0).is_err());
+        while g.phase() == GamePhase::OpeningRoll {
+            let _ = g.roll()?;
+        }
+        assert_eq!(g.phase(), GamePhase::Moving);
+        let player = g.who_plays;
+        assert!(matches!(g.pass(player), Err(Error::MoveFirst)));
+
+        let turns = g.board.legal_turns(player, g.dices.values)?;
+        g.play(&turns[0])?;
+        assert_eq!(g.phase(), GamePhase::Rolling);
+        assert_eq!(g.who_plays, player.other());
+        // the same turn can not be played twice
+        assert!(matches!(g.play(&turns[0]), Err(Error::NotYourTurn)));
+
+        g.submit(player.other(), PlayerAction::OfferDouble)?;
+        assert_eq!(g.phase(), GamePhase::DoubleOffered);
+        g.submit(player, PlayerAction::Drop)?;
+        assert_eq!(g.phase(), GamePhase::Finished);
+        Ok(())
+    }
+
+    #[test]
+    fn test_automatic_doubles() -> Result<(), Error> {
+        let mut g = Game::new().with_murphy(1);
+        g.double_automatically()?;
+        assert_eq!((g.cube.value(), g.automatic_doubles()), (2, 1));
+        // the limit is reached
+        g.double_automatically()?;
+        assert_eq!(g.cube.value(), 2);
+        assert_eq!(g.cube.owner(), Player::Nobody);
+
+        // without a limit every tie doubles, and none without the rule
+        let mut g = Game::new().with_murphy(0);
+        for _ in 0..3 {
+            g.double_automatically()?;
+        }
+        assert_eq!(g.cube.value(), 8);
+        let mut g = Game::new();
+        g.double_automatically()?;
+        assert_eq!(g.cube.value(), 1);
+
+        // the points won count the cube doubled
+        let mut g = Game::new().with_murphy(0);
+        g.double_automatically()?;
+        g.who_plays = Player::Player0;
+        g.resign(Player::Player1)?;
+        assert_eq!(g.result(), Some((Player::Player0, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_play() -> Result<(), Error> {
+        let mut g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (3, 1),
+                consumed: (false, false, true, true),
+            },
+            ..Game::new()
+        };
+        let turn = Board::new().legal_turns(Player::Player0, (1, 3))?[0].clone();
+        let wrong = Board::new().legal_turns(Player::Player0, (6, 5))?[0].clone();
+        let theirs = Board::new().legal_turns(Player::Player1, (3, 1))?[0].clone();
+        assert!(matches!(g.play(&wrong), Err(Error::DiceInvalid)));
+        assert!(matches!(g.play(&theirs), Err(Error::NotYourTurn)));
+        g.play(&turn)?;
+        assert_ne!(g.board, Board::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_turns() -> Result<(), Error> {
+        let g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (3, 1),
+                consumed: (false, false, true, true),
+            },
+            ..Game::new()
+        };
+        let turn = Board::new().legal_turns(Player::Player0, (3, 1))?[0].clone();
+        let wrong = Board::new().legal_turns(Player::Player0, (6, 5))?[0].clone();
+        // a turn predicted from a position which is not the one of the game
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set(Player::Player0, 22, 1)?;
+        let stale = board.legal_turns(Player::Player0, (3, 1))?;
+        let stale = stale
+            .into_iter()
+            .find(|t| t.moves()[0].checker().from() == Location::Point(23))
+            .ok_or(Error::MoveInvalid)?;
+
+        assert!(g.has_legal_move(Player::Player0, (3, 1)));
+        assert!(g.forced_turn().is_none());
+        let verdicts = g.check_turns(&[turn, wrong, stale]);
+        assert!(verdicts[0].is_ok());
+        assert!(matches!(verdicts[1], Err(Error::DiceInvalid)));
+        assert!(matches!(verdicts[2], Err(Error::MoveInvalid)));
+        assert_eq!(g.board, Board::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_forced_turn() -> Result<(), Error> {
+        // player 0 bears off its last two checkers with any roll
+        let mut fields = [0; 24];
+        fields[0] = 2;
+        let mut other = [0; 24];
+        other[20] = 15;
+        let board = Board::from_raw((fields, 0, 13), (other, 0, 0));
+        let mut g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (6, 5),
+                consumed: (false, false, true, true),
+            },
+            board,
+            ..Game::new()
+        };
+        let turn = g.forced_turn().ok_or(Error::MoveInvalid)?;
+        assert_eq!(turn.moves().len(), 2);
+        g.play(&turn)?;
+        assert_eq!(g.result().map(|(winner, _)| winner), Some(Player::Player0));
+        assert!(g.forced_turn().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pass() -> Result<(), Error> {
+        // player 0 on the bar against a closed board
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set_bar(Player::Player0, 1)?;
+        for field in 0..6 {
+            board.set(Player::Player1, field, 2)?;
+        }
+        let mut g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (4, 2),
+                consumed: (false, false, true, true),
+            },
+            board,
+            ..Game::new()
+        };
+        assert!(matches!(g.pass(Player::Player1), Err(Error::NotYourTurn)));
+        assert!(!g.has_legal_move(Player::Player0, (4, 2)));
+        assert!(g.has_legal_move(Player::Player1, (4, 2)));
+        assert!(g.forced_turn().is_some_and(|turn| turn.is_empty()));
+        g.pass(Player::Player0)?;
+        assert_eq!(g.who_plays, Player::Player1);
+        assert_eq!(g.phase(), GamePhase::Rolling);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gammon() -> Result<(), Error> {
+        // player 0 bears off the last checker, player 1 has not borne off any
+        let mut board = Board::new();
+        board.set(Player::Player0, 5, -4)?;
+        board.set(Player::Player0, 7, -3)?;
+        board.set(Player::Player0, 12, -5)?;
+        board.set(Player::Player0, 23, -2)?;
+        board.set_off(Player::Player0, 14)?;
+        let game = Game {
+            who_plays: Player::Player0,
+            board,
+            dices: Dices {
+                values: (6, 1),
+                consumed: (false, true, true, true),
+            },
+            ..Game::new()
+        };
+
+        let mut g = game.clone();
+        g.cube.accept(Player::Player1)?;
+        g.submit(Player::Player0, PlayerAction::Move { dice: 6, from: 5 })?;
+        // player 1 has checkers in player 0's home board
+        assert_eq!(g.result(), Some((Player::Player0, 6)));
+
+        let mut g = game.clone();
+        g.rules.jacoby = true;
+        g.submit(Player::Player0, PlayerAction::Move { dice: 6, from: 5 })?;
+        assert_eq!(g.result(), Some((Player::Player0, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_acey_deucey() -> Result<(), Error> {
+        let mut g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (1, 2),
+                consumed: (false, false, true, true),
+            },
+            ..Game::new_variant(Variant::AceyDeucey)
+        };
+        // the checker entered moves on while the others wait off the board
+        g.submit(Player::Player0, PlayerAction::MoveFromBar { dice: 2 })?;
+        g.submit(Player::Player0, PlayerAction::Move { dice: 1, from: 22 })?;
+        assert_eq!(g.phase(), GamePhase::ChoosingDoubles);
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::Roll),
+            Err(Error::ChooseDoublesFirst)
+        ));
+        assert!(matches!(
+            g.submit(Player::Player1, PlayerAction::ChooseDoubles { dice: 6 }),
+            Err(Error::NotYourTurn)
+        ));
+        g.submit(Player::Player0, PlayerAction::ChooseDoubles { dice: 6 })?;
+        assert_eq!(g.phase(), GamePhase::Moving);
+
+        // the player rolls again after the doubles
+        let turn = g
+            .board
+            .legal_variant_turns(Player::Player0, (6, 6), Variant::AceyDeucey)?[0]
+            .clone();
+        g.play(&turn)?;
+        assert_eq!(g.phase(), GamePhase::Rolling);
+        assert_eq!(g.who_plays, Player::Player0);
+        let _ = g.undo();
+        let _ = g.undo();
+        assert_eq!(g.phase(), GamePhase::ChoosingDoubles);
+
+        // a 1-2 has no bonus in standard games
+        let mut g = Game {
+            who_plays: Player::Player0,
+            dices: Dices {
+                values: (1, 2),
+                consumed: (false, false, true, true),
+            },
+            ..Game::new()
+        };
+        let turn = g.board.legal_turns(Player::Player0, (1, 2))?[0].clone();
+        g.play(&turn)?;
+        assert_eq!(g.who_plays, Player::Player1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refused_move_unchanged() -> Result<(), Error> {
+        let mut g = before_roll();
+        g.roll_first = false;
+        g.dices = Dices {
+            values: (6, 5),
+            consumed: (false, false, true, true),
+        };
+        // the 19-point, where both checkers would land, is held by player 1
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::Move { dice: 5, from: 23 }),
+            Err(Error::FieldBlocked)
+        ));
+        assert_eq!(g.board, Board::new());
+        assert!(g.history().is_empty());
+
+        g.board.set(Player::Player0, 23, -1)?;
+        g.board.set_bar(Player::Player0, 1)?;
+        let board = g.board.clone();
+        assert!(matches!(
+            g.submit(Player::Player0, PlayerAction::MoveFromBar { dice: 6 }),
+            Err(Error::FieldBlocked)
+        ));
+        assert_eq!(g.board, board);
+        assert!(g.board.validate().is_ok());
+        g.submit(Player::Player0, PlayerAction::MoveFromBar { dice: 5 })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_out_of_range() -> Result<(), Error> {
+        let mut fields = [0; 24];
+        fields[1..6].fill(3);
+        let (player1, bar, off) = Board::new().raw(Player::Player1)?;
+        let board = Board::from_raw((fields, 0, 0), (player1, bar, off));
+        let mut g = Game::resume(board.clone(), Cube::default(), Player::Player0, false)?;
+        g.roll_first = false;
+        g.dices = Dices {
+            values: (6, 5),
+            consumed: (false, false, true, true),
+        };
+        for from in [24, 128, 200, 255] {
+            assert!(matches!(
+                g.submit(Player::Player0, PlayerAction::Move { dice: 6, from }),
+                Err(Error::FieldInvalid)
+            ));
+        }
+        assert_eq!(g.board, board);
+        g.submit(Player::Player0, PlayerAction::Move { dice: 6, from: 5 })?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_crawford() {
+        let mut g = Game {
+            crawford: true,
+            ..before_roll()
+        };
+        assert!(g
+            .submit(Player::Player0, PlayerAction::OfferDouble)
+            .is_err());
+    }
+
+    #[test]
+    fn test_submit_resign() -> Result<(), Error> {
+        let mut g = Game::new();
+        assert!(g.submit(Player::Player0, PlayerAction::Resign).is_err());
+        let mut g = before_roll();
+        g.submit(Player::Player0, PlayerAction::Resign)?;
+        assert_eq!(g.result(), Some((Player::Player1, 1)));
+        Ok(())
+    }
 }
@@ -0,0 +1,134 @@
+use super::history::State;
+use super::Game;
+use crate::rules::{Player, Turn};
+use crate::Error;
+
+impl Game {
+    /// Enable the self-consistency audit of the engine, e.g. for servers running rated games.
+    ///
+    /// Every turn played is re-derived from the legal turns generated for the board before it, and
+    /// every checker moved from the legal moves of the dice, comparing the board reached and
+    /// validating it afterwards. On any divergence the action is undone and refused with
+    /// [`Error::AuditFailed`]. The audit costs the generation of the legal turns for every action.
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    /// use backgammon::rules::Roll;
+    ///
+    /// let mut game = Game::new().with_audit();
+    /// while game.phase() == GamePhase::OpeningRoll {
+    ///     game.roll().unwrap();
+    /// }
+    /// let turn = game.board.legal_turns(game.who_plays, game.dices.values).unwrap()[0].clone();
+    /// game.play(&turn).unwrap();
+    /// assert!(game.is_audited());
+    /// ```
+    pub fn with_audit(mut self) -> Self {
+        self.audit = true;
+        self
+    }
+
+    /// Check if the self-consistency audit is enabled, see [`Game::with_audit`]
+    pub fn is_audited(&self) -> bool {
+        self.audit
+    }
+
+    /// Check a turn played from the given state against the legal turns generated for it
+    pub(super) fn audit_turn(&mut self, turn: &Turn, before: &State) -> Result<(), Error> {
+        if !self.audit {
+            return Ok(());
+        }
+        let board = before.board();
+        let legal = board
+            .legal_variant_turns(turn.player(), turn.dices(), self.variant)
+            .is_ok_and(|turns| turns.contains(turn));
+        let mut expected = board.clone();
+        let consistent = legal
+            && turn.apply(&mut expected).is_ok()
+            && expected == self.board
+            && self.board.validate_variant(self.variant).is_ok();
+        self.audited(consistent, before)
+    }
+
+    /// Check a checker moved with the dice from the given state against the legal moves generated
+    /// for it
+    pub(super) fn audit_move(
+        &mut self,
+        player: Player,
+        dice: u8,
+        before: &State,
+    ) -> Result<(), Error> {
+        if !self.audit {
+            return Ok(());
+        }
+        let board = before.board();
+        let consistent = board
+            .generate_variant_moves(player, usize::from(dice), self.variant)
+            .is_ok_and(|moves| {
+                moves.iter().any(|m| {
+                    let mut expected = board.clone();
+                    expected.apply_move(m).is_ok() && expected == self.board
+                })
+            })
+            && self.board.validate_variant(self.variant).is_ok();
+        self.audited(consistent, before)
+    }
+
+    /// Undo the action and refuse it, unless the audit found it consistent
+    fn audited(&mut self, consistent: bool, before: &State) -> Result<(), Error> {
+        if consistent {
+            return Ok(());
+        }
+        self.restore(before.clone());
+        Err(Error::AuditFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, Move};
+    use crate::PlayerAction;
+
+    #[test]
+    fn audit_actions() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        game = game.with_audit();
+        game.roll_first = false;
+        game.dices.values = (6, 1);
+        game.dices.consumed = (false, false, true, true);
+        let _ = game.move_checker(Player::Player0, 6, 23)?;
+        game.submit(Player::Player0, PlayerAction::Move { dice: 1, from: 7 })?;
+        assert_eq!(game.who_plays, Player::Player1);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_divergence() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        game = game.with_audit();
+        let before = game.state();
+        // a board no legal move of a 3 leads to, as if the engine had moved a checker wrongly
+        game.board.set(Player::Player0, 12, -1)?;
+        game.board.set(Player::Player0, 8, 1)?;
+        assert!(matches!(
+            game.audit_move(Player::Player0, 3, &before),
+            Err(Error::AuditFailed)
+        ));
+        assert_eq!(game.board, Board::new());
+
+        let turn = Board::new().legal_turns(Player::Player0, (3, 1))?[0].clone();
+        game.board.set(Player::Player0, 1, 1)?;
+        assert!(matches!(
+            game.audit_turn(&turn, &before),
+            Err(Error::AuditFailed)
+        ));
+        assert_eq!(game.board, Board::new());
+
+        // without the audit nothing is checked
+        game.audit = false;
+        game.board.set(Player::Player0, 1, 1)?;
+        game.audit_turn(&turn, &before)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use super::{Game, GamePhase};
+use crate::rules::Player;
+use crate::{Error, PlayerAction};
+use serde::{Deserialize, Serialize};
+
+/// Represents the rules of speed play, see [`Game::with_blitz`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blitz {
+    /// The time of each player for the game, in milliseconds
+    pub time: u64,
+    /// The time each decision may take before the time of the player runs, in milliseconds
+    pub delay: u64,
+    /// Whether the cube is left out of the game, so only the result of the board counts
+    pub no_cube: bool,
+}
+
+impl Default for Blitz {
+    /// Three minutes for each player with a delay of twelve seconds per decision, like the
+    /// common online speedgammon clocks
+    fn default() -> Self {
+        Blitz {
+            time: 3 * 60 * 1000,
+            delay: 12 * 1000,
+            no_cube: false,
+        }
+    }
+}
+
+/// Represents the clocks of both players in speed play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Clock {
+    blitz: Blitz,
+    /// the time left of player 0 and player 1
+    left: (u64, u64),
+    /// the time of the last action submitted
+    since: Option<u64>,
+}
+
+impl Clock {
+    /// Get the time left of a player, once charged for a decision from the last action to now
+    fn charged(&self, player: Player, now: u64) -> (u64, bool) {
+        let left = match player {
+            Player::Player0 => self.left.0,
+            _ => self.left.1,
+        };
+        let used = self.since.map_or(0, |since| {
+            now.saturating_sub(since).saturating_sub(self.blitz.delay)
+        });
+        (left.saturating_sub(used), used > left)
+    }
+}
+
+impl Game {
+    /// Set up the game for speed play with one call: both players get a clock, takebacks are
+    /// refused, forced turns are played automatically once rolled, and the cube can be left out.
+    /// The clocks run with the times given to [`Game::submit_at`], starting at the first action.
+    ///
+    /// ```
+    /// use backgammon::{Blitz, Game, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let blitz = Blitz {
+    ///     time: 60_000,
+    ///     delay: 5_000,
+    ///     no_cube: true,
+    /// };
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)
+    ///     .unwrap()
+    ///     .with_blitz(blitz);
+    /// assert!(game.submit_at(Player::Player0, PlayerAction::OfferDouble, 0).is_err());
+    /// game.submit_at(Player::Player0, PlayerAction::Roll, 0).unwrap();
+    /// assert_eq!(game.time_left(20_000), Some((45_000, 60_000)));
+    /// ```
+    pub fn with_blitz(mut self, blitz: Blitz) -> Self {
+        self.clock = Some(Clock {
+            blitz,
+            left: (blitz.time, blitz.time),
+            since: None,
+        });
+        self
+    }
+
+    /// Get the rules of speed play, if set, see [`Game::with_blitz`]
+    pub fn blitz(&self) -> Option<Blitz> {
+        self.clock.map(|clock| clock.blitz)
+    }
+
+    /// Get the time left of player 0 and player 1 at the given time, if the game is played with
+    /// clocks. Only the clock of the player to act runs.
+    pub fn time_left(&self, now: u64) -> Option<(u64, u64)> {
+        let clock = self.clock?;
+        let mut left = clock.left;
+        match self.to_act() {
+            Some(Player::Player0) => left.0 = clock.charged(Player::Player0, now).0,
+            Some(Player::Player1) => left.1 = clock.charged(Player::Player1, now).0,
+            _ => {}
+        }
+        Some(left)
+    }
+
+    /// Check the clock of the player to act at the given time. A player who ran out of time loses
+    /// the game at its current value and is returned.
+    pub fn poll_clock(&mut self, now: u64) -> Option<Player> {
+        let player = self.to_act()?;
+        if !self.clock?.charged(player, now).1 {
+            return None;
+        }
+        self.submit(player, PlayerAction::Resign).ok()?;
+        Some(player)
+    }
+
+    /// Get the player whose decision is awaited
+    pub(super) fn to_act(&self) -> Option<Player> {
+        match self.phase() {
+            GamePhase::OpeningRoll | GamePhase::Finished => None,
+            GamePhase::DoubleOffered => Some(self.who_plays.other()),
+            _ => Some(self.who_plays),
+        }
+    }
+
+    /// Check that a player acting at the given time has time left
+    pub(super) fn check_clock(&self, player: Player, now: u64) -> Result<(), Error> {
+        match self.clock {
+            Some(clock) if self.to_act() == Some(player) && clock.charged(player, now).1 => {
+                Err(Error::DeadlineExpired)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Charge the time of a decision from the last action to now to the player acting
+    pub(super) fn charge_clock(&mut self, player: Option<Player>, now: u64) {
+        let Some(clock) = self.clock.as_mut() else {
+            return;
+        };
+        match player {
+            Some(Player::Player0) => clock.left.0 = clock.charged(Player::Player0, now).0,
+            Some(Player::Player1) => clock.left.1 = clock.charged(Player::Player1, now).0,
+            _ => {}
+        }
+        clock.since = Some(now);
+    }
+
+    /// Play the turn rolled automatically in speed play, if it is forced
+    pub(super) fn play_forced(&mut self) -> Result<(), Error> {
+        if self.clock.is_none() {
+            return Ok(());
+        }
+        match self.forced_turn() {
+            Some(turn) => self.play(&turn),
+            None => Ok(()),
+        }
+    }
+
+    /// Check if the cube is left out in speed play
+    pub(super) fn is_cubeless(&self) -> bool {
+        self.clock.is_some_and(|clock| clock.blitz.no_cube)
+    }
+
+    /// Check if takebacks are refused in speed play
+    pub(super) fn refuses_takebacks(&self) -> bool {
+        self.clock.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, Roll};
+
+    /// Start a game with player 0 about to roll, with one minute for each player and a delay of
+    /// two seconds
+    fn blitz(board: Board, no_cube: bool) -> Result<Game, Error> {
+        let blitz = Blitz {
+            time: 60_000,
+            delay: 2_000,
+            no_cube,
+        };
+        Ok(Game::resume(board, Cube::default(), Player::Player0, false)?.with_blitz(blitz))
+    }
+
+    #[test]
+    fn clock() -> Result<(), Error> {
+        let mut game = blitz(Board::new(), false)?;
+        assert_eq!(game.time_left(1_000_000), Some((60_000, 60_000)));
+        game.submit_at(Player::Player0, PlayerAction::OfferDouble, 0)?;
+        game.submit_at(Player::Player1, PlayerAction::Take, 5_000)?;
+        assert_eq!(game.time_left(5_000), Some((60_000, 57_000)));
+        assert_eq!(game.time_left(10_000), Some((57_000, 57_000)));
+        assert_eq!(game.poll_clock(67_000), None);
+
+        let refused = game.submit_at(Player::Player0, PlayerAction::Roll, 70_000);
+        assert!(matches!(refused, Err(Error::DeadlineExpired)));
+        assert_eq!(game.phase(), GamePhase::Rolling);
+        assert_eq!(game.time_left(70_000), Some((0, 57_000)));
+        assert_eq!(game.poll_clock(70_000), Some(Player::Player0));
+        assert_eq!(game.result(), Some((Player::Player1, 2)));
+        assert_eq!(game.poll_clock(80_000), None);
+        Ok(())
+    }
+
+    #[test]
+    fn forced_turns() -> Result<(), Error> {
+        // player 0 on the bar against a closed board
+        let mut closed = [0; 24];
+        closed[..6].fill(2);
+        let board = Board::from_raw(([0; 24], 1, 14), (closed, 0, 3));
+        let mut game = blitz(board.clone(), false)?;
+        game.submit_at(Player::Player0, PlayerAction::Roll, 0)?;
+        assert_eq!(game.who_plays, Player::Player1);
+        assert_eq!(game.phase(), GamePhase::Rolling);
+        assert_eq!(game.history().len(), 2);
+
+        // without speed play, the player has to pass
+        let mut game = Game::resume(board, Cube::default(), Player::Player0, false)?;
+        let _ = game.roll()?;
+        assert_eq!(game.phase(), GamePhase::Moving);
+        Ok(())
+    }
+
+    #[test]
+    fn no_takeback_no_cube() -> Result<(), Error> {
+        let mut game = blitz(Board::new(), true)?;
+        let refused = game.submit(Player::Player0, PlayerAction::OfferDouble);
+        assert!(matches!(refused, Err(Error::DoublingNotPermitted)));
+        game.submit(Player::Player0, PlayerAction::Roll)?;
+        let turn = game.board.legal_turns(Player::Player0, game.dices.values)?[0].clone();
+        game.submit(Player::Player0, PlayerAction::PlayTurn(turn))?;
+        let refused = game.submit(Player::Player0, PlayerAction::RequestTakeback);
+        assert!(matches!(refused, Err(Error::TakebackInvalid)));
+        assert_eq!(game.blitz().map(|blitz| blitz.no_cube), Some(true));
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+use super::{Game, GamePhase};
+use crate::rules::{Board, Cube, Dices, Player, Rules};
+use crate::Error;
+
+/// Represents a game as FIBS board state, the `board:` line sent by the First Internet
+/// Backgammon Server to its clients.
+///
+/// The line describes the game from the perspective of one player, called "You" by FIBS, against
+/// the opponent. Lines are written with the player as `O` moving from the 24-point to the 1-point,
+/// and read in any color and direction. A double offered by the player is not part of the line,
+/// nor are the dices already played of a turn. A game not started yet has nobody on turn, and
+/// finished games can not be written.
+///
+/// ```
+/// use backgammon::FibsBoard;
+/// use backgammon::rules::Player;
+///
+/// let line = "board:You:someplayer:3:0:0:0:-2:0:0:0:0:5:0:3:0:0:0:-5:5:0:0:0:-3:0:-5:0:0:0:0:2:\
+///             0:1:6:2:0:0:1:1:1:0:1:-1:0:25:0:0:0:0:2:0:0:0";
+/// let fibs = FibsBoard::parse(line, Player::Player0).unwrap();
+/// assert_eq!(fibs.names.1, "someplayer");
+/// assert_eq!(fibs.game.who_plays, Player::Player0);
+/// assert_eq!(fibs.game.dices.values, (6, 2));
+/// assert_eq!(fibs.to_line().unwrap(), line);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FibsBoard {
+    /// The player the line is written for
+    pub player: Player,
+    /// The names of the player and the opponent
+    pub names: (String, String),
+    /// The score of the player and the opponent
+    pub score: (u64, u64),
+    /// The game, with the match length as its points
+    pub game: Game,
+}
+
+/// The amount of fields of a board state line, including the leading `board`
+const FIELDS: usize = 53;
+
+impl FibsBoard {
+    /// Parse a board state line, with the given player being "You"
+    pub fn parse(line: &str, player: Player) -> Result<FibsBoard, Error> {
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        let fields: Vec<&str> = line.trim().split(':').collect();
+        if fields.len() != FIELDS || fields[0] != "board" {
+            return Err(Error::BoardStateInvalid);
+        }
+        let numbers = fields[3..]
+            .iter()
+            .map(|field| field.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::BoardStateInvalid)?;
+        // the numbers, indexed by their field in the line
+        let n = |field: usize| numbers[field - 3];
+        let count = |value: i64| u8::try_from(value).map_err(|_| Error::BoardStateInvalid);
+        let score = |value: i64| u64::try_from(value).map_err(|_| Error::BoardStateInvalid);
+
+        let (colour, direction) = (n(41), n(42));
+        let home = match (colour, direction) {
+            (1 | -1, -1) => 0,
+            (1 | -1, 1) => 25,
+            _ => return Err(Error::BoardStateInvalid),
+        };
+        if n(43) != home || n(44) != 25 - home {
+            return Err(Error::BoardStateInvalid);
+        }
+
+        // the points of the line from the perspective of the player, fields 6 to 31
+        let point = |p: usize| n(if home == 0 { 6 + p } else { 31 - p });
+        let mut own = [0; 24];
+        let mut other = [0; 24];
+        for p in 1..=24 {
+            let checkers = point(p) * colour;
+            if checkers > 0 {
+                own[p - 1] = count(checkers)?;
+            } else {
+                other[24 - p] = count(-checkers)?;
+            }
+        }
+        let (own_bar, other_bar) = (count(n(47))?, count(n(48))?);
+        let (own_off, other_off) = (count(n(45))?, count(n(46))?);
+        let sides = ((own, own_bar, own_off), (other, other_bar, other_off));
+        let board = match player {
+            Player::Player0 => Board::from_raw(sides.0, sides.1),
+            _ => Board::from_raw(sides.1, sides.0),
+        };
+        board.validate().map_err(|_| Error::PositionInvalid)?;
+
+        let mut cube = Cube::default();
+        cube.set(score(n(37))?)?;
+        let (may_double, may_double_other) = (n(38) == 1, n(39) == 1);
+        cube.set_owner(match (may_double, may_double_other) {
+            (true, false) if cube.value() > 1 => player,
+            (false, true) if cube.value() > 1 => player.other(),
+            _ => Player::Nobody,
+        });
+        let crawford = n(51) == 1;
+        let rules = Rules {
+            points: u32::try_from(n(3)).map_err(|_| Error::BoardStateInvalid)?,
+            ..Rules::default()
+        };
+
+        let who_plays = match n(32) * colour {
+            0 => Player::Nobody,
+            1 => player,
+            -1 => player.other(),
+            _ => return Err(Error::BoardStateInvalid),
+        };
+        let dices = if who_plays == player {
+            (n(33), n(34))
+        } else {
+            (n(35), n(36))
+        };
+        let dices = (count(dices.0)?, count(dices.1)?);
+        let rolled = (1..=6).contains(&dices.0) && (1..=6).contains(&dices.1);
+        if !rolled && dices != (0, 0) {
+            return Err(Error::DiceInvalid);
+        }
+
+        let mut game = match who_plays {
+            Player::Nobody => Game {
+                board,
+                cube,
+                crawford,
+                ..Game::default()
+            },
+            _ => Game::resume(board, cube, who_plays, crawford)?,
+        };
+        game.rules = rules;
+        if n(40) == 1 && who_plays != Player::Nobody {
+            // the opponent of the player on turn has to respond to the double
+            game.who_plays = player.other();
+            game.cube_received = true;
+        } else if rolled && who_plays != Player::Nobody {
+            game.roll_first = false;
+            game.dices = Dices {
+                values: dices,
+                consumed: (false, false, dices.0 != dices.1, dices.0 != dices.1),
+            };
+        }
+
+        Ok(FibsBoard {
+            player,
+            names: (fields[1].to_string(), fields[2].to_string()),
+            score: (score(n(4))?, score(n(5))?),
+            game,
+        })
+    }
+
+    /// Write the board state line, with the player as `O` moving from the 24-point to the 1-point
+    pub fn to_line(&self) -> Result<String, Error> {
+        let (player, game) = (self.player, &self.game);
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        for name in [&self.names.0, &self.names.1] {
+            if name.contains(':') || name.contains('\n') {
+                return Err(Error::BoardStateInvalid);
+            }
+        }
+        let phase = game.phase();
+        if phase == GamePhase::Finished {
+            return Err(Error::GameEnded);
+        }
+
+        let (own, own_bar, own_off) = game.board.raw(player)?;
+        let (other, other_bar, other_off) = game.board.raw(player.other())?;
+        let mut points = [0i64; 26];
+        for field in 0..24 {
+            points[field + 1] = i64::from(own[field]) - i64::from(other[23 - field]);
+        }
+        points[0] = -i64::from(other_bar);
+        points[25] = i64::from(own_bar);
+
+        let turn = match phase {
+            GamePhase::OpeningRoll => 0,
+            _ if game.who_plays == player => 1,
+            _ => -1,
+        };
+        let dices = match phase {
+            GamePhase::Moving => game.dices.values,
+            _ => (0, 0),
+        };
+        let (own_dices, other_dices) = match turn {
+            1 => (dices, (0, 0)),
+            _ => ((0, 0), dices),
+        };
+        let may_double = |p: Player| {
+            let owner = game.cube.owner();
+            i64::from(!game.crawford && (owner == Player::Nobody || owner == p))
+        };
+        let was_doubled = phase == GamePhase::DoubleOffered && game.who_plays != player;
+        let can_move = match phase {
+            GamePhase::Moving if turn == 1 => (1..=6u8)
+                .map(|dice| {
+                    let mut dices = game.dices;
+                    let mut count = 0;
+                    while dices.consume(dice).is_ok() {
+                        count += 1;
+                    }
+                    count
+                })
+                .sum(),
+            _ => 0,
+        };
+
+        let mut line = format!(
+            "board:{}:{}:{}:{}:{}",
+            self.names.0, self.names.1, game.rules.points, self.score.0, self.score.1
+        );
+        let numbers = points.iter().copied().chain([
+            turn,
+            i64::from(own_dices.0),
+            i64::from(own_dices.1),
+            i64::from(other_dices.0),
+            i64::from(other_dices.1),
+            i64::try_from(game.cube.value()).map_err(|_| Error::CubeValueInvalid)?,
+            may_double(player),
+            may_double(player.other()),
+            i64::from(was_doubled),
+            1,
+            -1,
+            0,
+            25,
+            i64::from(own_off),
+            i64::from(other_off),
+            i64::from(own_bar),
+            i64::from(other_bar),
+            can_move,
+            0,
+            i64::from(game.crawford),
+            0,
+        ]);
+        for number in numbers {
+            line.push_str(&format!(":{}", number));
+        }
+        Ok(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerAction;
+
+    /// The example board state of the FIBS client documentation
+    const EXAMPLE: &str = "board:You:someplayer:3:0:0:0:-2:0:0:0:0:5:0:3:0:0:0:-5:5:0:0:0:-3:0:\
+                           -5:0:0:0:0:2:0:1:6:2:0:0:1:1:1:0:1:-1:0:25:0:0:0:0:2:0:0:0";
+
+    #[test]
+    fn example() -> Result<(), Error> {
+        let fibs = FibsBoard::parse(EXAMPLE, Player::Player1)?;
+        assert_eq!(fibs.game.board, Board::new());
+        assert_eq!(fibs.game.phase(), GamePhase::Moving);
+        assert_eq!(fibs.game.who_plays, Player::Player1);
+        assert_eq!(fibs.game.rules.points, 3);
+        assert_eq!(fibs.score, (0, 0));
+        assert_eq!(fibs.to_line()?, EXAMPLE);
+        Ok(())
+    }
+
+    #[test]
+    fn colour_and_direction() -> Result<(), Error> {
+        // the example seen as X moving from the 1-point to the 24-point
+        let numbers: Vec<&str> = EXAMPLE.split(':').collect();
+        let mut mirrored: Vec<String> = numbers.iter().map(|s| s.to_string()).collect();
+        for i in 0..26 {
+            let value: i64 = numbers[6 + i].parse().unwrap_or_default();
+            mirrored[31 - i] = (-value).to_string();
+        }
+        mirrored[32] = "-1".to_string();
+        mirrored[41] = "-1".to_string();
+        mirrored[42] = "1".to_string();
+        mirrored[43] = "25".to_string();
+        mirrored[44] = "0".to_string();
+        let fibs = FibsBoard::parse(&mirrored.join(":"), Player::Player0)?;
+        assert_eq!(fibs.to_line()?, EXAMPLE);
+        Ok(())
+    }
+
+    #[test]
+    fn double_and_bar() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set_bar(Player::Player1, 1)?;
+        let mut game = Game::resume(board, Cube::default(), Player::Player1, false)?;
+        game.submit(Player::Player1, PlayerAction::OfferDouble)?;
+        let fibs = FibsBoard {
+            player: Player::Player0,
+            names: ("alice".to_string(), "bob".to_string()),
+            score: (1, 2),
+            game,
+        };
+        let line = fibs.to_line()?;
+        let parsed = FibsBoard::parse(&line, Player::Player0)?;
+        assert_eq!(parsed.game.phase(), GamePhase::DoubleOffered);
+        assert_eq!(parsed.game.who_plays, Player::Player1);
+        assert_eq!(parsed.game.board.get().bar, (0, 1));
+        assert_eq!(parsed.score, (1, 2));
+        assert_eq!(parsed.to_line()?, line);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            FibsBoard::parse("board:You:x:3", Player::Player0),
+            Err(Error::BoardStateInvalid)
+        ));
+        let broken = EXAMPLE.replace(":0:1:6:2:", ":0:1:6:9:");
+        assert!(matches!(
+            FibsBoard::parse(&broken, Player::Player0),
+            Err(Error::DiceInvalid)
+        ));
+        let broken = EXAMPLE.replace(
+            "board:You:someplayer:3:0:0:0:-2:",
+            "board:You:someplayer:3:0:0:0:-3:",
+        );
+        assert!(matches!(
+            FibsBoard::parse(&broken, Player::Player0),
+            Err(Error::PositionInvalid)
+        ));
+        assert!(matches!(
+            FibsBoard::parse(EXAMPLE, Player::Nobody),
+            Err(Error::PlayerInvalid)
+        ));
+    }
+}
@@ -0,0 +1,988 @@
+use super::{Bonus, Game, GamePhase};
+use crate::rules::{Board, BoardDisplay, Cube, Dices, Hit, MoveChecker, Player, Turn};
+use crate::{Double, Error, GameRecord, InvariantError, PlayerAction};
+use serde::{Deserialize, Serialize};
+
+/// Represents an action recorded in the [`History`] of a game
+///
+/// Events serialize as objects tagged with a snake case `event` field, e.g.
+/// `{"event":"offer_double","player":"Player0"}`. Wrap them in [`Versioned`](crate::Versioned)
+/// to exchange them with clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// The dices were rolled, by nobody in case of the opening roll
+    Roll {
+        /// The player rolling
+        player: Player,
+        /// The dice values rolled
+        dices: (u8, u8),
+    },
+    /// A single checker was moved
+    Move {
+        /// The move of the checker
+        checker: MoveChecker,
+        /// The checker of the opponent sent to the bar, if any
+        hit: Option<Hit>,
+    },
+    /// A complete turn was played, see [`Game::play`]
+    Turn(Turn),
+    /// The player passed the rest of the turn, see [`Game::pass`]
+    Pass {
+        /// The player passing
+        player: Player,
+    },
+    /// The player offered the cube
+    OfferDouble {
+        /// The player offering
+        player: Player,
+    },
+    /// The player took the cube
+    Take {
+        /// The player taking
+        player: Player,
+    },
+    /// The player dropped the cube
+    Drop {
+        /// The player dropping
+        player: Player,
+    },
+    /// The player resigned
+    Resign {
+        /// The player resigning
+        player: Player,
+    },
+    /// The player chose the doubles of the bonus roll, see [`Game::choose_doubles`]
+    ChooseDoubles {
+        /// The player choosing
+        player: Player,
+        /// The dice value of the doubles
+        dice: u8,
+    },
+    /// The game was voided without a winner, see [`Game::void`]
+    Void,
+}
+
+/// Represents an action changing the cube, see [`CubeEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CubeAction {
+    /// The cube was doubled automatically by a tie of the opening roll
+    Automatic,
+    /// The player offered the cube
+    Offer,
+    /// The player took the cube
+    Take,
+    /// The player dropped the cube
+    Drop,
+}
+
+/// Represents a change of the cube recorded in the [`History`] of a game, e.g. to attribute cube
+/// errors in an analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CubeEvent {
+    /// The amount of turns played before the action
+    pub turn: usize,
+    /// The player acting, nobody for automatic doubles
+    pub player: Player,
+    /// The action changing the cube
+    pub action: CubeAction,
+    /// The value of the cube after the action
+    pub value: u64,
+}
+
+/// Represents one step of the replay of a game, see [`Game::replay_iter`]. Steps can be
+/// serialized, e.g. wrapped in [`Versioned`](crate::Versioned), to stream a game to clients.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReplayStep<'a> {
+    /// The action applied
+    pub event: &'a HistoryEvent,
+    /// The board before the action
+    pub before: BoardDisplay,
+    /// The board after the action
+    pub after: BoardDisplay,
+    /// The player on turn after the action, nobody before the opening roll is won
+    pub who_plays: Player,
+    /// The dices after the action
+    pub dices: (u8, u8),
+    /// The value of the cube after the action
+    pub cube_value: u64,
+}
+
+/// Represents the part of a game changed by actions, to be restored when undoing them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct State {
+    dices: Dices,
+    who_plays: Player,
+    board: Board,
+    cube: Cube,
+    since_crawford: u8,
+    roll_first: bool,
+    cube_received: bool,
+    result: Option<(Player, u64)>,
+    automatic_doubles: u8,
+    bonus: Bonus,
+}
+
+impl State {
+    /// Get the board of the state
+    pub(super) fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Get the player on turn in the state
+    pub(super) fn who_plays(&self) -> Player {
+        self.who_plays
+    }
+
+    /// Get the dices of the state
+    pub(super) fn dices(&self) -> &Dices {
+        &self.dices
+    }
+
+    /// Get the cube of the state
+    pub(super) fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    /// Get the winner and the points won in the state, once the game has ended
+    pub(super) fn result(&self) -> Option<(Player, u64)> {
+        self.result
+    }
+}
+
+/// Represents an action together with the state of the game before and after it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    event: HistoryEvent,
+    before: State,
+    after: State,
+    /// the time the action was submitted, see [`Game::submit_at`]
+    #[serde(default)]
+    at: Option<u64>,
+}
+
+/// Represents how long a player took for the decisions of a game or a match, in milliseconds,
+/// see [`History::timing`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TimingStats {
+    /// The amount of decisions timed
+    pub decisions: u64,
+    /// The total time taken
+    pub total: u64,
+    /// The longest time taken for one decision
+    pub longest: u64,
+}
+
+impl TimingStats {
+    /// Get the average time taken per decision, if any decision was timed
+    pub fn average(&self) -> Option<u64> {
+        self.total.checked_div(self.decisions)
+    }
+
+    /// Add the time taken for a decision
+    pub(crate) fn add(&mut self, time: u64) {
+        self.decisions += 1;
+        self.total = self.total.saturating_add(time);
+        self.longest = self.longest.max(time);
+    }
+
+    /// Add the decisions of other statistics, e.g. of another game of a match
+    pub(crate) fn merge(&mut self, other: &TimingStats) {
+        self.decisions += other.decisions;
+        self.total = self.total.saturating_add(other.total);
+        self.longest = self.longest.max(other.longest);
+    }
+}
+
+impl HistoryEvent {
+    /// Get the player deciding on the action, nobody for the opening roll and a voided game
+    fn player(&self) -> Player {
+        match self {
+            HistoryEvent::Move { checker, .. } => checker.player(),
+            HistoryEvent::Turn(turn) => turn.player(),
+            HistoryEvent::Roll { player, .. }
+            | HistoryEvent::Pass { player }
+            | HistoryEvent::OfferDouble { player }
+            | HistoryEvent::Take { player }
+            | HistoryEvent::Drop { player }
+            | HistoryEvent::Resign { player }
+            | HistoryEvent::ChooseDoubles { player, .. } => *player,
+            HistoryEvent::Void => Player::Nobody,
+        }
+    }
+}
+
+/// Represents the actions applied to a game, which can be undone and redone.
+///
+/// Each action keeps the complete state of the game before and after it, so undoing an action
+/// also returns checkers it hit from the bar. Applying a new action discards the actions undone
+/// before. The history is serialized with the game; deserializing checks that each action
+/// starts from the state the action before left.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedHistory")]
+pub struct History {
+    done: Vec<Entry>,
+    undone: Vec<Entry>,
+}
+
+/// Represents a deserialized history before its actions have been checked to form a chain
+#[derive(Deserialize)]
+struct UncheckedHistory {
+    done: Vec<Entry>,
+    undone: Vec<Entry>,
+}
+
+impl TryFrom<UncheckedHistory> for History {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedHistory) -> Result<Self, Self::Error> {
+        // the actions undone are redone from the last one, continuing the actions done
+        let chain = unchecked
+            .done
+            .iter()
+            .chain(unchecked.undone.iter().rev())
+            .collect::<Vec<_>>();
+        for (action, pair) in chain.windows(2).enumerate() {
+            if pair[0].after != pair[1].before {
+                return Err(InvariantError::HistoryChain { action: action + 1 });
+            }
+        }
+        Ok(History {
+            done: unchecked.done,
+            undone: unchecked.undone,
+        })
+    }
+}
+
+impl History {
+    /// Get the actions applied, in the order they were applied
+    pub fn events(&self) -> impl Iterator<Item = &HistoryEvent> {
+        self.done.iter().map(|entry| &entry.event)
+    }
+
+    /// Get the amount of actions applied
+    pub fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    /// Check if no actions have been applied
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty()
+    }
+
+    /// Check if there is an action to undo
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Check if there is an undone action to redo
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Get the time each action applied was submitted, see [`Game::submit_at`]
+    pub(super) fn submitted(&self) -> impl Iterator<Item = Option<u64>> + '_ {
+        self.done.iter().map(|entry| entry.at)
+    }
+
+    /// Get the state of the game after the first actions applied, none if no action was applied
+    pub(super) fn state_after(&self, actions: usize) -> Option<&State> {
+        match actions.checked_sub(1) {
+            Some(last) => self.done.get(last).map(|entry| &entry.after),
+            None => self.done.first().map(|entry| &entry.before),
+        }
+    }
+
+    /// Get the time each player took for the actions submitted with [`Game::submit_at`], in
+    /// the order they were applied. A decision takes from the previous action timed, by either
+    /// player, to its own, so the first action timed is not measured.
+    pub fn thinking_times(&self) -> Vec<(Player, u64)> {
+        let mut times = Vec::new();
+        let mut previous = None;
+        for entry in &self.done {
+            let Some(at) = entry.at else {
+                continue;
+            };
+            let player = entry.event.player();
+            if let (Some(since), true) = (previous, player != Player::Nobody) {
+                times.push((player, at.saturating_sub(since)));
+            }
+            previous = Some(at);
+        }
+        times
+    }
+
+    /// Get the statistics of the time player 0 and player 1 took for their decisions, see
+    /// [`History::thinking_times`]
+    ///
+    /// ```
+    /// use backgammon::{Game, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit_at(Player::Player0, PlayerAction::OfferDouble, 1_000).unwrap();
+    /// game.submit_at(Player::Player1, PlayerAction::Take, 9_000).unwrap();
+    /// game.submit_at(Player::Player0, PlayerAction::Roll, 10_500).unwrap();
+    /// let (player0, player1) = game.history().timing();
+    /// assert_eq!((player0.decisions, player0.total), (1, 1_500));
+    /// assert_eq!((player1.decisions, player1.longest), (1, 8_000));
+    /// ```
+    pub fn timing(&self) -> (TimingStats, TimingStats) {
+        let mut stats = (TimingStats::default(), TimingStats::default());
+        for (player, time) in self.thinking_times() {
+            match player {
+                Player::Player0 => stats.0.add(time),
+                _ => stats.1.add(time),
+            }
+        }
+        stats
+    }
+
+    /// Replay the actions applied, see [`Game::replay_iter`]
+    pub fn replay(&self) -> impl DoubleEndedIterator<Item = ReplayStep<'_>> + ExactSizeIterator {
+        self.done.iter().map(|entry| ReplayStep {
+            event: &entry.event,
+            before: entry.before.board.get(),
+            after: entry.after.board.get(),
+            who_plays: entry.after.who_plays,
+            dices: entry.after.dices.values,
+            cube_value: entry.after.cube.value(),
+        })
+    }
+
+    /// Get the changes of the cube, in the order they were applied.
+    ///
+    /// ```
+    /// use backgammon::{CubeAction, Game, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit(Player::Player0, PlayerAction::OfferDouble).unwrap();
+    /// game.submit(Player::Player1, PlayerAction::Take).unwrap();
+    /// let cube = game.history().cube_history();
+    /// assert_eq!(cube[1].action, CubeAction::Take);
+    /// assert_eq!((cube[1].player, cube[1].value), (Player::Player1, 2));
+    /// ```
+    pub fn cube_history(&self) -> Vec<CubeEvent> {
+        let mut turn = 0;
+        let mut events = Vec::new();
+        for entry in &self.done {
+            let (player, action) = match entry.event {
+                HistoryEvent::Roll { .. } => {
+                    if entry.after.automatic_doubles > entry.before.automatic_doubles {
+                        events.push(CubeEvent {
+                            turn,
+                            player: Player::Nobody,
+                            action: CubeAction::Automatic,
+                            value: entry.after.cube.value(),
+                        });
+                    }
+                    // a roll starts a turn, unless the opening roll is a tie
+                    if entry.after.who_plays != Player::Nobody {
+                        turn += 1;
+                    }
+                    continue;
+                }
+                HistoryEvent::OfferDouble { player } => (player, CubeAction::Offer),
+                HistoryEvent::Take { player } => (player, CubeAction::Take),
+                HistoryEvent::Drop { player } => (player, CubeAction::Drop),
+                _ => continue,
+            };
+            events.push(CubeEvent {
+                turn,
+                player,
+                action,
+                value: entry.after.cube.value(),
+            });
+        }
+        events
+    }
+}
+
+/// Represents the turn currently played checker by checker, while building a [`GameRecord`]
+struct Playing {
+    board: Board,
+    player: Player,
+    dices: (u8, u8),
+    moves: Vec<MoveChecker>,
+}
+
+impl Playing {
+    /// Get the turn played
+    fn turn(self) -> Result<Turn, Error> {
+        Turn::new(&self.board, self.player, self.dices, self.moves)
+    }
+}
+
+impl Game {
+    /// Get the history of the actions applied to the game
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Get the record of the game from its history, with the turns and the doubles, e.g. to
+    /// export it with [`GameRecord::to_sgf`].
+    ///
+    /// Checkers moved one by one are combined into the turn they form once the turn ended, a turn
+    /// still being played is left out. Returns an error if the game did not start from the
+    /// starting position.
+    ///
+    /// ```
+    /// use backgammon::{Game, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit(Player::Player0, PlayerAction::OfferDouble).unwrap();
+    /// game.submit(Player::Player1, PlayerAction::Drop).unwrap();
+    /// let record = game.to_record().unwrap();
+    /// assert_eq!((record.winner, record.points), (Player::Player0, 1));
+    /// assert!(record.to_sgf().unwrap().contains(";W[double]\n;B[drop]"));
+    /// ```
+    pub fn to_record(&self) -> Result<GameRecord, Error> {
+        let mut record = GameRecord::default();
+        let mut playing: Option<Playing> = None;
+        for entry in &self.history.done {
+            // any other action ends the turn played checker by checker
+            let continued = matches!(
+                entry.event,
+                HistoryEvent::Move { .. } | HistoryEvent::Pass { .. } | HistoryEvent::Turn(_)
+            );
+            if let Some(current) = playing.take_if(|_| !continued) {
+                match (current.turn(), &entry.event) {
+                    (Ok(turn), _) => record.turns.push(turn),
+                    // a game may end in the middle of a turn
+                    (Err(_), HistoryEvent::Resign { .. } | HistoryEvent::Void) => {}
+                    (Err(error), _) => return Err(error),
+                }
+            }
+            match &entry.event {
+                HistoryEvent::Roll { .. } if entry.after.who_plays != Player::Nobody => {
+                    let first = record.turns.is_empty() && record.doubles.is_empty();
+                    if first && entry.before.board != Board::new() {
+                        return Err(Error::PositionInvalid);
+                    }
+                    playing = Some(Playing {
+                        board: entry.after.board.clone(),
+                        player: entry.after.who_plays,
+                        dices: entry.after.dices.values,
+                        moves: Vec::new(),
+                    });
+                }
+                HistoryEvent::Move { checker, .. } => {
+                    if let Some(current) = playing.as_mut() {
+                        current.moves.push(checker.clone());
+                    }
+                }
+                HistoryEvent::Pass { .. } => {
+                    if let Some(current) = playing.take() {
+                        record.turns.push(current.turn()?);
+                    }
+                }
+                HistoryEvent::Turn(turn) => {
+                    playing = None;
+                    record.turns.push(turn.clone());
+                }
+                HistoryEvent::ChooseDoubles { player, dice } => {
+                    playing = Some(Playing {
+                        board: entry.after.board.clone(),
+                        player: *player,
+                        dices: (*dice, *dice),
+                        moves: Vec::new(),
+                    });
+                }
+                HistoryEvent::OfferDouble { player } => record.doubles.push(Double {
+                    turn: record.turns.len(),
+                    player: *player,
+                    taken: false,
+                }),
+                HistoryEvent::Take { .. } => {
+                    if let Some(double) = record.doubles.last_mut() {
+                        double.taken = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        // the last turn is complete if it ended the game
+        if let Some(current) = playing {
+            if self.result.is_some() || self.roll_first {
+                record.turns.push(current.turn()?);
+            }
+        }
+        if let Some((winner, points)) = self.result {
+            record.winner = winner;
+            record.points = points;
+        }
+        Ok(record)
+    }
+
+    /// Replay the game from its first action to the last one applied, e.g. for review tools
+    /// stepping through a finished game.
+    ///
+    /// Each step holds the action together with the board before and after it, so the steps can
+    /// be walked forward and backward without applying the actions again.
+    ///
+    /// ```
+    /// use backgammon::{Game, HistoryEvent, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit(Player::Player0, PlayerAction::OfferDouble).unwrap();
+    /// game.submit(Player::Player1, PlayerAction::Take).unwrap();
+    /// let steps: Vec<_> = game.replay_iter().collect();
+    /// assert_eq!(steps.len(), 2);
+    /// assert_eq!(*steps[1].event, HistoryEvent::Take { player: Player::Player1 });
+    /// assert_eq!(steps[1].cube_value, 2);
+    /// assert_eq!(game.replay_iter().next_back().unwrap().after, Board::new().get());
+    /// ```
+    pub fn replay_iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = ReplayStep<'_>> + ExactSizeIterator {
+        self.history.replay()
+    }
+
+    /// Undo the last action applied and return it, restoring the game to the state before.
+    ///
+    /// Undoing a roll allows to roll again, so applications may want to restrict undo to the
+    /// moves of a turn.
+    ///
+    /// ```
+    /// use backgammon::{Game, PlayerAction};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false).unwrap();
+    /// game.submit(Player::Player0, PlayerAction::OfferDouble).unwrap();
+    /// assert!(game.undo().is_some());
+    /// assert!(game.submit(Player::Player1, PlayerAction::Take).is_err());
+    /// assert!(game.redo().is_some());
+    /// assert!(game.submit(Player::Player1, PlayerAction::Take).is_ok());
+    /// ```
+    pub fn undo(&mut self) -> Option<HistoryEvent> {
+        self.takeback = None;
+        let entry = self.history.done.pop()?;
+        self.restore(entry.before.clone());
+        let event = entry.event.clone();
+        self.history.undone.push(entry);
+        Some(event)
+    }
+
+    /// Redo the last action undone and return it, see [`Game::undo`]
+    pub fn redo(&mut self) -> Option<HistoryEvent> {
+        self.takeback = None;
+        let entry = self.history.undone.pop()?;
+        self.restore(entry.after.clone());
+        let event = entry.event.clone();
+        self.history.done.push(entry);
+        Some(event)
+    }
+
+    /// Submit an action of a player at the given time, in milliseconds since any fixed instant,
+    /// e.g. the Unix epoch, see [`Game::submit`]. The time is recorded in the history, to tell
+    /// how long the players took for their decisions, see [`History::timing`].
+    pub fn submit_at(
+        &mut self,
+        player: Player,
+        action: PlayerAction,
+        now: u64,
+    ) -> Result<(), Error> {
+        self.check_clock(player, now)?;
+        let recorded = self.history.done.len();
+        let to_act = self.to_act();
+        self.submit(player, action)?;
+        self.charge_clock(to_act.filter(|&to_act| to_act == player), now);
+        for entry in self.history.done.iter_mut().skip(recorded) {
+            entry.at = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Check that the last action of the history left the game in its current state
+    pub(super) fn check_history(&self) -> Result<(), InvariantError> {
+        match self.history.done.last() {
+            Some(entry) if entry.after != self.state() => Err(InvariantError::HistoryChain {
+                action: self.history.done.len(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the state changed by actions
+    pub(super) fn state(&self) -> State {
+        State {
+            dices: self.dices,
+            who_plays: self.who_plays,
+            board: self.board.clone(),
+            cube: self.cube.clone(),
+            since_crawford: self.since_crawford,
+            roll_first: self.roll_first,
+            cube_received: self.cube_received,
+            result: self.result,
+            automatic_doubles: self.automatic_doubles,
+            bonus: self.bonus,
+        }
+    }
+
+    /// Restore the state changed by actions
+    pub(super) fn restore(&mut self, state: State) {
+        self.dices = state.dices;
+        self.who_plays = state.who_plays;
+        self.board = state.board;
+        self.cube = state.cube;
+        self.since_crawford = state.since_crawford;
+        self.roll_first = state.roll_first;
+        self.cube_received = state.cube_received;
+        self.result = state.result;
+        self.automatic_doubles = state.automatic_doubles;
+        self.bonus = state.bonus;
+    }
+
+    /// Get the player who played the last action, if it was part of a turn
+    fn last_played_by(&self) -> Option<Player> {
+        match &self.history.done.last()?.event {
+            HistoryEvent::Move { checker, .. } => Some(checker.player()),
+            HistoryEvent::Turn(turn) => Some(turn.player()),
+            HistoryEvent::Pass { player } => Some(*player),
+            _ => None,
+        }
+    }
+
+    /// Ask the opponent to take back the last turn of a player, which is possible until the
+    /// opponent acts
+    pub(super) fn request_takeback(&mut self, player: Player) -> Result<(), Error> {
+        if self.refuses_takebacks()
+            || self.phase() != GamePhase::Rolling
+            || self.who_plays != player.other()
+            || self.last_played_by() != Some(player)
+            || self.takeback.is_some()
+        {
+            return Err(Error::TakebackInvalid);
+        }
+        self.takeback = Some(player);
+        Ok(())
+    }
+
+    /// Answer the takeback requested by the opponent of a player. Accepting undoes the moves of
+    /// the last turn of the opponent, who then plays the same dices again.
+    pub(super) fn answer_takeback(&mut self, player: Player, accept: bool) -> Result<(), Error> {
+        let requester = player.other();
+        if self.takeback != Some(requester) {
+            return Err(Error::TakebackInvalid);
+        }
+        self.takeback = None;
+        if accept {
+            while self.last_played_by() == Some(requester) {
+                let _ = self.undo();
+            }
+            // the moves taken back can not be redone
+            self.history.undone.clear();
+        }
+        Ok(())
+    }
+
+    /// Record an action applied to the game in the given state before
+    pub(super) fn record(&mut self, event: HistoryEvent, before: State) {
+        let after = self.state();
+        self.takeback = None;
+        self.history.undone.clear();
+        self.history.done.push(Entry {
+            event,
+            before,
+            after,
+            at: None,
+        });
+    }
+
+    /// Record the move of a checker between two fields of the player moving, encoded as in
+    /// [`MoveChecker::to_bytes`]
+    pub(super) fn record_move(&mut self, player: Player, fields: [u8; 2], before: State) {
+        let Some(checker) = MoveChecker::from_bytes(player, fields) else {
+            return;
+        };
+        let bar = |board: &Board| board.raw(player.other()).map_or(0, |raw| raw.1);
+        let hit = match checker.to().point() {
+            Some(point) if bar(&self.board) > bar(&before.board) => Some(Hit {
+                player: player.other(),
+                point,
+            }),
+            _ => None,
+        };
+        self.record(HistoryEvent::Move { checker, hit }, before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Location, Move, Roll};
+    use crate::{Error, GamePhase, PlayerAction};
+
+    /// Get a game with player 0 to move the given dices
+    fn rolled(board: Board, values: (u8, u8)) -> Result<Game, Error> {
+        let mut game = Game::resume(board, Cube::default(), Player::Player0, false)?;
+        game.roll_first = false;
+        game.dices.values = values;
+        game.dices.consumed = (false, false, true, true);
+        Ok(game)
+    }
+
+    #[test]
+    fn replay() -> Result<(), Error> {
+        let mut game = rolled(Board::new(), (3, 1))?;
+        let _ = game.move_checker(Player::Player0, 3, 7)?;
+        let _ = game.move_checker(Player::Player0, 1, 5)?;
+        let _ = game.roll()?;
+        let steps: Vec<_> = game.replay_iter().collect();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].before, Board::new().get());
+        // each step starts from the board the previous one ended with
+        for pair in steps.windows(2) {
+            assert_eq!(pair[1].before, pair[0].after);
+        }
+        assert_eq!(steps[2].after, game.board.get());
+        assert_eq!(steps[1].who_plays, Player::Player1);
+        assert!(matches!(steps[2].event, HistoryEvent::Roll { .. }));
+
+        // undone actions are not replayed
+        let _ = game.undo();
+        assert_eq!(game.replay_iter().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn cube_history_and_record() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        let _ = game.roll()?;
+        let turn = game.board.legal_turns(Player::Player0, game.dices.values)?[0].clone();
+        game.play(&turn)?;
+        game.submit(Player::Player1, PlayerAction::OfferDouble)?;
+        game.submit(Player::Player0, PlayerAction::Take)?;
+        let _ = game.roll()?;
+        // player 1 plays the turn checker by checker
+        let turn = game.board.legal_turns(Player::Player1, game.dices.values)?[0].clone();
+        for m in turn.moves() {
+            let _ = match m.checker().from() {
+                Location::Point(point) => {
+                    let field = 24 - usize::from(point);
+                    game.move_checker(Player::Player1, m.dice(), field)?
+                }
+                _ => game.move_checker_from_bar(Player::Player1, m.dice())?,
+            };
+        }
+        game.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        game.submit(Player::Player1, PlayerAction::Drop)?;
+
+        let cube = game.history().cube_history();
+        let actions: Vec<(usize, Player, CubeAction, u64)> = cube
+            .iter()
+            .map(|event| (event.turn, event.player, event.action, event.value))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (1, Player::Player1, CubeAction::Offer, 1),
+                (1, Player::Player0, CubeAction::Take, 2),
+                (2, Player::Player0, CubeAction::Offer, 2),
+                (2, Player::Player1, CubeAction::Drop, 2),
+            ]
+        );
+
+        let record = game.to_record()?;
+        assert_eq!(record.turns.len(), 2);
+        assert_eq!(record.turns[1], turn);
+        assert_eq!(record.doubles.len(), 2);
+        assert!(record.doubles[0].taken && !record.doubles[1].taken);
+        assert_eq!((record.winner, record.points), (Player::Player0, 2));
+        assert_eq!(GameRecord::from_sgf(&record.to_sgf()?)?, record);
+        Ok(())
+    }
+
+    #[test]
+    fn record_of_resignation() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        let _ = game.roll()?;
+        game.submit(Player::Player0, PlayerAction::Resign)?;
+        let record = game.to_record()?;
+        assert!(record.turns.is_empty());
+        assert_eq!(record.winner, Player::Player1);
+        Ok(())
+    }
+
+    #[test]
+    fn record_of_resumed_position() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set(Player::Player0, 22, 1)?;
+        let mut game = Game::resume(board, Cube::default(), Player::Player0, false)?;
+        assert!(game.to_record()?.turns.is_empty());
+        let _ = game.roll()?;
+        assert!(matches!(game.to_record(), Err(Error::PositionInvalid)));
+        Ok(())
+    }
+
+    #[test]
+    fn undo_redo_roll() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        let _ = game.roll()?;
+        let dices = game.dices.values;
+        assert_eq!(
+            game.history().events().last(),
+            Some(&HistoryEvent::Roll {
+                player: Player::Player0,
+                dices
+            })
+        );
+        assert!(game.undo().is_some());
+        assert_eq!(game.phase(), GamePhase::Rolling);
+        assert!(game.undo().is_none());
+        assert!(game.redo().is_some());
+        assert_eq!(game.phase(), GamePhase::Moving);
+        assert_eq!(game.dices.values, dices);
+        assert!(game.redo().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn undo_hit() -> Result<(), Error> {
+        // player 1 has a blot on the 21-point, which player 0 hits from the 24-point
+        let mut fields0 = [0; 24];
+        fields0[23] = 15;
+        let mut fields1 = [0; 24];
+        fields1[3] = 1;
+        fields1[10] = 14;
+        let board = Board::from_raw((fields0, 0, 0), (fields1, 0, 0));
+        let mut game = rolled(board.clone(), (3, 1))?;
+
+        let _ = game.move_checker(Player::Player0, 3, 23)?;
+        assert_eq!(game.board.get().bar, (0, 1));
+        let checker = MoveChecker::new(Player::Player0, Location::Point(24), Location::Point(21))?;
+        let hit = Some(Hit {
+            player: Player::Player1,
+            point: 21,
+        });
+        assert_eq!(game.undo(), Some(HistoryEvent::Move { checker, hit }));
+        assert_eq!(game.board, board);
+        assert!(game.dices.is_available(3));
+
+        assert!(game.redo().is_some());
+        assert_eq!(game.board.get().bar, (0, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn new_action_discards_undone() -> Result<(), Error> {
+        let mut game = rolled(Board::new(), (3, 1))?;
+        let _ = game.move_checker(Player::Player0, 3, 7)?;
+        assert!(game.undo().is_some());
+        assert!(game.history().can_redo());
+        let _ = game.move_checker(Player::Player0, 1, 5)?;
+        assert!(!game.history().can_redo());
+        assert_eq!(game.history().len(), 1);
+
+        game.submit(Player::Player0, PlayerAction::Move { dice: 3, from: 7 })?;
+        assert_eq!(game.phase(), GamePhase::Rolling);
+        assert!(game.undo().is_some());
+        assert_eq!(game.who_plays, Player::Player0);
+        assert_eq!(game.phase(), GamePhase::Moving);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize() -> Result<(), Box<dyn std::error::Error>> {
+        let mut game = rolled(Board::new(), (3, 1))?;
+        let _ = game.move_checker(Player::Player0, 3, 7)?;
+        let _ = game.move_checker(Player::Player0, 1, 5)?;
+        game.submit(Player::Player1, PlayerAction::OfferDouble)?;
+        assert!(game.undo().is_some());
+
+        let json = serde_json::to_value(&game)?;
+        let mut stored: Game = serde_json::from_value(json.clone())?;
+        assert_eq!(stored.history().len(), 2);
+        assert_eq!(stored.to_record()?, game.to_record()?);
+        assert!(stored.redo().is_some());
+        assert_eq!(stored.phase(), GamePhase::DoubleOffered);
+        for _ in 0..3 {
+            assert!(stored.undo().is_some());
+        }
+        assert_eq!(stored.board, Board::new());
+
+        // the actions have to form a chain leading to the state of the game
+        let mut tampered = json.clone();
+        tampered["history"]["done"][0]["after"]["roll_first"] = true.into();
+        assert!(serde_json::from_value::<Game>(tampered).is_err());
+        let mut tampered = json.clone();
+        tampered["history"]["undone"][0]["before"]["cube_received"] = true.into();
+        assert!(serde_json::from_value::<Game>(tampered).is_err());
+        let mut tampered = json;
+        tampered["roll_first"] = false.into();
+        assert!(serde_json::from_value::<Game>(tampered).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn takeback() -> Result<(), Error> {
+        let mut game = rolled(Board::new(), (3, 1))?;
+        assert!(matches!(
+            game.submit(Player::Player0, PlayerAction::RequestTakeback),
+            Err(Error::TakebackInvalid)
+        ));
+        game.submit(Player::Player0, PlayerAction::Move { dice: 3, from: 7 })?;
+        game.submit(Player::Player0, PlayerAction::Move { dice: 1, from: 5 })?;
+        let after = game.board.clone();
+
+        // only the player who moved may ask, once, and the opponent answers
+        for (player, action) in [
+            (Player::Player1, PlayerAction::RequestTakeback),
+            (Player::Player0, PlayerAction::AcceptTakeback),
+        ] {
+            assert!(matches!(
+                game.submit(player, action),
+                Err(Error::TakebackInvalid)
+            ));
+        }
+        game.submit(Player::Player0, PlayerAction::RequestTakeback)?;
+        assert!(game
+            .submit(Player::Player0, PlayerAction::RequestTakeback)
+            .is_err());
+        assert!(game
+            .submit(Player::Player0, PlayerAction::AcceptTakeback)
+            .is_err());
+        game.submit(Player::Player1, PlayerAction::DeclineTakeback)?;
+        assert_eq!(game.takeback_requested(), None);
+        assert_eq!(game.board, after);
+
+        // the request lapses once the opponent rolls
+        game.submit(Player::Player0, PlayerAction::RequestTakeback)?;
+        game.submit(Player::Player1, PlayerAction::Roll)?;
+        assert_eq!(game.takeback_requested(), None);
+        assert!(game
+            .submit(Player::Player1, PlayerAction::AcceptTakeback)
+            .is_err());
+        assert!(game.undo().is_some());
+
+        game.submit(Player::Player0, PlayerAction::RequestTakeback)?;
+        game.submit(Player::Player1, PlayerAction::AcceptTakeback)?;
+        assert_eq!(game.board, Board::new());
+        assert_eq!(game.who_plays, Player::Player0);
+        assert_eq!(game.phase(), GamePhase::Moving);
+        assert!(game.dices.is_available(3) && game.dices.is_available(1));
+        assert!(!game.history().can_redo());
+        Ok(())
+    }
+
+    #[test]
+    fn undo_drop() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        game.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        game.submit(Player::Player1, PlayerAction::Drop)?;
+        assert!(game.result().is_some());
+        assert_eq!(
+            game.undo(),
+            Some(HistoryEvent::Drop {
+                player: Player::Player1
+            })
+        );
+        assert_eq!(game.result(), None);
+        assert_eq!(game.phase(), GamePhase::DoubleOffered);
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+use super::{Game, GamePhase};
+use crate::rules::{decode_base64, encode_base64};
+use crate::rules::{Board, Cube, Dices, Player, Rules};
+use crate::Error;
+
+/// The largest match length or score fitting into the 15 bits of a match ID
+const MAX_POINTS: u64 = 0x7fff;
+
+/// Game state of a match ID: no game started yet
+const NOT_STARTED: u128 = 0;
+/// Game state of a match ID: the game is being played
+const PLAYING: u128 = 1;
+/// Game state of a match ID: the game is over
+const OVER: u128 = 2;
+
+/// Map a player to its bit in the match ID
+fn player_bit(player: Player) -> u128 {
+    u128::from(player == Player::Player1)
+}
+
+/// Map a bit of the match ID to its player
+fn bit_player(bit: u128) -> Player {
+    if bit == 1 {
+        Player::Player1
+    } else {
+        Player::Player0
+    }
+}
+
+impl Game {
+    /// Get the GNU Backgammon Match ID of the game, with the given score of the match.
+    ///
+    /// The ID encodes the cube, the player on roll and the player on turn, the Crawford flag, a
+    /// pending double, the dices rolled, the match length taken from [`Rules::points`] and the
+    /// score. Together with [`Board::to_gnubg_position_id`] it describes the complete state of a
+    /// match in the form GNU Backgammon uses. The 66 bits are stored least significant bit first
+    /// in nine bytes, which are encoded as 12 characters of base64.
+    ///
+    /// Dices already consumed during the turn are not recorded.
+    ///
+    /// ```
+    /// use backgammon::Game;
+    ///
+    /// let id = Game::new().to_match_id((0, 0)).unwrap();
+    /// assert_eq!(id, "MADgAAAAAAAA");
+    /// ```
+    pub fn to_match_id(&self, score: (u64, u64)) -> Result<String, Error> {
+        let length = u64::from(self.rules.points);
+        if length > MAX_POINTS {
+            return Err(Error::MatchLengthInvalid);
+        }
+        if score.0 > MAX_POINTS || score.1 > MAX_POINTS {
+            return Err(Error::ScoreInvalid);
+        }
+        let cube = self.cube.value().trailing_zeros();
+        if cube > 15 {
+            return Err(Error::CubeValueInvalid);
+        }
+        let owner = match self.cube.owner() {
+            Player::Nobody => 3,
+            player => player_bit(player),
+        };
+
+        let phase = self.phase();
+        let (state, on_roll) = match phase {
+            GamePhase::OpeningRoll => (NOT_STARTED, Player::Player0),
+            GamePhase::Finished => (OVER, self.result.map_or(self.who_plays, |r| r.0)),
+            _ => (PLAYING, self.who_plays),
+        };
+        let (turn, double) = match phase {
+            GamePhase::DoubleOffered => (on_roll.other(), 1),
+            _ => (on_roll, 0),
+        };
+        let dices = match phase {
+            GamePhase::Moving => self.dices.values,
+            _ => (0, 0),
+        };
+
+        let fields = [
+            (u128::from(cube), 4),
+            (owner, 2),
+            (player_bit(on_roll), 1),
+            (u128::from(self.crawford), 1),
+            (state, 3),
+            (player_bit(turn), 1),
+            (double, 1),
+            (0, 2),
+            (u128::from(dices.0), 3),
+            (u128::from(dices.1), 3),
+            (u128::from(length), 15),
+            (u128::from(score.0), 15),
+            (u128::from(score.1), 15),
+        ];
+        let (bits, _) = fields
+            .iter()
+            .fold((0u128, 0), |(bits, shift), &(value, width)| {
+                (bits | value << shift, shift + width)
+            });
+        Ok(encode_base64(&bits.to_le_bytes()[..9]))
+    }
+
+    /// Create a game from a GNU Backgammon Match ID and the board of the game, see
+    /// [`Game::to_match_id`]. Returns the game and the score of the match.
+    ///
+    /// Only games not yet started or being played can be resumed, finished games are refused with
+    /// [`Error::GameEnded`]. The match length becomes [`Rules::points`], all other rules are left
+    /// at their defaults.
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    /// use backgammon::rules::Board;
+    ///
+    /// let (game, score) = Game::from_match_id("QYkqASAAIAAA", Board::new()).unwrap();
+    /// assert_eq!(game.phase(), GamePhase::Moving);
+    /// assert_eq!(game.dices.values, (5, 2));
+    /// assert_eq!(game.rules.points, 9);
+    /// assert_eq!(score, (2, 4));
+    /// ```
+    pub fn from_match_id(id: &str, board: Board) -> Result<(Game, (u64, u64)), Error> {
+        let key: [u8; 9] = decode_base64(id).ok_or(Error::MatchIdInvalid)?;
+        let mut bytes = [0u8; 16];
+        bytes[..9].copy_from_slice(&key);
+        let mut bits = u128::from_le_bytes(bytes);
+        let mut take = |width: u32| {
+            let value = bits & ((1 << width) - 1);
+            bits >>= width;
+            value
+        };
+
+        let cube_log = take(4) as u32;
+        let owner = take(2);
+        let on_roll = bit_player(take(1));
+        let crawford = take(1) == 1;
+        let state = take(3);
+        let turn = bit_player(take(1));
+        let double = take(1) == 1;
+        let resignation = take(2);
+        let dices = (take(3) as u8, take(3) as u8);
+        let length = take(15) as u32;
+        let score = (take(15) as u64, take(15) as u64);
+
+        // the game may also have ended by resignation or a dropped cube
+        if state > OVER + 2 || resignation != 0 || owner == 2 {
+            return Err(Error::MatchIdInvalid);
+        }
+        if state >= OVER {
+            return Err(Error::GameEnded);
+        }
+        if length > 0 && (score.0 >= u64::from(length) || score.1 >= u64::from(length)) {
+            return Err(Error::ScoreInvalid);
+        }
+        let rolled = (1..=6).contains(&dices.0) && (1..=6).contains(&dices.1);
+        if !rolled && dices != (0, 0) || rolled && double {
+            return Err(Error::MatchIdInvalid);
+        }
+        if turn != if double { on_roll.other() } else { on_roll } {
+            return Err(Error::MatchIdInvalid);
+        }
+
+        let mut cube = Cube::default();
+        cube.set(1 << cube_log)?;
+        cube.set_owner(match owner {
+            3 => Player::Nobody,
+            bit => bit_player(bit),
+        });
+        let rules = Rules {
+            points: length,
+            ..Rules::default()
+        };
+
+        if state == NOT_STARTED {
+            if rolled || double || crawford && cube != Cube::default() {
+                return Err(Error::MatchIdInvalid);
+            }
+            board.validate().map_err(|_| Error::PositionInvalid)?;
+            let game = Game {
+                rules,
+                board,
+                cube,
+                crawford,
+                ..Game::default()
+            };
+            return Ok((game, score));
+        }
+
+        let mut game = Game::resume(board, cube, on_roll, crawford)?;
+        game.rules = rules;
+        game.cube_received = double;
+        if rolled {
+            game.roll_first = false;
+            game.dices = Dices {
+                values: dices,
+                consumed: (false, false, dices.0 != dices.1, dices.0 != dices.1),
+            };
+        }
+        Ok((game, score))
+    }
+}
+
+impl Game {
+    /// Get the GNU Backgammon ID of the game, the Position ID of the board with the player on roll
+    /// and the Match ID joined by a colon, e.g. `4HPwATDgc/ABMA:MADgAAAAAAAA` at the start.
+    pub fn to_gnubg_id(&self, score: (u64, u64)) -> Result<String, Error> {
+        let on_roll = match self.who_plays {
+            Player::Nobody => Player::Player0,
+            player => player,
+        };
+        Ok(format!(
+            "{}:{}",
+            self.board.to_gnubg_position_id(on_roll)?,
+            self.to_match_id(score)?
+        ))
+    }
+
+    /// Create a game from a GNU Backgammon ID, see [`Game::to_gnubg_id`]. Returns the game and the
+    /// score of the match.
+    ///
+    /// ```
+    /// use backgammon::{Game, GamePhase};
+    ///
+    /// let (game, score) = Game::from_gnubg_id("4HPwATDgc/ABMA:QYkqASAAIAAA").unwrap();
+    /// assert_eq!(game.phase(), GamePhase::Moving);
+    /// assert_eq!(score, (2, 4));
+    /// ```
+    pub fn from_gnubg_id(id: &str) -> Result<(Game, (u64, u64)), Error> {
+        let (position, match_id) = id.split_once(':').ok_or(Error::MatchIdInvalid)?;
+        let key: [u8; 9] = decode_base64(match_id).ok_or(Error::MatchIdInvalid)?;
+        // the player on roll is the seventh bit of the match ID
+        let on_roll = bit_player(u128::from(key[0] >> 6 & 1));
+        let board = Board::from_gnubg_position_id(position, on_roll)?;
+        Game::from_match_id(match_id, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Roll;
+
+    #[test]
+    fn new_game() -> Result<(), Error> {
+        let game = Game::new();
+        let id = game.to_match_id((0, 0))?;
+        let (resumed, score) = Game::from_match_id(&id, Board::new())?;
+        assert_eq!(resumed.phase(), GamePhase::OpeningRoll);
+        assert_eq!(resumed.rules.points, 7);
+        assert_eq!(score, (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn manual_example() -> Result<(), Error> {
+        let (game, score) = Game::from_match_id("QYkqASAAIAAA", Board::new())?;
+        assert_eq!(game.cube.value(), 2);
+        assert_eq!(game.cube.owner(), Player::Player0);
+        assert_eq!(game.who_plays, Player::Player1);
+        assert!(!game.is_crawford());
+        assert_eq!(game.to_match_id(score)?, "QYkqASAAIAAA");
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        game.rules.points = 5;
+        let _ = game.roll()?;
+        let id = game.to_match_id((3, 1))?;
+        let (resumed, score) = Game::from_match_id(&id, game.board.clone())?;
+        assert_eq!(score, (3, 1));
+        assert_eq!(resumed.phase(), GamePhase::Moving);
+        assert_eq!(resumed.who_plays, game.who_plays);
+        assert_eq!(resumed.dices.values, game.dices.values);
+        assert_eq!(resumed.to_match_id(score)?, id);
+
+        let mut offered = Game::resume(Board::new(), Cube::default(), Player::Player1, false)?;
+        offered.submit(Player::Player1, crate::PlayerAction::OfferDouble)?;
+        let id = offered.to_match_id((0, 0))?;
+        let (resumed, _) = Game::from_match_id(&id, Board::new())?;
+        assert_eq!(resumed.phase(), GamePhase::DoubleOffered);
+        assert_eq!(resumed.who_plays, Player::Player1);
+        assert_eq!(resumed.to_match_id((0, 0))?, id);
+        Ok(())
+    }
+
+    #[test]
+    fn gnubg_id() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set(Player::Player1, 17, 1)?;
+        let game = Game::resume(board, Cube::default(), Player::Player1, false)?;
+        let id = game.to_gnubg_id((1, 0))?;
+        let (resumed, score) = Game::from_gnubg_id(&id)?;
+        assert_eq!(resumed.board, game.board);
+        assert_eq!(resumed.who_plays, Player::Player1);
+        assert_eq!(score, (1, 0));
+        assert_eq!(
+            Game::new().to_gnubg_id((0, 0))?,
+            "4HPwATDgc/ABMA:MADgAAAAAAAA"
+        );
+        assert!(matches!(
+            Game::from_gnubg_id("4HPwATDgc/ABMA"),
+            Err(Error::MatchIdInvalid)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        let board = Board::new();
+        assert!(matches!(
+            Game::from_match_id("QYkqASAAIAA", board.clone()),
+            Err(Error::MatchIdInvalid)
+        ));
+        assert!(matches!(
+            Game::from_match_id("QYkqASAAIAA!", board.clone()),
+            Err(Error::MatchIdInvalid)
+        ));
+        // score of 9 in a 9 point match
+        assert!(matches!(
+            Game::from_match_id("QYkqAZAAIAAA", board.clone()),
+            Err(Error::ScoreInvalid)
+        ));
+        let game = Game {
+            rules: Rules {
+                points: 40000,
+                ..Rules::default()
+            },
+            ..Game::default()
+        };
+        assert!(matches!(
+            game.to_match_id((0, 0)),
+            Err(Error::MatchLengthInvalid)
+        ));
+    }
+}
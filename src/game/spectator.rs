@@ -0,0 +1,219 @@
+use super::history::State;
+use super::{Game, HistoryEvent};
+use crate::rules::{BoardDisplay, Player};
+use serde::{Deserialize, Serialize};
+
+/// Represents how far spectators of a game are behind the players, see [`Spectators`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BroadcastDelay {
+    /// Spectators see every action once it is applied
+    #[default]
+    None,
+    /// Spectators see an action once the given amount of actions has been applied after it
+    Actions(usize),
+    /// Spectators see an action the given milliseconds after it was submitted with
+    /// [`Game::submit_at`]. Actions submitted without a time are seen as soon as the actions
+    /// before them.
+    Millis(u64),
+}
+
+/// Represents what spectators see of a game, see [`Game::with_spectators`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Spectators {
+    /// How far spectators are behind the players
+    pub delay: BroadcastDelay,
+    /// Whether the board, the dices and the checkers played are hidden, leaving the cube, the
+    /// player on turn and the result
+    pub hide_board: bool,
+    /// Whether offers not answered yet are hidden: a double offered and a takeback requested.
+    /// A resignation can't be pending, it ends the game at once.
+    pub hide_offers: bool,
+}
+
+/// Represents a game as seen by spectators, see [`Game::spectate`]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpectatorView {
+    /// The actions seen, in the order they were applied
+    pub events: Vec<HistoryEvent>,
+    /// The board, unless hidden
+    pub board: Option<BoardDisplay>,
+    /// The player on turn
+    pub who_plays: Player,
+    /// The dices rolled, zero if hidden
+    pub dices: (u8, u8),
+    /// The value of the cube
+    pub cube_value: u64,
+    /// The player asking to take back their last turn, see [`Game::takeback_requested`]
+    pub takeback_requested: Option<Player>,
+    /// The winner and the points won, once the game has ended
+    pub result: Option<(Player, u64)>,
+}
+
+impl Game {
+    /// Set what spectators see of the game, e.g. for tournament streaming with a broadcast
+    /// delay, see [`Game::spectate`]
+    ///
+    /// ```
+    /// use backgammon::{BroadcastDelay, Game, PlayerAction, Spectators};
+    /// use backgammon::rules::{Board, Cube, Player};
+    ///
+    /// let spectators = Spectators {
+    ///     delay: BroadcastDelay::Actions(1),
+    ///     ..Spectators::default()
+    /// };
+    /// let mut game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)
+    ///     .unwrap()
+    ///     .with_spectators(spectators);
+    /// game.submit(Player::Player0, PlayerAction::OfferDouble).unwrap();
+    /// game.submit(Player::Player1, PlayerAction::Take).unwrap();
+    /// let view = game.spectate(0);
+    /// assert_eq!(view.events.len(), 1);
+    /// assert_eq!(view.cube_value, 1);
+    /// ```
+    pub fn with_spectators(mut self, spectators: Spectators) -> Self {
+        self.spectators = spectators;
+        self
+    }
+
+    /// Get what spectators see of the game, see [`Game::with_spectators`]
+    pub fn spectators(&self) -> Spectators {
+        self.spectators
+    }
+
+    /// Get the game as seen by spectators at the given time, in milliseconds as given to
+    /// [`Game::submit_at`]. The time is only used for a [`BroadcastDelay::Millis`].
+    pub fn spectate(&self, now: u64) -> SpectatorView {
+        let applied = self.history.len();
+        let mut seen = match self.spectators.delay {
+            BroadcastDelay::None => applied,
+            BroadcastDelay::Actions(delay) => applied.saturating_sub(delay),
+            BroadcastDelay::Millis(delay) => self
+                .history
+                .submitted()
+                .take_while(|at| at.is_none_or(|at| at.saturating_add(delay) <= now))
+                .count(),
+        };
+        let events: Vec<_> = self.history.events().take(seen).collect();
+        if self.spectators.hide_offers
+            && matches!(events.last(), Some(HistoryEvent::OfferDouble { .. }))
+        {
+            seen -= 1;
+        }
+        let current = self.state();
+        let state: &State = self.history.state_after(seen).unwrap_or(&current);
+        let hide_board = self.spectators.hide_board;
+        let events = events
+            .into_iter()
+            .take(seen)
+            .filter(|event| {
+                !hide_board
+                    || !matches!(
+                        event,
+                        HistoryEvent::Roll { .. }
+                            | HistoryEvent::Move { .. }
+                            | HistoryEvent::Turn(_)
+                            | HistoryEvent::Pass { .. }
+                            | HistoryEvent::ChooseDoubles { .. }
+                    )
+            })
+            .cloned()
+            .collect();
+        let takeback_requested = match seen == applied && !self.spectators.hide_offers {
+            true => self.takeback,
+            false => None,
+        };
+        SpectatorView {
+            events,
+            board: (!hide_board).then(|| state.board().get()),
+            who_plays: state.who_plays(),
+            dices: match hide_board {
+                true => (0, 0),
+                false => state.dices().values,
+            },
+            cube_value: state.cube().value(),
+            takeback_requested,
+            result: state.result(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube};
+    use crate::{Error, PlayerAction};
+
+    /// Start a game with player 0 about to roll, seen by the given spectators
+    fn game(spectators: Spectators) -> Result<Game, Error> {
+        Ok(
+            Game::resume(Board::new(), Cube::default(), Player::Player0, false)?
+                .with_spectators(spectators),
+        )
+    }
+
+    #[test]
+    fn delay() -> Result<(), Error> {
+        let spectators = Spectators {
+            delay: BroadcastDelay::Millis(5_000),
+            ..Spectators::default()
+        };
+        let mut game = game(spectators)?;
+        let view = game.spectate(0);
+        assert!(view.events.is_empty());
+        assert_eq!(view.board, Some(Board::new().get()));
+
+        game.submit_at(Player::Player0, PlayerAction::OfferDouble, 1_000)?;
+        game.submit_at(Player::Player1, PlayerAction::Take, 3_000)?;
+        game.submit_at(Player::Player0, PlayerAction::Roll, 4_000)?;
+        let view = game.spectate(7_999);
+        assert_eq!(
+            view.events,
+            [HistoryEvent::OfferDouble {
+                player: Player::Player0
+            }]
+        );
+        assert_eq!((view.who_plays, view.cube_value), (Player::Player0, 1));
+        let view = game.spectate(8_000);
+        assert_eq!(view.events.len(), 2);
+        assert_eq!((view.who_plays, view.cube_value), (Player::Player0, 2));
+        assert_eq!(view.dices, (0, 0));
+        let view = game.spectate(9_000);
+        assert_eq!(view.dices, game.dices.values);
+        assert_eq!(
+            view,
+            game.with_spectators(Spectators::default()).spectate(0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hidden() -> Result<(), Error> {
+        let spectators = Spectators {
+            hide_board: true,
+            hide_offers: true,
+            ..Spectators::default()
+        };
+        let mut game = game(spectators)?;
+        game.submit(Player::Player0, PlayerAction::OfferDouble)?;
+        let view = game.spectate(0);
+        assert!(view.events.is_empty());
+        assert_eq!((view.who_plays, view.cube_value), (Player::Player0, 1));
+        assert_eq!(view.board, None);
+
+        game.submit(Player::Player1, PlayerAction::Take)?;
+        game.submit(Player::Player0, PlayerAction::Roll)?;
+        let turn = game.board.legal_turns(Player::Player0, game.dices.values)?[0].clone();
+        game.submit(Player::Player0, PlayerAction::PlayTurn(turn))?;
+        game.submit(Player::Player0, PlayerAction::RequestTakeback)?;
+        let view = game.spectate(0);
+        assert_eq!(view.events.len(), 2);
+        assert_eq!((view.dices, view.takeback_requested), ((0, 0), None));
+
+        let game = game.with_spectators(Spectators::default());
+        let view = game.spectate(0);
+        assert_eq!(view.events.len(), 4);
+        assert_eq!(view.takeback_requested, Some(Player::Player0));
+        assert_eq!(view.board, Some(game.board.get()));
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use crate::rules::{Board, Player, Turn};
+use crate::{Error, Game, GamePhase};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Represents a move listed by GNU Backgammon for a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    /// The move in the notation of GNU Backgammon, e.g. `8/5 6/5` or `bar/22*`
+    pub notation: String,
+    /// The equity of the player on roll after the move
+    pub equity: f64,
+}
+
+/// Represents the differences found between GNU Backgammon and this crate for a position, see
+/// [`GnubgHarness::compare`]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Comparison {
+    /// The legal turns of this crate GNU Backgammon does not list
+    pub missing: Vec<Turn>,
+    /// The moves listed by GNU Backgammon which are not legal in this crate
+    pub illegal: Vec<String>,
+    /// The turns whose equities differ by more than the tolerance, with the equity of GNU
+    /// Backgammon and the one evaluated here
+    pub equities: Vec<(Turn, f64, f64)>,
+}
+
+impl Comparison {
+    /// Check if no difference was found
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.illegal.is_empty() && self.equities.is_empty()
+    }
+}
+
+/// Cross-checks the legal moves and equities of this crate against an installed GNU Backgammon,
+/// for validating the rules on a large number of positions.
+///
+/// For each position the program is started in its text mode, `gnubg -t -q`, the position is set
+/// with [`Game::to_gnubg_id`] and all moves are listed with `hint`. The moves have to form the
+/// same set of resulting positions as the legal turns here, and the equities listed are compared
+/// with an evaluator given by the caller, e.g. the [`HypergammonSolver`](crate::HypergammonSolver).
+/// Commands run before each position, like the evaluation settings, are set with
+/// [`GnubgHarness::with_setup`].
+///
+/// The harness is only available with the `gnubg` feature.
+///
+/// ```no_run
+/// use backgammon::{Game, GnubgHarness};
+///
+/// // the opening roll of 3-1 for player 0
+/// let (game, score) = Game::from_gnubg_id("4HPwATDgc/ABMA:MIHlAAAAAAAA").unwrap();
+/// let harness = GnubgHarness::new("gnubg")
+///     .with_setup(vec!["set evaluation chequerplay evaluation plies 0".into()]);
+/// let comparison = harness.compare(&game, score, |_, _| None).unwrap();
+/// assert!(comparison.is_consistent());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GnubgHarness {
+    program: PathBuf,
+    setup: Vec<String>,
+    tolerance: f64,
+}
+
+impl GnubgHarness {
+    /// Create a harness running the given GNU Backgammon program, comparing equities with a
+    /// tolerance of 0.001
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        GnubgHarness {
+            program: program.into(),
+            setup: Vec::new(),
+            tolerance: 1e-3,
+        }
+    }
+
+    /// Set the commands run before each position
+    pub fn with_setup(mut self, commands: Vec<String>) -> Self {
+        self.setup = commands;
+        self
+    }
+
+    /// Set the largest difference of equities accepted
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Get the moves GNU Backgammon lists for the player on roll of a game with the dices rolled
+    /// and the given score of the match, in the order listed
+    pub fn hints(&self, game: &Game, score: (u64, u64)) -> io::Result<Vec<Hint>> {
+        if game.phase() != GamePhase::Moving {
+            return Err(invalid(Error::MoveInvalid));
+        }
+        let id = game.to_gnubg_id(score).map_err(invalid)?;
+        let mut script = String::from("new game\n");
+        for command in &self.setup {
+            script.push_str(command);
+            script.push('\n');
+        }
+        script.push_str(&format!("set gnubgid {id}\nhint 10000\n"));
+
+        let mut child = Command::new(&self.program)
+            .args(["-t", "-q"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // closing the input ends the program once the commands are run
+            stdin.write_all(script.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        Ok(parse_hints(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Compare the moves GNU Backgammon lists for the player on roll of a game with the dices
+    /// rolled against the legal turns of this crate. The equity of each turn is evaluated as the
+    /// negated equity of the opponent on roll after it, or the points won if it ends the game;
+    /// positions the evaluator returns `None` for are not compared.
+    pub fn compare(
+        &self,
+        game: &Game,
+        score: (u64, u64),
+        evaluate: impl Fn(&Board, Player) -> Option<f64>,
+    ) -> io::Result<Comparison> {
+        let hints = self.hints(game, score)?;
+        self.check(game, &hints, evaluate).map_err(invalid)
+    }
+
+    /// Compare the moves listed against the legal turns of the game
+    fn check(
+        &self,
+        game: &Game,
+        hints: &[Hint],
+        evaluate: impl Fn(&Board, Player) -> Option<f64>,
+    ) -> Result<Comparison, Error> {
+        let (player, dices) = (game.who_plays, game.dices.values);
+        let mut plays = game
+            .board
+            .generate_variant_plays(player, dices, game.variant())?;
+        let mut comparison = Comparison::default();
+        for hint in hints {
+            let listed = Turn::parse(&hint.notation, player, &game.board, dices)
+                .and_then(|turn| {
+                    let mut after = game.board.clone();
+                    turn.apply(&mut after)?;
+                    Ok(after)
+                })
+                .ok()
+                .and_then(|after| plays.iter().position(|(_, board)| *board == after));
+            let Some(index) = listed else {
+                comparison.illegal.push(hint.notation.clone());
+                continue;
+            };
+            let (turn, after) = plays.swap_remove(index);
+            let equity = match after.win_kind(player) {
+                Some(kind) => Some(kind.multiplier() as f64),
+                None => evaluate(&after, player.other()).map(|equity| -equity),
+            };
+            if let Some(equity) = equity {
+                if (equity - hint.equity).abs() > self.tolerance {
+                    comparison.equities.push((turn, hint.equity, equity));
+                }
+            }
+        }
+        comparison.missing = plays.into_iter().map(|(turn, _)| turn).collect();
+        Ok(comparison)
+    }
+}
+
+/// Parse the moves listed by the `hint` command, lines like
+/// `    1. Cubeful 0-ply    8/5 6/5                      Eq.: +0.172`
+fn parse_hints(output: &str) -> Vec<Hint> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (rank, rest) = line.trim_start().split_once(". ")?;
+            if rank.is_empty() || !rank.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let (play, equity) = rest.split_once("Eq.:")?;
+            let equity = equity.split_whitespace().next()?.parse().ok()?;
+            // the evaluation comes first, e.g. `Cubeful 2-ply` or `Rollout`
+            let words: Vec<&str> = play.split_whitespace().collect();
+            let start = words
+                .iter()
+                .position(|word| word.ends_with("-ply") || *word == "Rollout")?;
+            Some(Hint {
+                notation: words[start + 1..].join(" "),
+                equity,
+            })
+        })
+        .collect()
+}
+
+/// Turn an error into an error of invalid data
+fn invalid(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The output of `hint` for the opening roll of 3-1, cut to three moves
+    const OUTPUT: &str = "
+    1. Cubeful 0-ply    8/5 6/5                      Eq.: +0.172
+       0.551 0.174 0.008 - 0.449 0.124 0.005
+        0-ply cubeful prune [world class]
+    2. Cubeful 0-ply    13/10 24/23                  Eq.: -0.037 ( -0.209)
+       0.498 0.140 0.007 - 0.502 0.136 0.006
+        0-ply cubeful prune [world class]
+    3. Cubeful 0-ply    24/21 24/23                  Eq.: -0.074 ( -0.246)
+";
+
+    #[test]
+    fn hints() {
+        let hints = parse_hints(OUTPUT);
+        assert_eq!(hints.len(), 3);
+        assert_eq!(
+            hints[0],
+            Hint {
+                notation: "8/5 6/5".into(),
+                equity: 0.172
+            }
+        );
+        assert_eq!(hints[1].notation, "13/10 24/23");
+        assert_eq!(hints[1].equity, -0.037);
+        assert_eq!(hints[2].notation, "24/21 24/23");
+        assert!(parse_hints("Position ID: 4HPwATDgc/ABMA").is_empty());
+    }
+
+    #[test]
+    fn compare() -> Result<(), Error> {
+        let harness = GnubgHarness::new("gnubg");
+        // the opening roll of 3-1 for player 0
+        let (game, _) = Game::from_gnubg_id("4HPwATDgc/ABMA:MIHlAAAAAAAA")?;
+        let hints = parse_hints(OUTPUT);
+        let comparison = harness.check(&game, &hints, |_, _| None)?;
+        assert!(comparison.illegal.is_empty());
+        assert!(comparison.equities.is_empty());
+        // 16 plays of 3-1 from the start are not listed
+        assert_eq!(comparison.missing.len(), 16 - 3);
+
+        let comparison = harness.check(&game, &hints, |_, _| Some(0.2))?;
+        assert_eq!(comparison.equities.len(), 3);
+        assert_eq!(comparison.equities[1].1, -0.037);
+        assert_eq!(comparison.equities[1].2, -0.2);
+
+        let hints = [Hint {
+            notation: "8/4 6/5".into(),
+            equity: 0.0,
+        }];
+        let comparison = harness.check(&game, &hints, |_, _| None)?;
+        assert_eq!(comparison.illegal, ["8/4 6/5"]);
+        assert!(!comparison.is_consistent());
+
+        let harness = GnubgHarness::new("gnubg").with_tolerance(0.5);
+        let comparison = harness.check(&game, &parse_hints(OUTPUT), |_, _| Some(0.2))?;
+        assert_eq!(comparison.equities.len(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{Error, Game, SharedGame};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// Represents a registry of concurrently played games, keyed by ID
+///
+/// Each game is a [`SharedGame`] with its own lock, so actions on different games never wait for
+/// each other; the registry itself is only locked exclusively while games are added or removed.
+/// Games not accessed for a while can be expired, and the whole registry can be snapshotted and
+/// restored, e.g. when a server restarts.
+///
+/// ```
+/// use backgammon::{Game, GameHub};
+/// use std::time::Duration;
+///
+/// let hub = GameHub::new();
+/// let id = hub.create(Game::new()).unwrap();
+/// let game = hub.get(id).unwrap();
+/// game.write(|game| game.rules.points = 5);
+///
+/// let restored = GameHub::restore(hub.snapshot()).unwrap();
+/// assert_eq!(restored.get(id).unwrap().snapshot().rules.points, 5);
+///
+/// assert!(hub.expire_idle(Duration::from_secs(3600)).is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct GameHub {
+    games: RwLock<HashMap<u64, HubEntry>>,
+    next_id: AtomicU64,
+}
+
+/// Holds a game of the hub together with the time it was last accessed
+#[derive(Debug)]
+struct HubEntry {
+    game: SharedGame,
+    accessed: Mutex<Instant>,
+}
+
+impl HubEntry {
+    fn new(game: SharedGame) -> Self {
+        HubEntry {
+            game,
+            accessed: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self.accessed.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
+
+    fn idle(&self) -> Duration {
+        self.accessed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .elapsed()
+    }
+}
+
+impl GameHub {
+    /// Create an empty hub
+    pub fn new() -> Self {
+        GameHub::default()
+    }
+
+    /// Add a game to the hub, returning its ID. Fails with [`Error::GameIdUnavailable`] once the
+    /// IDs wrapped around to one still in use.
+    pub fn create(&self, game: Game) -> Result<u64, Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        match self
+            .games
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(id)
+        {
+            Entry::Occupied(_) => Err(Error::GameIdUnavailable),
+            Entry::Vacant(entry) => {
+                let _ = entry.insert(HubEntry::new(SharedGame::new(game)));
+                Ok(id)
+            }
+        }
+    }
+
+    /// Get a handle to a game, marking it as accessed
+    pub fn get(&self, id: u64) -> Option<SharedGame> {
+        let games = self.games.read().unwrap_or_else(PoisonError::into_inner);
+        let entry = games.get(&id)?;
+        entry.touch();
+        Some(entry.game.clone())
+    }
+
+    /// Remove a game from the hub. Handles obtained before remain usable.
+    pub fn remove(&self, id: u64) -> Option<SharedGame> {
+        self.games
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id)
+            .map(|entry| entry.game)
+    }
+
+    /// Get the amount of games in the hub
+    pub fn len(&self) -> usize {
+        self.games
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Check if the hub holds no games
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all games not accessed for at least `max_idle`, returning their IDs in ascending
+    /// order
+    pub fn expire_idle(&self, max_idle: Duration) -> Vec<u64> {
+        let mut games = self.games.write().unwrap_or_else(PoisonError::into_inner);
+        let mut expired: Vec<u64> = games
+            .iter()
+            .filter(|(_, entry)| entry.idle() >= max_idle)
+            .map(|(id, _)| *id)
+            .collect();
+        expired.sort_unstable();
+        for id in &expired {
+            let _ = games.remove(id);
+        }
+        expired
+    }
+
+    /// Get a copy of the current state of all games, keyed by ID
+    pub fn snapshot(&self) -> BTreeMap<u64, Game> {
+        self.games
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, entry)| (*id, entry.game.snapshot()))
+            .collect()
+    }
+
+    /// Create a hub from a snapshot. New games get IDs above all restored ones, so restoring a
+    /// game under the ID `u64::MAX` fails with [`Error::GameIdUnavailable`].
+    pub fn restore(snapshot: BTreeMap<u64, Game>) -> Result<Self, Error> {
+        let next_id = match snapshot.keys().next_back() {
+            Some(id) => id.checked_add(1).ok_or(Error::GameIdUnavailable)?,
+            None => 0,
+        };
+        Ok(GameHub {
+            games: RwLock::new(
+                snapshot
+                    .into_iter()
+                    .map(|(id, game)| (id, HubEntry::new(SharedGame::new(game))))
+                    .collect(),
+            ),
+            next_id: AtomicU64::new(next_id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Roll;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_create_remove() -> Result<(), Error> {
+        let hub = GameHub::new();
+        assert!(hub.is_empty());
+        let a = hub.create(Game::new())?;
+        let b = hub.create(Game::new())?;
+        assert_ne!(a, b);
+        assert_eq!(hub.len(), 2);
+
+        let game = hub.remove(a).unwrap();
+        game.write(|game| game.rules.points = 3);
+        assert!(hub.get(a).is_none());
+        assert!(hub.remove(a).is_none());
+        assert_eq!(hub.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_games() {
+        let hub = Arc::new(GameHub::new());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let hub = Arc::clone(&hub);
+                thread::spawn(move || {
+                    let id = hub.create(Game::new()).unwrap();
+                    hub.get(id).unwrap().write(|game| game.rules.points = 1);
+                    id
+                })
+            })
+            .collect();
+        let mut ids: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 8);
+        assert!(hub.snapshot().values().all(|game| game.rules.points == 1));
+    }
+
+    #[test]
+    fn test_expire_idle() -> Result<(), Error> {
+        let hub = GameHub::new();
+        let a = hub.create(Game::new())?;
+        let b = hub.create(Game::new())?;
+        assert!(hub.expire_idle(Duration::from_secs(3600)).is_empty());
+        assert_eq!(hub.expire_idle(Duration::ZERO), vec![a, b]);
+        assert!(hub.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore() -> Result<(), Box<dyn std::error::Error>> {
+        let hub = GameHub::new();
+        let _ = hub.create(Game::new())?;
+        let id = hub.create(Game::new())?;
+        hub.get(0).unwrap().write(|game| game.roll().map(|_| ()))?;
+        hub.get(id).unwrap().write(|game| game.rules.beaver = true);
+
+        let json = serde_json::to_string(&hub.snapshot())?;
+        let restored = GameHub::restore(serde_json::from_str(&json)?)?;
+        assert_eq!(restored.len(), 2);
+        assert!(restored.get(id).unwrap().read(|game| game.rules.beaver));
+        // the history of a restored game can be undone
+        assert!(restored.get(0).unwrap().write(|game| game.undo()).is_some());
+        assert_eq!(restored.create(Game::new())?, id + 1);
+
+        let last = BTreeMap::from([(u64::MAX, Game::new())]);
+        assert!(matches!(
+            GameHub::restore(last),
+            Err(Error::GameIdUnavailable)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_id_in_use() -> Result<(), Error> {
+        let hub = GameHub::restore(BTreeMap::from([(u64::MAX - 1, Game::new())]))?;
+        assert_eq!(hub.create(Game::new())?, u64::MAX);
+        // the IDs wrap around to 0, which is free
+        assert_eq!(hub.create(Game::new())?, 0);
+        hub.next_id.store(u64::MAX - 1, Ordering::Relaxed);
+        assert!(matches!(
+            hub.create(Game::new()),
+            Err(Error::GameIdUnavailable)
+        ));
+        assert_eq!(hub.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::rules::Location;
+use serde::{Deserialize, Serialize};
+
+/// Represents the geometry of a board rendered by a frontend, to map taps or clicks to the
+/// location they hit.
+///
+/// The board is laid out as the diagram of [`Board`](crate::rules::Board): the points 12 to 7
+/// and 6 to 1 form the top row from left to right with the bar in between, the points 13 to 24
+/// the bottom row, and the trays for the checkers borne off are on the right. Coordinates are
+/// in the units of the frontend, e.g. pixels, growing to the right and to the bottom.
+///
+/// ```
+/// use backgammon::BoardLayout;
+/// use backgammon::rules::Location;
+///
+/// // points 40 wide, the board 400 high, with the top left corner at (0, 0)
+/// let layout = BoardLayout::new(40.0, 400.0);
+/// assert_eq!(layout.pick(10.0, 10.0), Some(Location::Point(12)));
+/// assert_eq!(layout.pick(10.0, 390.0), Some(Location::Point(13)));
+/// assert_eq!(layout.pick(250.0, 200.0), Some(Location::Bar));
+/// assert_eq!(layout.pick(540.0, 10.0), Some(Location::Off));
+/// assert_eq!(layout.pick(-5.0, 10.0), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoardLayout {
+    /// The position of the left edge of the board
+    pub left: f32,
+    /// The position of the top edge of the board
+    pub top: f32,
+    /// The width of a point
+    pub point_width: f32,
+    /// The width of the bar
+    pub bar_width: f32,
+    /// The width of the trays, zero if the trays are not shown
+    pub tray_width: f32,
+    /// The height of the board, with the top and the bottom row each taking half of it
+    pub height: f32,
+    /// Whether the board is rendered from the other side of the table, see
+    /// [`BoardDisplay::flipped`](crate::rules::BoardDisplay::flipped)
+    pub flipped: bool,
+}
+
+impl BoardLayout {
+    /// Create the layout of a board with its top left corner at the origin, and the bar and the
+    /// trays as wide as a point
+    pub fn new(point_width: f32, height: f32) -> Self {
+        BoardLayout {
+            left: 0.0,
+            top: 0.0,
+            point_width,
+            bar_width: point_width,
+            tray_width: point_width,
+            height,
+            flipped: false,
+        }
+    }
+
+    /// Get the location at the given position, or `None` outside the board.
+    ///
+    /// Points are returned in absolute coordinates, see [`Location`]. Any position on the bar is
+    /// [`Location::Bar`] and any position on the trays [`Location::Off`], meaning the bar and the
+    /// off of the player moving: a frontend combines them with the player on turn to build a
+    /// [`MoveChecker`](crate::rules::MoveChecker).
+    pub fn pick(&self, x: f32, y: f32) -> Option<Location> {
+        let (x, y) = (x - self.left, y - self.top);
+        let half = 6.0 * self.point_width;
+        if !(0.0..self.height).contains(&y) || x < 0.0 {
+            return None;
+        }
+        let column = if x < half {
+            x / self.point_width
+        } else if x < half + self.bar_width {
+            return Some(Location::Bar);
+        } else if x < 2.0 * half + self.bar_width {
+            (x - self.bar_width) / self.point_width
+        } else if x < 2.0 * half + self.bar_width + self.tray_width {
+            return Some(Location::Off);
+        } else {
+            return None;
+        };
+        let column = (column as u8).min(11);
+        let point = if y < self.height / 2.0 {
+            12 - column
+        } else {
+            13 + column
+        };
+        Some(Location::Point(if self.flipped {
+            25 - point
+        } else {
+            point
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_points() {
+        let layout = BoardLayout {
+            left: 100.0,
+            top: 50.0,
+            ..BoardLayout::new(30.0, 300.0)
+        };
+        // the corners of the board
+        assert_eq!(layout.pick(101.0, 51.0), Some(Location::Point(12)));
+        assert_eq!(layout.pick(489.0, 51.0), Some(Location::Point(1)));
+        assert_eq!(layout.pick(101.0, 349.0), Some(Location::Point(13)));
+        assert_eq!(layout.pick(489.0, 349.0), Some(Location::Point(24)));
+        // next to the bar
+        assert_eq!(layout.pick(279.0, 100.0), Some(Location::Point(7)));
+        assert_eq!(layout.pick(311.0, 100.0), Some(Location::Point(6)));
+        assert_eq!(layout.pick(311.0, 250.0), Some(Location::Point(19)));
+        assert_eq!(layout.pick(295.0, 100.0), Some(Location::Bar));
+        assert_eq!(layout.pick(500.0, 300.0), Some(Location::Off));
+
+        for (x, y) in [(99.0, 100.0), (521.0, 100.0), (200.0, 49.0), (200.0, 350.0)] {
+            assert_eq!(layout.pick(x, y), None);
+        }
+    }
+
+    #[test]
+    fn pick_flipped() {
+        let layout = BoardLayout {
+            flipped: true,
+            tray_width: 0.0,
+            ..BoardLayout::new(40.0, 400.0)
+        };
+        assert_eq!(layout.pick(10.0, 10.0), Some(Location::Point(13)));
+        assert_eq!(layout.pick(510.0, 390.0), Some(Location::Point(1)));
+        assert_eq!(layout.pick(530.0, 10.0), None);
+    }
+}
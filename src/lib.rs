@@ -62,6 +62,10 @@
 //! should be easy to implement wrappers using this library in combination with some databases to
 //! store the game state.
 //!
+//! ## Thread Safety
+//! All types of this crate are `Send` and `Sync`. To share a game between threads, e.g. between
+//! the request handlers of a server, wrap it in a [`SharedGame`](`crate::SharedGame`).
+//!
 //! ## Discussions and Support
 //! Remember that the APIs are not stable yet. Any support is very welcome. Please open an
 //! [Issue](https://github.com/carlostrub/backgammon/issues) to discuss features or ask for help.
@@ -93,14 +97,87 @@
     variant_size_differences
 )] // be tough on code quality
 
+/// Implements the actions of a player
+mod action;
+pub use action::PlayerAction;
+/// Implements agents making decisions for a player
+mod agent;
+pub use agent::RaceCubeAgent;
+/// Implements the analyses of matches read from GNU Backgammon files
+mod analysis;
+pub use analysis::{AnnotatedGame, AnnotatedMatch, Annotation, Evaluation, Mark, MoveAnalysis};
+/// Implements the compact match transmission format
+mod compact;
+pub use compact::{CompactMatch, MatchDices};
+/// Implements loading of labeled position collections
+mod corpus;
+pub use corpus::{Corpus, CorpusEntry, Expectation};
+/// Implements games played by correspondence
+mod correspondence;
+pub use correspondence::{CorrespondenceEvent, CorrespondenceGame};
+/// Implements the binary training dataset format
+mod dataset;
+pub use dataset::{DatasetReader, DatasetWriter, TrainingRecord};
 /// Implements all possible Backgammon errors
 mod error;
-pub use error::Error;
+pub use error::{Error, InvariantError};
 /// Implements a Backgammon game
 mod game;
-pub use game::{Game, GameWithPlayerDices};
+pub use game::{
+    Blitz, BroadcastDelay, CubeAction, CubeEvent, FibsBoard, Game, GamePhase, GameWithPlayerDices,
+    History, HistoryEvent, ReplayStep, SpectatorView, Spectators, TimingStats,
+};
+/// Implements the cross-checking harness against GNU Backgammon
+#[cfg(feature = "gnubg")]
+mod harness;
+#[cfg(feature = "gnubg")]
+pub use harness::{Comparison, GnubgHarness, Hint};
+/// Implements a Backgammon game shared between threads
+mod shared;
+pub use shared::SharedGame;
+/// Implements a registry of concurrently played games
+mod hub;
+pub use hub::GameHub;
+/// Implements mapping positions on rendered boards to locations
+mod layout;
+pub use layout::BoardLayout;
 /// Implements a Backgammon match
 mod r#match;
-pub use r#match::Match;
+pub use r#match::{Handicap, Match, VoidPolicy};
+/// Implements message keys for translating the text produced by the crate
+mod message;
+pub use message::{Localize, Message, Translator};
+/// Implements the nomenclature of opening plays
+mod opening;
+pub use opening::opening_name;
+/// Implements queues of actions received from untrusted clients
+mod queue;
+pub use queue::{ActionQueue, QueuedAction};
+/// Implements game histories
+mod record;
+pub use record::{Double, GameRecord};
+/// Implements named save slots for games
+mod saves;
+pub use saves::{SaveMetadata, SaveSlots};
+/// Implements persistence backends for games
+mod store;
+pub use store::{FileStore, GameStore, MemoryStore};
+/// Implements the versioned message schema for clients
+mod schema;
+pub use schema::{Versioned, SCHEMA_VERSION};
+/// Implements games played against itself
+mod selfplay;
+pub use selfplay::SelfPlay;
+/// Implements the exact solver of Hypergammon
+mod solver;
+pub use solver::HypergammonSolver;
+/// Implements series of matches
+mod series;
+pub use series::{Series, SeriesStats};
+/// Implements SGF game records
+mod sgf;
+/// Implements score sheets of matches
+mod sheet;
+pub use sheet::{ScoreSheet, ScoreSheetRow};
 /// Implements the board, the dices, the cube, and all other Backgammon rules
 pub mod rules;
@@ -1,5 +1,6 @@
-use crate::game::Game;
-use crate::rules::{GameRules, MatchRules, Rules};
+use crate::game::{Game, TimingStats};
+use crate::rules::{GameRules, MatchRules, Player, Rules};
+use crate::Error;
 
 use std::fmt;
 
@@ -10,6 +11,34 @@ pub struct Match {
     pub rules: Rules,
     /// The games played in the match
     pub games: Vec<Game>,
+    /// The score at which the match was resumed, including points given as handicap
+    resumed_score: (u64, u64),
+    /// The handicap given to the weaker player
+    handicap: Option<(Player, Handicap)>,
+    /// How voided games are counted
+    void_policy: VoidPolicy,
+    /// The voided games which have been replayed
+    replayed: Vec<Game>,
+}
+
+/// Represents how a match counts voided games, see [`Match::with_void_policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoidPolicy {
+    /// The voided game is played again, as if it had never been started
+    #[default]
+    Replay,
+    /// The voided game counts as played without points, e.g. a voided Crawford game ends the
+    /// Crawford game
+    Skip,
+}
+
+/// Represents a compensation given to the weaker player of a match, see [`Match::with_handicap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handicap {
+    /// The player starts the match with the given points
+    Points(u64),
+    /// The player makes the first roll of every game instead of the opening roll
+    FirstMove,
 }
 
 // implement Display trait
@@ -37,6 +66,266 @@ impl Match {
     pub fn new() -> Self {
         Match::default()
     }
+
+    /// The commonly played match lengths
+    pub const PRESETS: [u32; 6] = [1, 3, 5, 7, 11, 25];
+
+    /// Start a new match over one of the commonly played lengths in [`Match::PRESETS`], with the
+    /// Crawford rule enabled:
+    /// ```
+    /// use backgammon::Match;
+    ///
+    /// let m = Match::preset(5).unwrap();
+    /// assert_eq!(m.rules.points, 5);
+    /// assert!(Match::preset(4).is_err());
+    /// ```
+    pub fn preset(points: u32) -> Result<Self, Error> {
+        if !Match::PRESETS.contains(&points) {
+            return Err(Error::MatchLengthInvalid);
+        }
+        Ok(Match::new().with_points(points).with_crawford())
+    }
+
+    /// Resume a match in progress from an external source, e.g. at a score of 5-3 in a 9 point
+    /// match with the current game given by [`Game::resume`]:
+    /// ```
+    /// use backgammon::{Game, Match};
+    /// use backgammon::rules::{Board, Cube, MatchRules, Player, Rules};
+    ///
+    /// let game = Game::resume(Board::new(), Cube::default(), Player::Player1, false).unwrap();
+    /// let m = Match::resume(Rules::default().with_points(9), (5, 3), game).unwrap();
+    /// assert_eq!(m.away_scores(), (4, 6));
+    /// ```
+    pub fn resume(rules: Rules, score: (u64, u64), mut game: Game) -> Result<Self, Error> {
+        let points = u64::from(rules.points);
+        if score.0 >= points || score.1 >= points {
+            return Err(Error::ScoreInvalid);
+        }
+        game.rules = rules;
+        Ok(Match {
+            rules,
+            games: vec![game],
+            resumed_score: score,
+            ..Match::default()
+        })
+    }
+
+    /// Give a handicap to the weaker player before the first game of the match.
+    ///
+    /// Points given are part of the score of the player from the start, they have to leave the
+    /// player more than one game away from winning the match. With the first move, the player
+    /// rolls first in every game of the match.
+    ///
+    /// ```
+    /// use backgammon::{Handicap, Match};
+    /// use backgammon::rules::Player;
+    ///
+    /// let m = Match::preset(7)
+    ///     .unwrap()
+    ///     .with_handicap(Player::Player1, Handicap::Points(2))
+    ///     .unwrap();
+    /// assert_eq!(m.score(), (0, 2));
+    ///
+    /// let mut m = Match::preset(7)
+    ///     .unwrap()
+    ///     .with_handicap(Player::Player1, Handicap::FirstMove)
+    ///     .unwrap();
+    /// assert_eq!(m.current_game().unwrap().who_plays, Player::Player1);
+    /// ```
+    pub fn with_handicap(mut self, player: Player, handicap: Handicap) -> Result<Self, Error> {
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        if !self.games.is_empty() || self.handicap.is_some() {
+            return Err(Error::GameStarted);
+        }
+        if let Handicap::Points(points) = handicap {
+            let score = match player {
+                Player::Player0 => &mut self.resumed_score.0,
+                _ => &mut self.resumed_score.1,
+            };
+            let total = score.checked_add(points).and_then(|s| s.checked_add(1));
+            if points == 0 || total.is_none_or(|t| t >= u64::from(self.rules.points)) {
+                return Err(Error::ScoreInvalid);
+            }
+            *score += points;
+        }
+        self.handicap = Some((player, handicap));
+        Ok(self)
+    }
+
+    /// Get the handicap given to the weaker player, if any
+    pub fn handicap(&self) -> Option<(Player, Handicap)> {
+        self.handicap
+    }
+
+    /// Set how voided games are counted, see [`Game::void`]. By default, voided games are
+    /// replayed.
+    ///
+    /// ```
+    /// use backgammon::{Match, VoidPolicy};
+    ///
+    /// let mut m = Match::preset(3).unwrap().with_void_policy(VoidPolicy::Replay);
+    /// m.current_game().unwrap().void().unwrap();
+    /// assert!(m.current_game().unwrap().result().is_none());
+    /// assert_eq!(m.games.len(), 1);
+    /// assert_eq!(m.replayed().len(), 1);
+    /// ```
+    pub fn with_void_policy(mut self, policy: VoidPolicy) -> Self {
+        self.void_policy = policy;
+        self
+    }
+
+    /// Get the voided games which have been replayed, in the order they were voided
+    pub fn replayed(&self) -> &[Game] {
+        &self.replayed
+    }
+
+    /// Get the game currently played, starting a new game once the previous one has ended.
+    ///
+    /// Returns an error once the match has a winner.
+    ///
+    /// ```
+    /// use backgammon::{Match, PlayerAction};
+    /// use backgammon::rules::{MatchRules, Player};
+    ///
+    /// let mut m = Match::new().with_points(1);
+    /// let game = m.current_game().unwrap();
+    /// while game.who_plays == Player::Nobody {
+    ///     game.submit(Player::Player0, PlayerAction::Roll).unwrap();
+    /// }
+    /// let loser = game.who_plays;
+    /// game.submit(loser, PlayerAction::Resign).unwrap();
+    ///
+    /// assert_eq!(m.winner(), Some(loser.other()));
+    /// assert!(m.current_game().is_err());
+    /// ```
+    pub fn current_game(&mut self) -> Result<&mut Game, Error> {
+        if self.winner().is_some() {
+            return Err(Error::GameEnded);
+        }
+        if self.void_policy == VoidPolicy::Replay && self.games.last().is_some_and(Game::is_void) {
+            self.replayed.extend(self.games.pop());
+        }
+        if self.games.last().is_none_or(|game| game.result().is_some()) {
+            let points = u64::from(self.rules.points);
+            let score = self.score();
+            let crawford =
+                self.is_crawford_possible() && (score.0 + 1 >= points || score.1 + 1 >= points);
+            let first = match self.handicap {
+                Some((player, Handicap::FirstMove)) => player,
+                _ => Player::Nobody,
+            };
+            self.games
+                .push(Game::for_match(self.rules, crawford, first));
+        }
+        self.games.last_mut().ok_or(Error::GameEnded)
+    }
+
+    /// Check if the game currently played is the Crawford game, i.e. the game after a player first
+    /// reached a score of points - 1, in which the cube can not be offered
+    pub fn is_crawford_game(&self) -> bool {
+        self.games
+            .last()
+            .is_some_and(|game| game.result().is_none() && game.is_crawford())
+    }
+
+    /// Get the winner of the match, once a player has reached the match length
+    pub fn winner(&self) -> Option<Player> {
+        let points = u64::from(self.rules.points);
+        let score = self.score();
+        if score.0 >= points {
+            Some(Player::Player0)
+        } else if score.1 >= points {
+            Some(Player::Player1)
+        } else {
+            None
+        }
+    }
+
+    /// Get the points won by each player in the finished games, including the score the match
+    /// was resumed at and points given as handicap
+    pub fn score(&self) -> (u64, u64) {
+        self.scores().last().copied().unwrap_or(self.resumed_score)
+    }
+
+    /// Get the points each player still needs to win the match
+    pub fn away_scores(&self) -> (u64, u64) {
+        let points = u64::from(self.rules.points);
+        let score = self.score();
+        (
+            points.saturating_sub(score.0),
+            points.saturating_sub(score.1),
+        )
+    }
+
+    /// Get the player leading the match, or `Player::Nobody` if the score is tied
+    pub fn leader(&self) -> Player {
+        let score = self.score();
+        match score.0.cmp(&score.1) {
+            std::cmp::Ordering::Greater => Player::Player0,
+            std::cmp::Ordering::Less => Player::Player1,
+            std::cmp::Ordering::Equal => Player::Nobody,
+        }
+    }
+
+    /// Check whether a Crawford game can still be played in this match, i.e. the Crawford rule is
+    /// enabled, the match is not over and no Crawford game has been started yet
+    pub fn is_crawford_possible(&self) -> bool {
+        if !self.rules.crawford || self.rules.points < 2 {
+            return false;
+        }
+        let points = u64::from(self.rules.points);
+        // a match resumed after a player reached points - 1 has already decided the Crawford game
+        let resumed = self.resumed_score;
+        if resumed.0 + 1 >= points || resumed.1 + 1 >= points {
+            return false;
+        }
+        let scores = self.scores();
+        if scores
+            .last()
+            .is_some_and(|score| score.0 >= points || score.1 >= points)
+        {
+            return false;
+        }
+        // the game following the one where a player first reached points - 1 is the Crawford game
+        match scores
+            .iter()
+            .position(|score| score.0 + 1 >= points || score.1 + 1 >= points)
+        {
+            Some(game) => self.games.len() == game + 1,
+            None => true,
+        }
+    }
+
+    /// Get the statistics of the time player 0 and player 1 took for their decisions in all the
+    /// games of the match, see [`History::timing`](crate::History::timing)
+    pub fn timing(&self) -> (TimingStats, TimingStats) {
+        let mut stats = (TimingStats::default(), TimingStats::default());
+        for game in &self.games {
+            let (player0, player1) = game.history().timing();
+            stats.0.merge(&player0);
+            stats.1.merge(&player1);
+        }
+        stats
+    }
+
+    /// Get the running score after each game of the match, skipping games without a result. A
+    /// voided game leaves the score unchanged.
+    fn scores(&self) -> Vec<(u64, u64)> {
+        let mut score = self.resumed_score;
+        let mut scores = Vec::new();
+        for game in &self.games {
+            match game.result() {
+                Some((Player::Player0, points)) => score.0 += points,
+                Some((Player::Player1, points)) => score.1 += points,
+                Some(_) => {}
+                None => continue,
+            }
+            scores.push(score);
+        }
+        scores
+    }
 }
 
 /// Implements SetRules for Match
@@ -84,6 +373,8 @@ impl GameRules for Match {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::{Board, Cube};
+    use crate::PlayerAction;
 
     #[test]
     fn test_default_match() {
@@ -125,6 +416,224 @@ mod tests {
         assert_eq!(m.rules.points, 3);
     }
 
+    /// Game won by a player with the given amount of points
+    fn won(player: Player, points: u64) -> Result<Game, Error> {
+        let mut game = Game::new();
+        while game.who_plays == Player::Nobody {
+            game.submit(Player::Player0, PlayerAction::Roll)?;
+        }
+        game.cube.set(points)?;
+        game.submit(player.other(), PlayerAction::Resign)?;
+        Ok(game)
+    }
+
+    #[test]
+    fn test_preset() -> Result<(), Error> {
+        for points in Match::PRESETS {
+            let m = Match::preset(points)?;
+            assert_eq!(m.rules.points, points);
+            assert!(m.rules.crawford);
+        }
+        assert!(Match::preset(0).is_err());
+        assert!(Match::preset(9).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_score() -> Result<(), Error> {
+        let mut m = Match::preset(5)?;
+        assert_eq!(m.score(), (0, 0));
+        assert_eq!(m.away_scores(), (5, 5));
+        assert_eq!(m.leader(), Player::Nobody);
+        assert!(m.is_crawford_possible());
+
+        m.games.push(won(Player::Player1, 2)?);
+        m.games.push(Game::new());
+        assert_eq!(m.score(), (0, 2));
+        assert_eq!(m.away_scores(), (5, 3));
+        assert_eq!(m.leader(), Player::Player1);
+
+        m.games.push(won(Player::Player0, 2)?);
+        assert_eq!(m.leader(), Player::Nobody);
+        Ok(())
+    }
+
+    #[test]
+    fn test_current_game() -> Result<(), Error> {
+        let mut m = Match::preset(3)?.with_beaver();
+        let game = m.current_game()?;
+        assert!(game.rules.beaver);
+        assert_eq!(game.rules.points, 3);
+        assert_eq!(m.games.len(), 1);
+        let _ = m.current_game()?;
+        assert_eq!(m.games.len(), 1);
+
+        m.games[0] = won(Player::Player1, 2)?;
+        let _ = m.current_game()?;
+        assert_eq!(m.games.len(), 2);
+        assert_eq!(m.winner(), None);
+
+        m.games[1] = won(Player::Player1, 1)?;
+        assert_eq!(m.winner(), Some(Player::Player1));
+        assert!(matches!(m.current_game(), Err(Error::GameEnded)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_crawford_game() -> Result<(), Error> {
+        let mut m = Match::preset(3)?;
+        let _ = m.current_game()?;
+        assert!(!m.is_crawford_game());
+        m.games[0] = won(Player::Player0, 2)?;
+
+        let game = m.current_game()?;
+        assert!(game.is_crawford());
+        while game.who_plays == Player::Nobody {
+            game.submit(Player::Player0, PlayerAction::Roll)?;
+        }
+        assert!(m.is_crawford_game());
+        assert!(!m.is_crawford_possible());
+
+        // no doubling in the Crawford game
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player1, true)?;
+        assert!(matches!(
+            game.submit(Player::Player1, PlayerAction::OfferDouble),
+            Err(Error::DoublingNotPermitted)
+        ));
+
+        // the game after the Crawford game allows doubling again
+        m.games[1] = won(Player::Player1, 1)?;
+        let game = m.current_game()?;
+        assert!(!game.is_crawford());
+        assert!(!m.is_crawford_game());
+
+        // without the Crawford rule, there is no Crawford game
+        let mut m = Match::preset(3)?;
+        m.rules.crawford = false;
+        m.games.push(won(Player::Player0, 2)?);
+        assert!(!m.current_game()?.is_crawford());
+        Ok(())
+    }
+
+    #[test]
+    fn test_void_policy() -> Result<(), Error> {
+        for (policy, crawford) in [(VoidPolicy::Replay, true), (VoidPolicy::Skip, false)] {
+            let mut m = Match::preset(3)?.with_void_policy(policy);
+            let _ = m.current_game()?;
+            m.games[0] = won(Player::Player0, 2)?;
+            let game = m.current_game()?;
+            assert!(game.is_crawford());
+            game.void()?;
+            assert_eq!(m.score(), (2, 0));
+            assert_eq!(m.winner(), None);
+
+            // a replayed Crawford game is the Crawford game again
+            let game = m.current_game()?;
+            assert_eq!(game.is_crawford(), crawford);
+            assert_eq!(m.games.len(), if crawford { 2 } else { 3 });
+            assert_eq!(m.replayed().len(), usize::from(crawford));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_crawford_possible() -> Result<(), Error> {
+        let mut m = Match::preset(3)?;
+        m.games.push(won(Player::Player0, 2)?);
+        // the next game is the Crawford game
+        assert!(m.is_crawford_possible());
+        m.games.push(Game::new());
+        assert!(!m.is_crawford_possible());
+
+        let mut m = Match::preset(3)?;
+        m.games.push(won(Player::Player0, 4)?);
+        assert_eq!(m.away_scores(), (0, 3));
+        assert!(!m.is_crawford_possible());
+
+        assert!(!Match::preset(1)?.is_crawford_possible());
+        let mut m = Match::new();
+        m.rules.crawford = false;
+        assert!(!m.is_crawford_possible());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume() -> Result<(), Error> {
+        let rules = Rules::default().with_points(9);
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        let mut m = Match::resume(rules, (5, 3), game)?;
+        assert_eq!(m.score(), (5, 3));
+        assert_eq!(m.leader(), Player::Player0);
+        assert!(m.is_crawford_possible());
+        assert_eq!(m.games[0].rules.points, 9);
+
+        m.games[0].submit(Player::Player0, PlayerAction::Resign)?;
+        assert_eq!(m.score(), (5, 4));
+
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player0, true)?;
+        let m = Match::resume(rules, (8, 3), game.clone())?;
+        assert!(!m.is_crawford_possible());
+        assert!(Match::resume(rules, (9, 3), game).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_handicap_points() -> Result<(), Error> {
+        let mut m = Match::preset(5)?.with_handicap(Player::Player1, Handicap::Points(2))?;
+        assert_eq!(m.handicap(), Some((Player::Player1, Handicap::Points(2))));
+        assert_eq!(m.score(), (0, 2));
+        assert_eq!(m.away_scores(), (5, 3));
+        assert_eq!(m.score_sheet().start, (0, 2));
+
+        // the handicap counts towards the Crawford game and the winner
+        m.games.push(won(Player::Player1, 2)?);
+        assert!(m.current_game()?.is_crawford());
+        m.games[1] = won(Player::Player1, 1)?;
+        assert_eq!(m.winner(), Some(Player::Player1));
+
+        assert!(matches!(
+            Match::preset(5)?.with_handicap(Player::Player0, Handicap::Points(4)),
+            Err(Error::ScoreInvalid)
+        ));
+        assert!(matches!(
+            Match::preset(5)?.with_handicap(Player::Player0, Handicap::Points(0)),
+            Err(Error::ScoreInvalid)
+        ));
+        assert!(matches!(
+            Match::preset(5)?.with_handicap(Player::Player0, Handicap::Points(u64::MAX)),
+            Err(Error::ScoreInvalid)
+        ));
+        assert!(matches!(
+            Match::preset(5)?.with_handicap(Player::Nobody, Handicap::FirstMove),
+            Err(Error::PlayerInvalid)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_handicap_first_move() -> Result<(), Error> {
+        let mut m = Match::preset(3)?.with_handicap(Player::Player0, Handicap::FirstMove)?;
+        assert_eq!(m.score(), (0, 0));
+        let game = m.current_game()?;
+        assert_eq!(game.phase(), crate::GamePhase::Rolling);
+        assert!(matches!(
+            game.submit(Player::Player1, PlayerAction::Roll),
+            Err(Error::NotYourTurn)
+        ));
+        game.submit(Player::Player0, PlayerAction::Roll)?;
+        assert_eq!(game.who_plays, Player::Player0);
+
+        // the handicap applies to every game of the match
+        m.games[0] = won(Player::Player1, 1)?;
+        assert_eq!(m.current_game()?.who_plays, Player::Player0);
+
+        assert!(matches!(
+            m.with_handicap(Player::Player1, Handicap::Points(1)),
+            Err(Error::GameStarted)
+        ));
+        Ok(())
+    }
+
     // test Display trait
     #[test]
     fn test_display() {
@@ -0,0 +1,300 @@
+use crate::rules::Player;
+use crate::{Error, HistoryEvent, InvariantError};
+use std::fmt;
+
+/// Represents a human-readable text produced by the crate as a stable key with named arguments,
+/// so clients can show the text in their own language, see [`Localize`].
+///
+/// Keys are dot-separated, e.g. `error.not_your_turn` or `event.roll`, and never change once
+/// released. Arguments are already rendered as text, e.g. players as `Player 0` and dices as
+/// `6-5`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// The key of the text
+    pub key: &'static str,
+    /// The arguments of the text, by name
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    /// Create a message without arguments
+    pub fn new(key: &'static str) -> Self {
+        Message {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    /// Add a named argument to the message
+    pub fn with_arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    /// Get the value of an argument
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Trait for translating messages into the language of a client
+pub trait Translator {
+    /// Get the text of a message, or `None` if there is no translation for it
+    fn translate(&self, message: &Message) -> Option<String>;
+}
+
+/// Trait for values rendered as human-readable text, which is English through
+/// [`Display`](fmt::Display) and can be translated through its [`Message`].
+///
+/// ```
+/// use backgammon::{Error, Localize, Message, Translator};
+///
+/// struct German;
+///
+/// impl Translator for German {
+///     fn translate(&self, message: &Message) -> Option<String> {
+///         match message.key {
+///             "error.not_your_turn" => Some("Du bist nicht am Zug".to_string()),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Error::NotYourTurn.localize(&German), "Du bist nicht am Zug");
+/// assert_eq!(Error::RollFirst.localize(&German), "Roll first");
+/// ```
+pub trait Localize: fmt::Display {
+    /// Get the message of the text
+    fn message(&self) -> Message;
+
+    /// Get the text translated, falling back to English if the translator has no translation
+    fn localize(&self, translator: &dyn Translator) -> String {
+        translator
+            .translate(&self.message())
+            .unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl Localize for Error {
+    fn message(&self) -> Message {
+        Message::new(match self {
+            Error::GameStarted => "error.game_started",
+            Error::GameEnded => "error.game_ended",
+            Error::CubeReceived => "error.cube_received",
+            Error::DoublingNotPermitted => "error.doubling_not_permitted",
+            Error::CubeValueInvalid => "error.cube_value_invalid",
+            Error::PlayerInvalid => "error.player_invalid",
+            Error::FieldBlocked => "error.field_blocked",
+            Error::FieldInvalid => "error.field_invalid",
+            Error::NotYourTurn => "error.not_your_turn",
+            Error::MoveInvalid => "error.move_invalid",
+            Error::MoveInvalidBar => "error.move_invalid_bar",
+            Error::MoveFirst => "error.move_first",
+            Error::RollFirst => "error.roll_first",
+            Error::DiceInvalid => "error.dice_invalid",
+            Error::DiceAlreadyConsumed => "error.dice_already_consumed",
+            Error::CorpusInvalid => "error.corpus_invalid",
+            Error::DatasetInvalid => "error.dataset_invalid",
+            Error::MatchLengthInvalid => "error.match_length_invalid",
+            Error::ActionQueueFull => "error.action_queue_full",
+            Error::PositionInvalid => "error.position_invalid",
+            Error::ScoreInvalid => "error.score_invalid",
+            Error::MatchIdInvalid => "error.match_id_invalid",
+            Error::NotationInvalid => "error.notation_invalid",
+            Error::SaveNameInvalid => "error.save_name_invalid",
+            Error::SaveInvalid => "error.save_invalid",
+            Error::BoardStateInvalid => "error.board_state_invalid",
+            Error::SgfInvalid => "error.sgf_invalid",
+            Error::DeadlineExpired => "error.deadline_expired",
+            Error::AuditFailed => "error.audit_failed",
+            Error::CompactMatchInvalid => "error.compact_match_invalid",
+            Error::ChooseDoublesFirst => "error.choose_doubles_first",
+            Error::GameIdUnavailable => "error.game_id_unavailable",
+            Error::TakebackInvalid => "error.takeback_invalid",
+            Error::RateLimited => "error.rate_limited",
+        })
+    }
+}
+
+impl Localize for InvariantError {
+    fn message(&self) -> Message {
+        match *self {
+            InvariantError::CheckerCount {
+                player,
+                count,
+                expected,
+            } => Message::new("invariant.checker_count")
+                .with_arg("player", player)
+                .with_arg("count", count)
+                .with_arg("expected", expected),
+            InvariantError::FieldShared { field } => {
+                Message::new("invariant.field_shared").with_arg("field", field)
+            }
+            InvariantError::CubeValue { exponent } => {
+                Message::new("invariant.cube_value").with_arg("exponent", exponent)
+            }
+            InvariantError::DiceValue { values } => Message::new("invariant.dice_value")
+                .with_arg("dices", format!("{}-{}", values.0, values.1)),
+            InvariantError::SchemaVersion { version } => {
+                Message::new("invariant.schema_version").with_arg("version", version)
+            }
+            InvariantError::StartPosition { variant } => Message::new("invariant.start_position")
+                .with_arg("variant", format!("{:?}", variant)),
+            InvariantError::HistoryChain { action } => {
+                Message::new("invariant.history_chain").with_arg("action", action)
+            }
+        }
+    }
+}
+
+/// Describes the event in English, e.g. `Player 0 rolls 6-5` or `Player 1 plays 24/18 13/11`
+impl fmt::Display for HistoryEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryEvent::Roll {
+                player: Player::Nobody,
+                dices,
+            } => write!(f, "Opening roll {}-{}", dices.0, dices.1),
+            HistoryEvent::Roll { player, dices } => {
+                write!(f, "{} rolls {}-{}", player, dices.0, dices.1)
+            }
+            HistoryEvent::Move { checker, hit } => {
+                let marker = if hit.is_some() { "*" } else { "" };
+                write!(f, "{} moves {}{}", checker.player(), checker, marker)
+            }
+            HistoryEvent::Turn(turn) if turn.is_empty() => {
+                write!(f, "{} can not move", turn.player())
+            }
+            HistoryEvent::Turn(turn) => write!(f, "{} plays {}", turn.player(), turn),
+            HistoryEvent::Pass { player } => write!(f, "{} passes", player),
+            HistoryEvent::OfferDouble { player } => write!(f, "{} doubles", player),
+            HistoryEvent::Take { player } => write!(f, "{} takes", player),
+            HistoryEvent::Drop { player } => write!(f, "{} drops", player),
+            HistoryEvent::Resign { player } => write!(f, "{} resigns", player),
+            HistoryEvent::ChooseDoubles { player, dice } => {
+                write!(f, "{} chooses {}-{}", player, dice, dice)
+            }
+            HistoryEvent::Void => write!(f, "Game voided"),
+        }
+    }
+}
+
+impl Localize for HistoryEvent {
+    fn message(&self) -> Message {
+        let dices = |dices: &(u8, u8)| format!("{}-{}", dices.0, dices.1);
+        match self {
+            HistoryEvent::Roll {
+                player: Player::Nobody,
+                dices: rolled,
+            } => Message::new("event.opening_roll").with_arg("dices", dices(rolled)),
+            HistoryEvent::Roll {
+                player,
+                dices: rolled,
+            } => Message::new("event.roll")
+                .with_arg("player", player)
+                .with_arg("dices", dices(rolled)),
+            HistoryEvent::Move { checker, hit } => {
+                let key = if hit.is_some() {
+                    "event.move_hit"
+                } else {
+                    "event.move"
+                };
+                Message::new(key)
+                    .with_arg("player", checker.player())
+                    .with_arg("move", checker)
+            }
+            HistoryEvent::Turn(turn) if turn.is_empty() => {
+                Message::new("event.no_move").with_arg("player", turn.player())
+            }
+            HistoryEvent::Turn(turn) => Message::new("event.turn")
+                .with_arg("player", turn.player())
+                .with_arg("turn", turn),
+            HistoryEvent::Pass { player } => Message::new("event.pass").with_arg("player", player),
+            HistoryEvent::OfferDouble { player } => {
+                Message::new("event.double").with_arg("player", player)
+            }
+            HistoryEvent::Take { player } => Message::new("event.take").with_arg("player", player),
+            HistoryEvent::Drop { player } => Message::new("event.drop").with_arg("player", player),
+            HistoryEvent::Resign { player } => {
+                Message::new("event.resign").with_arg("player", player)
+            }
+            HistoryEvent::ChooseDoubles { player, dice } => Message::new("event.choose_doubles")
+                .with_arg("player", player)
+                .with_arg("dices", dices(&(*dice, *dice))),
+            HistoryEvent::Void => Message::new("event.void"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Location, MoveChecker};
+
+    /// Translates into a pseudo language, keeping the key and the arguments visible
+    struct Keys;
+
+    impl Translator for Keys {
+        fn translate(&self, message: &Message) -> Option<String> {
+            if message.key == "error.move_first" {
+                return None;
+            }
+            let args: Vec<String> = message
+                .args
+                .iter()
+                .map(|(_, value)| value.clone())
+                .collect();
+            Some(format!("{}({})", message.key, args.join(", ")))
+        }
+    }
+
+    #[test]
+    fn localize() -> Result<(), Error> {
+        assert_eq!(Error::FieldBlocked.localize(&Keys), "error.field_blocked()");
+        assert_eq!(Error::MoveFirst.localize(&Keys), "Move first");
+
+        let invariant = InvariantError::CheckerCount {
+            player: Player::Player1,
+            count: 14,
+            expected: 15,
+        };
+        assert_eq!(invariant.message().arg("count"), Some("14"));
+        assert_eq!(
+            invariant.localize(&Keys),
+            "invariant.checker_count(Player 1, 14, 15)"
+        );
+
+        let checker = MoveChecker::new(Player::Player0, Location::Point(24), Location::Point(21))?;
+        let event = HistoryEvent::Move { checker, hit: None };
+        assert_eq!(event.to_string(), "Player 0 moves 24/21");
+        assert_eq!(event.localize(&Keys), "event.move(Player 0, 24/21)");
+        Ok(())
+    }
+
+    #[test]
+    fn event_display() {
+        let roll = HistoryEvent::Roll {
+            player: Player::Nobody,
+            dices: (3, 1),
+        };
+        assert_eq!(roll.to_string(), "Opening roll 3-1");
+        assert_eq!(roll.message().key, "event.opening_roll");
+        let drop = HistoryEvent::Drop {
+            player: Player::Player1,
+        };
+        assert_eq!(drop.to_string(), "Player 1 drops");
+        assert_eq!(HistoryEvent::Void.to_string(), "Game voided");
+        let choice = HistoryEvent::ChooseDoubles {
+            player: Player::Player0,
+            dice: 6,
+        };
+        assert_eq!(choice.to_string(), "Player 0 chooses 6-6");
+        assert_eq!(
+            choice.localize(&Keys),
+            "event.choose_doubles(Player 0, 6-6)"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+/// Return the common name of an opening play.
+///
+/// The play is given in standard notation from the point of view of the player moving, e.g.
+/// `"24/18 13/8"`. Moves of the same checker may be written in one step (`"24/13"`) or split up
+/// (`"24/18 18/13"`). Doubles and plays not matching the dices have no opening name.
+///
+/// ```
+/// use backgammon::opening_name;
+///
+/// assert_eq!(opening_name((6, 5), "24/13"), Some("Lover's Leap"));
+/// assert_eq!(opening_name((3, 1), "8/5 6/5"), Some("Make the five-point"));
+/// assert_eq!(opening_name((2, 1), "13/11 6/5"), Some("Slot the five-point"));
+/// ```
+pub fn opening_name(dices: (u8, u8), play: &str) -> Option<&'static str> {
+    if dices.0 == dices.1 || !(1..=6).contains(&dices.0) || !(1..=6).contains(&dices.1) {
+        return None;
+    }
+
+    let moves = parse_moves(play)?;
+    let pips: u8 = moves.iter().map(|(from, to)| from - to).sum();
+    if pips != dices.0 + dices.1 {
+        return None;
+    }
+
+    match moves[..] {
+        [(24, 13)] => Some("Lover's Leap"),
+        [(24, _)] => Some("Run"),
+        [(_, 5)] => Some("Slot the five-point"),
+        [(_, a), (_, b)] if a == b => match a {
+            7 => Some("Make the bar-point"),
+            5 => Some("Make the five-point"),
+            4 => Some("Make the four-point"),
+            3 => Some("Make the three-point"),
+            2 => Some("Make the two-point"),
+            1 => Some("Make the ace-point"),
+            _ => None,
+        },
+        [(24, _), _] | [_, (24, _)] => Some("Split"),
+        [(_, 5), _] | [_, (_, 5)] => Some("Slot the five-point"),
+        [(13, _), (13, _)] => Some("Two down"),
+        _ => None,
+    }
+}
+
+/// Parse a play into the moves of each checker, joining consecutive steps of the same checker
+fn parse_moves(play: &str) -> Option<Vec<(u8, u8)>> {
+    let mut moves: Vec<(u8, u8)> = Vec::new();
+    for token in play.split_whitespace() {
+        let (from, to) = token.trim_end_matches('*').split_once('/')?;
+        let (from, to) = (from.parse::<u8>().ok()?, to.parse::<u8>().ok()?);
+        if from > 24 || to == 0 || to >= from {
+            return None;
+        }
+        match moves.iter_mut().find(|(_, end)| *end == from) {
+            Some(step) => step.1 = to,
+            None => moves.push((from, to)),
+        }
+    }
+    if moves.is_empty() || moves.len() > 2 {
+        return None;
+    }
+    Some(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn making_points() {
+        assert_eq!(opening_name((3, 1), "8/5 6/5"), Some("Make the five-point"));
+        assert_eq!(opening_name((4, 2), "8/4 6/4"), Some("Make the four-point"));
+        assert_eq!(
+            opening_name((5, 3), "8/3 6/3"),
+            Some("Make the three-point")
+        );
+        assert_eq!(opening_name((6, 1), "13/7 8/7"), Some("Make the bar-point"));
+        assert_eq!(opening_name((6, 4), "8/2 6/2"), Some("Make the two-point"));
+    }
+
+    #[test]
+    fn running() {
+        assert_eq!(opening_name((6, 5), "24/13"), Some("Lover's Leap"));
+        assert_eq!(opening_name((5, 6), "24/18 18/13"), Some("Lover's Leap"));
+        assert_eq!(opening_name((6, 4), "24/14"), Some("Run"));
+        assert_eq!(opening_name((6, 3), "24/18 18/15"), Some("Run"));
+    }
+
+    #[test]
+    fn splitting_and_slotting() {
+        assert_eq!(opening_name((6, 4), "24/18 13/9"), Some("Split"));
+        assert_eq!(opening_name((2, 1), "13/11 24/23"), Some("Split"));
+        assert_eq!(
+            opening_name((2, 1), "13/11 6/5"),
+            Some("Slot the five-point")
+        );
+        assert_eq!(
+            opening_name((5, 1), "13/8 6/5"),
+            Some("Slot the five-point")
+        );
+        assert_eq!(opening_name((6, 2), "13/5"), Some("Slot the five-point"));
+        assert_eq!(opening_name((5, 4), "13/8 13/9"), Some("Two down"));
+    }
+
+    #[test]
+    fn no_name() {
+        assert_eq!(opening_name((3, 3), "8/5(2) 6/3(2)"), None);
+        assert_eq!(opening_name((3, 1), "8/5 6/4"), None);
+        assert_eq!(opening_name((3, 1), "8/4"), None);
+        assert_eq!(opening_name((6, 4), "8/2 6/2*"), Some("Make the two-point"));
+        assert_eq!(opening_name((5, 2), "13/11 8/3"), None);
+        assert_eq!(opening_name((3, 1), "bar/22"), None);
+        assert_eq!(opening_name((7, 1), "13/5"), None);
+    }
+}
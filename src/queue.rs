@@ -0,0 +1,380 @@
+use crate::rules::Player;
+use crate::{Error, Game, GamePhase, PlayerAction};
+use std::collections::{HashSet, VecDeque};
+
+/// The amount of idempotency keys remembered per player to detect retries
+const REMEMBERED_KEYS: usize = 1024;
+
+/// Represents an action waiting in an [`ActionQueue`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedAction {
+    /// The player submitting the action
+    pub player: Player,
+    /// The idempotency key chosen by the client, identical for retries of the same action
+    pub key: u64,
+    /// The action
+    pub action: PlayerAction,
+}
+
+/// Represents the keys and the rate limit of the actions received from one player
+#[derive(Debug, Clone, Default)]
+struct Client {
+    keys: HashSet<u64>,
+    history: VecDeque<u64>,
+    /// actions the player may still send at once
+    tokens: u64,
+    /// the time the tokens were last refilled
+    refilled: u64,
+}
+
+/// Represents a token bucket: each player may send `burst` actions at once, and one more per
+/// `interval` elapsed
+#[derive(Debug, Clone, Copy)]
+struct RateLimit {
+    burst: u64,
+    interval: u64,
+}
+
+/// Represents a queue of actions received from untrusted clients for one game
+///
+/// Clients attach an idempotency key to each action and reuse it when retrying, so an action
+/// sent twice over a flaky connection is only queued once. Keys are remembered per player, so
+/// the clients of both players can choose them independently. Actions of a player who is not to
+/// act in the game are refused, and each player may only have a limited amount of pending
+/// actions and, with [`ActionQueue::with_rate_limit`], send a limited amount of actions in a
+/// time, so flooding clients are rejected before they reach the game. All other rules are
+/// enforced by [`Game::submit`] when the queue is processed.
+///
+/// ```
+/// use backgammon::{ActionQueue, Game, PlayerAction};
+/// use backgammon::rules::Player;
+///
+/// let mut game = Game::new();
+/// let mut queue = ActionQueue::new(4);
+/// assert!(queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 0).unwrap());
+/// // a retry of the same action is ignored
+/// assert!(!queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 0).unwrap());
+///
+/// let results = queue.process(&mut game);
+/// assert_eq!(results.len(), 1);
+/// assert!(results[0].1.is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActionQueue {
+    limit: usize,
+    rate: Option<RateLimit>,
+    pending: VecDeque<QueuedAction>,
+    clients: [Client; 2],
+}
+
+impl ActionQueue {
+    /// Create a queue allowing up to `limit` pending actions per player
+    pub fn new(limit: usize) -> Self {
+        ActionQueue {
+            limit,
+            rate: None,
+            pending: VecDeque::new(),
+            clients: Default::default(),
+        }
+    }
+
+    /// Limit the rate of actions of each player: up to `burst` actions at once, and one more
+    /// per `interval` elapsed. Times are those passed to [`ActionQueue::push`], in any unit,
+    /// e.g. milliseconds.
+    ///
+    /// ```
+    /// use backgammon::{ActionQueue, Error, Game, PlayerAction};
+    /// use backgammon::rules::Player;
+    ///
+    /// let game = Game::new();
+    /// let mut queue = ActionQueue::new(8).with_rate_limit(1, 1000);
+    /// assert!(queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 0).unwrap());
+    /// assert!(matches!(
+    ///     queue.push(&game, Player::Player0, 2, PlayerAction::Roll, 500),
+    ///     Err(Error::RateLimited)
+    /// ));
+    /// assert!(queue.push(&game, Player::Player0, 2, PlayerAction::Roll, 1000).unwrap());
+    /// ```
+    pub fn with_rate_limit(mut self, burst: u64, interval: u64) -> Self {
+        self.rate = Some(RateLimit {
+            burst,
+            interval: interval.max(1),
+        });
+        for client in &mut self.clients {
+            client.tokens = burst;
+        }
+        self
+    }
+
+    /// Queue an action received at the given time, returning false if the player has already
+    /// sent an action with the same key.
+    ///
+    /// Fails with [`Error::NotYourTurn`] if the player is not to act in the current state of the
+    /// game, e.g. rolling while the opponent moves, and with [`Error::RateLimited`] or
+    /// [`Error::ActionQueueFull`] for flooding clients.
+    pub fn push(
+        &mut self,
+        game: &Game,
+        player: Player,
+        key: u64,
+        action: PlayerAction,
+        now: u64,
+    ) -> Result<bool, Error> {
+        let index = match player {
+            Player::Player0 => 0,
+            Player::Player1 => 1,
+            Player::Nobody => return Err(Error::PlayerInvalid),
+        };
+        if self.clients[index].keys.contains(&key) {
+            return Ok(false);
+        }
+        if let Some(rate) = self.rate {
+            self.clients[index].take_token(rate, now)?;
+        }
+        owns(game, player, &action)?;
+        if self.pending_for(player) >= self.limit {
+            return Err(Error::ActionQueueFull);
+        }
+
+        self.clients[index].remember(key);
+        self.pending.push_back(QueuedAction {
+            player,
+            key,
+            action,
+        });
+        Ok(true)
+    }
+
+    /// Get the amount of pending actions of a player
+    pub fn pending_for(&self, player: Player) -> usize {
+        self.pending.iter().filter(|a| a.player == player).count()
+    }
+
+    /// Get the amount of pending actions
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if no actions are pending
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Submit all pending actions to the game in the order they were received, returning the
+    /// result of each
+    pub fn process(&mut self, game: &mut Game) -> Vec<(QueuedAction, Result<(), Error>)> {
+        self.pending
+            .drain(..)
+            .map(|queued| {
+                let result = game.submit(queued.player, queued.action.clone());
+                (queued, result)
+            })
+            .collect()
+    }
+}
+
+impl Client {
+    /// Remember a key, forgetting the oldest one if too many are remembered
+    fn remember(&mut self, key: u64) {
+        if self.history.len() == REMEMBERED_KEYS {
+            if let Some(oldest) = self.history.pop_front() {
+                let _ = self.keys.remove(&oldest);
+            }
+        }
+        let _ = self.keys.insert(key);
+        self.history.push_back(key);
+    }
+
+    /// Refill the tokens for the time elapsed and take one for an action
+    fn take_token(&mut self, rate: RateLimit, now: u64) -> Result<(), Error> {
+        let refill = now.saturating_sub(self.refilled) / rate.interval;
+        if refill > 0 {
+            self.tokens = self.tokens.saturating_add(refill).min(rate.burst);
+            self.refilled = self
+                .refilled
+                .saturating_add(refill.saturating_mul(rate.interval));
+        }
+        if self.tokens == rate.burst {
+            // a full bucket does not save up time
+            self.refilled = self.refilled.max(now);
+        }
+        if self.tokens == 0 {
+            return Err(Error::RateLimited);
+        }
+        self.tokens -= 1;
+        Ok(())
+    }
+}
+
+/// Check that the player is to act with the action in the current state of the game
+fn owns(game: &Game, player: Player, action: &PlayerAction) -> Result<(), Error> {
+    let on_turn = match game.phase() {
+        GamePhase::Finished => return Err(Error::GameEnded),
+        // the opening roll may be triggered by any player
+        GamePhase::OpeningRoll => player,
+        GamePhase::DoubleOffered => game.who_plays.other(),
+        _ => game.who_plays,
+    };
+    let owner = match action {
+        PlayerAction::Resign => player,
+        PlayerAction::RequestTakeback => game.who_plays.other(),
+        PlayerAction::AcceptTakeback | PlayerAction::DeclineTakeback => game
+            .takeback_requested()
+            .map_or(on_turn, |requester| requester.other()),
+        _ => on_turn,
+    };
+    if owner != player {
+        return Err(Error::NotYourTurn);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube};
+
+    /// A game with player 0 about to roll
+    fn game() -> Result<Game, Error> {
+        Game::resume(Board::new(), Cube::default(), Player::Player0, false)
+    }
+
+    #[test]
+    fn test_duplicate() -> Result<(), Error> {
+        let game = Game::new();
+        let mut queue = ActionQueue::new(2);
+        assert!(queue.push(&game, Player::Player0, 7, PlayerAction::Roll, 0)?);
+        assert!(!queue.push(&game, Player::Player0, 7, PlayerAction::Roll, 0)?);
+        // the key of the other player is independent
+        assert!(queue.push(&game, Player::Player1, 7, PlayerAction::Resign, 0)?);
+        assert!(!queue.push(&game, Player::Player1, 7, PlayerAction::Resign, 0)?);
+        assert_eq!(queue.len(), 2);
+
+        // processed actions are still recognized as duplicates
+        let _ = queue.process(&mut Game::new());
+        assert!(queue.is_empty());
+        assert!(!queue.push(&game, Player::Player0, 7, PlayerAction::Roll, 0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flood() -> Result<(), Error> {
+        let game = Game::new();
+        let mut queue = ActionQueue::new(2);
+        assert!(queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 0)?);
+        assert!(queue.push(&game, Player::Player0, 2, PlayerAction::Roll, 0)?);
+        assert!(matches!(
+            queue.push(&game, Player::Player0, 3, PlayerAction::Roll, 0),
+            Err(Error::ActionQueueFull)
+        ));
+        // the opponent is not affected
+        assert!(queue.push(&game, Player::Player1, 4, PlayerAction::Roll, 0)?);
+        assert_eq!(queue.pending_for(Player::Player0), 2);
+        assert!(queue
+            .push(&game, Player::Nobody, 5, PlayerAction::Roll, 0)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limit() -> Result<(), Error> {
+        let game = Game::new();
+        let mut queue = ActionQueue::new(16).with_rate_limit(2, 100);
+        assert!(queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 1000)?);
+        assert!(queue.push(&game, Player::Player0, 2, PlayerAction::Roll, 1000)?);
+        assert!(matches!(
+            queue.push(&game, Player::Player0, 3, PlayerAction::Roll, 1099),
+            Err(Error::RateLimited)
+        ));
+        // retries cost nothing, and the opponent is not affected
+        assert!(!queue.push(&game, Player::Player0, 1, PlayerAction::Roll, 1099)?);
+        assert!(queue.push(&game, Player::Player1, 4, PlayerAction::Roll, 1099)?);
+
+        assert!(queue.push(&game, Player::Player0, 3, PlayerAction::Roll, 1100)?);
+        assert!(queue
+            .push(&game, Player::Player0, 5, PlayerAction::Roll, 1100)
+            .is_err());
+        // the bucket refills up to the burst only
+        assert!(queue.push(&game, Player::Player0, 5, PlayerAction::Roll, 5000)?);
+        assert!(queue.push(&game, Player::Player0, 6, PlayerAction::Roll, 5000)?);
+        assert!(queue
+            .push(&game, Player::Player0, 7, PlayerAction::Roll, 5000)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_turn_ownership() -> Result<(), Error> {
+        let mut game = game()?;
+        let mut queue = ActionQueue::new(4);
+        assert!(matches!(
+            queue.push(&game, Player::Player1, 1, PlayerAction::Roll, 0),
+            Err(Error::NotYourTurn)
+        ));
+        assert!(queue.push(&game, Player::Player0, 2, PlayerAction::OfferDouble, 0)?);
+        let _ = queue.process(&mut game);
+
+        // the opponent has to answer the cube first
+        assert!(matches!(
+            queue.push(&game, Player::Player0, 3, PlayerAction::Roll, 0),
+            Err(Error::NotYourTurn)
+        ));
+        // the key of a refused action is not remembered
+        assert!(queue.push(&game, Player::Player1, 1, PlayerAction::Take, 0)?);
+        let _ = queue.process(&mut game);
+        assert_eq!(game.cube.value(), 2);
+
+        // either player may resign at any time
+        assert!(queue.push(&game, Player::Player1, 4, PlayerAction::Resign, 0)?);
+        let _ = queue.process(&mut game);
+        assert!(matches!(
+            queue.push(&game, Player::Player0, 5, PlayerAction::Resign, 0),
+            Err(Error::GameEnded)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process() -> Result<(), Error> {
+        let mut game = Game::new();
+        let mut queue = ActionQueue::new(4);
+        let mut key = 0;
+        while game.who_plays == Player::Nobody {
+            key += 1;
+            let _ = queue.push(&game, Player::Player0, key, PlayerAction::Roll, 0)?;
+            let _ = queue.process(&mut game);
+        }
+        let player = game.who_plays;
+        let _ = queue.push(&game, player, 100, PlayerAction::OfferDouble, 0)?;
+        let _ = queue.push(&game, player, 101, PlayerAction::Resign, 0)?;
+        let results = queue.process(&mut game);
+        // the cube can not be offered after rolling
+        assert!(matches!(results[0].1, Err(Error::DoublingNotPermitted)));
+        assert!(results[1].1.is_ok());
+        assert_eq!(game.result(), Some((player.other(), 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forget_keys() -> Result<(), Error> {
+        let game = Game::new();
+        let mut queue = ActionQueue::new(REMEMBERED_KEYS + 1);
+        for key in 0..=REMEMBERED_KEYS as u64 {
+            assert!(queue.push(&game, Player::Player0, key, PlayerAction::Roll, 0)?);
+        }
+        assert!(queue
+            .push(&game, Player::Player0, 0, PlayerAction::Roll, 0)
+            .is_err());
+        let _ = queue.process(&mut Game::new());
+        assert!(queue.push(&game, Player::Player0, 0, PlayerAction::Roll, 0)?);
+        // the keys of the opponent are remembered separately
+        assert!(queue.push(
+            &game,
+            Player::Player1,
+            REMEMBERED_KEYS as u64,
+            PlayerAction::Roll,
+            0
+        )?);
+        Ok(())
+    }
+}
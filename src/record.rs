@@ -0,0 +1,72 @@
+use crate::rules::{Board, MoveChecker, Player, Turn};
+use crate::Error;
+use serde::{Deserialize, Serialize};
+
+/// Represents the full history of a game played from the starting position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct GameRecord {
+    /// The turns in the order they were played
+    pub turns: Vec<Turn>,
+    /// The winner of the game, or nobody if the game is not finished
+    pub winner: Player,
+    /// The doubles offered, in the order they were offered
+    #[serde(default)]
+    pub doubles: Vec<Double>,
+    /// The points won by the winner
+    #[serde(default)]
+    pub points: u64,
+}
+
+/// Represents a double offered in a game, which ends the game unless it was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Double {
+    /// The amount of turns played before the double
+    pub turn: usize,
+    /// The player offering the double
+    pub player: Player,
+    /// Whether the opponent took the double
+    pub taken: bool,
+}
+
+impl GameRecord {
+    /// Replay the game, returning the board before each turn followed by the final board.
+    ///
+    /// Each turn is checked to be a legal play from the position it was played in, so records
+    /// read from untrusted sources can not describe impossible games.
+    pub fn boards(&self) -> Result<Vec<Board>, Error> {
+        let mut board = Board::new();
+        let mut boards = vec![board.clone()];
+        for turn in &self.turns {
+            let moves: Vec<MoveChecker> =
+                turn.moves().iter().map(|m| m.checker().clone()).collect();
+            if Turn::new(&board, turn.player(), turn.dices(), moves)? != *turn {
+                return Err(Error::MoveInvalid);
+            }
+            turn.apply(&mut board)?;
+            boards.push(board.clone());
+        }
+        Ok(boards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards() -> Result<(), Error> {
+        let (turn, after) = Board::new().generate_plays(Player::Player0, (3, 1))?[0].clone();
+        let mut record = GameRecord {
+            turns: vec![turn],
+            ..GameRecord::default()
+        };
+        let boards = record.boards()?;
+        assert_eq!(boards, vec![Board::new(), after]);
+
+        // a turn played from another position does not fit
+        let (turn, _) = Board::new().generate_plays(Player::Player1, (6, 5))?[0].clone();
+        record.turns.push(turn);
+        assert!(record.boards().is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 /// Implements the board
 mod board;
-pub use board::{Board, BoardDisplay, Move};
+pub use board::{Board, BoardDisplay, Hit, Location, Move, MoveChecker, MoveOutcome, WinKind};
 /// Implements the double dice or cube
 mod cube;
 pub use cube::Cube;
@@ -9,7 +9,27 @@ mod player;
 pub use player::Player;
 /// Implements the pair of dices
 mod dices;
-pub use dices::{Dices, Roll, Players, PlayerWithDice};
+pub use dices::{Dices, PlayerWithDice, Players, Roll};
+/// Implements the GNU Backgammon position ID
+mod gnubg;
+pub(crate) use gnubg::{decode_base64, encode_base64};
+/// Implements dense indexes of positions with few checkers
+mod index;
+pub use index::PositionIndex;
+/// Implements metrics describing a position
+mod metrics;
+pub use metrics::PositionMetrics;
+/// Implements the variants of Backgammon
+mod variant;
+pub use variant::Variant;
+/// Implements the complete play of a turn
+mod turn;
+pub use turn::{Turn, TurnMove};
+/// Implements the standard move notation
+mod notation;
+/// Implements move generator verification
+mod perft;
+pub use perft::perft;
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
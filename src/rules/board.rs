@@ -1,6 +1,8 @@
-use crate::rules::Player;
-use crate::Error;
+use crate::rules::{Player, Turn, Variant};
+use crate::{Error, InvariantError};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
 
 /// Represents the Backgammon board
 ///
@@ -13,23 +15,44 @@ use serde::{Deserialize, Serialize};
 /// //        +12-11-10--9--8--7-------6--5--4--3--2--1-+
 /// //        | X           O    |   | O              X | +-------+
 /// //        | X           O    |   | O              X | | OFF O |
-/// //        | X           O    |   | O                | +-------+
-/// //        | X                |   | O                |
+/// //        | X           O    |   | O                | |   0   |
+/// //        | X                |   | O                | +-------+
 /// //        | X                |   | O                |
 /// //        |                  |BAR|                  |
 /// //        | O                |   | X                |
-/// //        | O                |   | X                |
-/// //        | O           X    |   | X                | +-------+
-/// //        | O           X    |   | X              O | | OFF X |
+/// //        | O                |   | X                | +-------+
+/// //        | O           X    |   | X                | | OFF X |
+/// //        | O           X    |   | X              O | |   0   |
 /// //        | O           X    |   | X              O | +-------+
 /// //        +13-14-15-16-17-18------19-20-21-22-23-24-+
 /// ```
+///
+/// The diagram is what the board's [`Display`](fmt::Display) implementation renders.
 
-#[derive(Debug, Clone, Serialize, PartialEq, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash, Deserialize, Default)]
+#[serde(try_from = "UncheckedBoard")]
 pub struct Board {
     raw_board: (PlayerBoard, PlayerBoard),
 }
 
+/// Represents a deserialized board before its invariants have been validated
+#[derive(Deserialize)]
+struct UncheckedBoard {
+    raw_board: (PlayerBoard, PlayerBoard),
+}
+
+impl TryFrom<UncheckedBoard> for Board {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedBoard) -> Result<Self, Self::Error> {
+        let board = Board {
+            raw_board: unchecked.raw_board,
+        };
+        board.validate()?;
+        Ok(board)
+    }
+}
+
 /// Represents the Backgammon board for both players (to be used for graphical representation).
 #[derive(Debug, Serialize, PartialEq, Deserialize)]
 pub struct BoardDisplay {
@@ -43,6 +66,119 @@ pub struct BoardDisplay {
     pub off: (u8, u8),
 }
 
+impl BoardDisplay {
+    /// Get the board as seen from the other side of the table, so player 1 sits where player 0
+    /// did before: the fields are renumbered, and the checkers, bar and off of both players are
+    /// swapped. Use to render the board for player 1.
+    ///
+    /// ```
+    /// use backgammon::rules::Board;
+    ///
+    /// let display = Board::new().get();
+    /// assert_eq!(display.flipped(), display);
+    /// ```
+    pub fn flipped(&self) -> BoardDisplay {
+        let mut board = [0; 24];
+        for (i, val) in board.iter_mut().enumerate() {
+            *val = -self.board[23 - i];
+        }
+        BoardDisplay {
+            board,
+            bar: (self.bar.1, self.bar.0),
+            off: (self.off.1, self.off.0),
+        }
+    }
+
+    /// Render the column of a point or the bar, `row` counting from the edge of the board
+    fn cell(checkers: i8, row: usize) -> String {
+        let count = checkers.unsigned_abs() as usize;
+        let symbol = if checkers > 0 { "O" } else { "X" };
+        if row >= count {
+            "   ".to_string()
+        } else if row == 4 && count > 5 {
+            format!("{:^3}", count)
+        } else {
+            format!(" {} ", symbol)
+        }
+    }
+
+    /// Render the tray holding the checkers borne off, `row` counting from its top
+    fn tray(symbol: &str, off: u8, row: usize) -> String {
+        match row {
+            0 | 3 => " +-------+".to_string(),
+            1 => format!(" | OFF {} |", symbol),
+            2 => format!(" |{:^7}|", off),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Renders the classic ASCII diagram with player 0 as `O` and player 1 as `X`, see [`Board`].
+/// Stacks of more than five checkers show their amount, the bar and the trays show the checkers
+/// on the bar and borne off.
+impl fmt::Display for BoardDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels = |points: [usize; 12]| {
+            let mut line = String::from("+");
+            for (i, point) in points.iter().enumerate() {
+                if i == 6 {
+                    line.push_str("-----");
+                }
+                line.push_str(&format!("{:->2}-", point));
+            }
+            line.push('+');
+            line
+        };
+        let row = |points: [usize; 12], bar: i8, row: usize| {
+            let mut line = String::from("|");
+            for (i, point) in points.iter().enumerate() {
+                if i == 6 {
+                    line.push_str(&format!("|{}|", Self::cell(bar, row)));
+                }
+                line.push_str(&Self::cell(self.board[point - 1], row));
+            }
+            line.push('|');
+            line
+        };
+
+        let top = [12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1];
+        let bottom = [13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24];
+        // player 1 enters into the top half of the board and player 0 into the bottom half
+        let bar = (i8::try_from(self.bar.0), i8::try_from(self.bar.1));
+        let (Ok(bar0), Ok(bar1)) = bar else {
+            return Err(fmt::Error);
+        };
+
+        let mut lines = vec![labels(top)];
+        for i in 0..5 {
+            lines.push(row(top, -bar1, i) + &Self::tray("O", self.off.0, i));
+        }
+        lines.push("|                  |BAR|                  |".to_string());
+        for i in (0..5).rev() {
+            // the tray of player 1 rests on the bottom edge of the board
+            let tray = 3usize
+                .checked_sub(i)
+                .map_or(String::new(), |i| Self::tray("X", self.off.1, i));
+            lines.push(row(bottom, bar0, i) + &tray);
+        }
+        lines.push(labels(bottom));
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the board from player 0's perspective, see [`BoardDisplay::flipped`] for player 1.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
 impl Board {
     /// Create a new board
     pub fn new() -> Self {
@@ -54,8 +190,8 @@ impl Board {
     /// This method outputs a tuple with three values:
     ///
     /// 1. the board represented as an array of 24 fields, each of which can hold 0 or more
-    /// checkers. Positive amounts represent checkers of player 0, negative amounts represent
-    /// checkers of player 1.
+    ///    checkers. Positive amounts represent checkers of player 0, negative amounts represent
+    ///    checkers of player 1.
     /// 2. the bar for both players
     /// 3. the off for both players
     pub fn get(&self) -> BoardDisplay {
@@ -82,6 +218,184 @@ impl Board {
         (self.raw_board.0.off, self.raw_board.1.off)
     }
 
+    /// Create a board from its graphical representation, e.g. as returned by [`Board::get`].
+    ///
+    /// Returns an error if the players do not have the checkers of a [`Variant`].
+    ///
+    /// ```
+    /// use backgammon::rules::Board;
+    ///
+    /// let mut display = Board::new().get();
+    /// assert_eq!(Board::from_display(&display).unwrap(), Board::new());
+    /// display.board[0] = 0;
+    /// assert!(Board::from_display(&display).is_err());
+    /// ```
+    pub fn from_display(display: &BoardDisplay) -> Result<Board, Error> {
+        let (mut player0, mut player1) = ([0; 24], [0; 24]);
+        for (field, &checkers) in display.board.iter().enumerate() {
+            if checkers > 0 {
+                player0[field] = checkers.unsigned_abs();
+            } else {
+                player1[23 - field] = checkers.unsigned_abs();
+            }
+        }
+        let board = Board::from_raw(
+            (player0, display.bar.0, display.off.0),
+            (player1, display.bar.1, display.off.1),
+        );
+        board.validate().map_err(|_| Error::PositionInvalid)?;
+        Ok(board)
+    }
+
+    /// Create a board from the checkers on the points, and the checkers on the bar and borne off
+    /// of both players, e.g. to set up a problem for analysis.
+    ///
+    /// Points are numbered from 1 to 24 as in the diagram of [`Board`], i.e. from the perspective
+    /// of player 0, and checkers given several times for a point add up. Returns an error if a
+    /// point is out of range, holds checkers of both players, or if the players do not have the
+    /// checkers of a [`Variant`].
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// // a bear-off problem: player 0 with 14 checkers off, player 1 with 13
+    /// let board = Board::from_points(
+    ///     &[(2, Player::Player0, 1), (20, Player::Player1, 2)],
+    ///     (0, 0),
+    ///     (14, 13),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(board.get().board[1], 1);
+    /// assert_eq!(board.get().board[19], -2);
+    /// ```
+    pub fn from_points(
+        points: &[(usize, Player, u8)],
+        bar: (u8, u8),
+        off: (u8, u8),
+    ) -> Result<Board, Error> {
+        let (mut player0, mut player1) = ([0u8; 24], [0u8; 24]);
+        for &(point, player, checkers) in points {
+            if !(1..=24).contains(&point) {
+                return Err(Error::FieldInvalid);
+            }
+            let field = match player {
+                Player::Player0 => &mut player0[point - 1],
+                Player::Player1 => &mut player1[24 - point],
+                Player::Nobody => return Err(Error::PlayerInvalid),
+            };
+            *field = field.checked_add(checkers).ok_or(Error::PositionInvalid)?;
+        }
+        let board = Board::from_raw((player0, bar.0, off.0), (player1, bar.1, off.1));
+        board.validate().map_err(|_| Error::PositionInvalid)?;
+        Ok(board)
+    }
+
+    /// Create a board from the fields, bar and off of both players, each numbered from the
+    /// player's perspective
+    pub(crate) fn from_raw(player0: ([u8; 24], u8, u8), player1: ([u8; 24], u8, u8)) -> Board {
+        let player_board = |(board, bar, off): ([u8; 24], u8, u8)| PlayerBoard { board, bar, off };
+        Board {
+            raw_board: (player_board(player0), player_board(player1)),
+        }
+    }
+
+    /// Get the fields, bar and off of a player, numbered from the player's perspective
+    pub(crate) fn raw(&self, player: Player) -> Result<([u8; 24], u8, u8), Error> {
+        let player_board = self.get_raw_board_for_player(player)?;
+        Ok((player_board.board, player_board.bar, player_board.off))
+    }
+
+    /// Validate that both players have the checkers of a [`Variant`] on the board, the bar and
+    /// off, i.e. 15 each or 3 each in Hypergammon, and that no field holds checkers of both
+    /// players.
+    ///
+    /// Boards built with [`Board::set`] may break these invariants, e.g. to set up positions
+    /// field by field. See [`Board::validate_variant`] to check a board of a known variant.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// let mut board = Board::new();
+    /// assert!(board.validate().is_ok());
+    /// board.set_bar(Player::Player1, 1).unwrap();
+    /// assert!(board.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.check_invariants(None)
+    }
+
+    /// Validate the invariants of a board played in the given variant, see [`Board::validate`].
+    ///
+    /// Both players have to have exactly the checkers of the variant, e.g. 3 in Hypergammon. The
+    /// checkers of Acey-deucey may all still be waiting on the bar to enter. Any such layout can
+    /// come up during a game, so see [`Board::validate_start`] to check the board a game starts
+    /// from.
+    ///
+    /// ```
+    /// use backgammon::rules::Variant;
+    ///
+    /// let board = Variant::Hypergammon.board();
+    /// assert!(board.validate_variant(Variant::Hypergammon).is_ok());
+    /// assert!(board.validate_variant(Variant::Standard).is_err());
+    /// ```
+    pub fn validate_variant(&self, variant: Variant) -> Result<(), InvariantError> {
+        self.check_invariants(Some(u32::from(variant.checkers())))
+    }
+
+    /// Validate that the board is the starting structure of a variant, e.g. with the four back
+    /// checkers of each player of Nackgammon or the three checkers of Hypergammon on the 24-, 23-
+    /// and 22-point, see [`Variant::board`].
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Variant};
+    ///
+    /// assert!(Board::new().validate_start(Variant::Standard).is_ok());
+    /// // the standard start is no Nackgammon start, although it has as many checkers
+    /// assert!(Board::new().validate_variant(Variant::Nackgammon).is_ok());
+    /// assert!(Board::new().validate_start(Variant::Nackgammon).is_err());
+    /// ```
+    pub fn validate_start(&self, variant: Variant) -> Result<(), InvariantError> {
+        self.validate_variant(variant)?;
+        if *self != variant.board() {
+            return Err(InvariantError::StartPosition { variant });
+        }
+        Ok(())
+    }
+
+    /// Check the invariants of the board, with the checkers expected of each player or, if not
+    /// given, those of the variant the checkers of player 0 match
+    fn check_invariants(&self, mut expected: Option<u32>) -> Result<(), InvariantError> {
+        for (player, player_board) in [
+            (Player::Player0, &self.raw_board.0),
+            (Player::Player1, &self.raw_board.1),
+        ] {
+            let count = player_board.board.iter().map(|&x| x as u32).sum::<u32>()
+                + player_board.bar as u32
+                + player_board.off as u32;
+            let expected = *expected.get_or_insert_with(|| {
+                Variant::ALL
+                    .iter()
+                    .map(|variant| u32::from(variant.checkers()))
+                    .find(|&checkers| checkers == count)
+                    .unwrap_or(15)
+            });
+            if count != expected {
+                return Err(InvariantError::CheckerCount {
+                    player,
+                    count,
+                    expected,
+                });
+            }
+        }
+
+        for field in 0..24 {
+            if self.raw_board.0.board[field] > 0 && self.raw_board.1.board[23 - field] > 0 {
+                return Err(InvariantError::FieldShared { field });
+            }
+        }
+        Ok(())
+    }
+
     /// Set checkers for a player on a field
     ///
     /// This method adds the amount of checkers for a player on a field. The field is numbered from
@@ -145,52 +459,299 @@ impl Board {
     }
 
     /// apply move from move checker
-    pub fn apply_move(&mut self, move_checker: &MoveChecker) -> Result<(), Error> {
-        match (&move_checker.from, &move_checker.to) {
+    ///
+    /// The outcome tells whether the move hit a checker of the opponent, and the checkers on the
+    /// bar afterwards. Bearing off is only possible once all checkers are in the home board, see
+    /// [`Board::can_bear_off`].
+    ///
+    /// In debug builds, a move breaking the invariants of a valid board panics, see
+    /// [`Board::validate`].
+    pub fn apply_move(&mut self, move_checker: &MoveChecker) -> Result<MoveOutcome, Error> {
+        let checked = cfg!(debug_assertions) && self.validate().is_ok();
+        let outcome = self.apply_move_unchecked(move_checker)?;
+        debug_assert!(
+            !checked || self.validate().is_ok(),
+            "move {} broke the invariants of the board",
+            move_checker
+        );
+        Ok(outcome)
+    }
+
+    /// Apply a move from move checker without checking the invariants, see [`Board::apply_move`]
+    fn apply_move_unchecked(&mut self, move_checker: &MoveChecker) -> Result<MoveOutcome, Error> {
+        let player = move_checker.player;
+        let to = match (&move_checker.from, &move_checker.to) {
             (BoardPosition::Bar, BoardPosition::Field(to)) => {
-                self.set_bar(move_checker.player, -1)?;
-                self.set(move_checker.player, *to, 1)?;
+                self.set_bar(player, -1)?;
+                *to
             }
             (BoardPosition::Field(from), BoardPosition::Field(to)) => {
-                self.set(move_checker.player, *from, -1)?;
-                self.set(move_checker.player, *to, 1)?;
+                self.set(player, *from, -1)?;
+                *to
             }
             (BoardPosition::Field(from), BoardPosition::Off) => {
-                self.set(move_checker.player, *from, -1)?;
-                self.set_off(move_checker.player, 1)?;
+                if !self.can_bear_off(player)? {
+                    return Err(Error::MoveInvalid);
+                }
+                self.set(player, *from, -1)?;
+                self.set_off(player, 1)?;
+                return Ok(self.outcome(None));
             }
             _ => return Err(Error::MoveInvalid),
+        };
+
+        let hit = if to < 24 && self.get_raw_board_for_opponent(player)?.board[23 - to] == 1 {
+            Some(Hit {
+                player: player.other(),
+                point: Location::from_field(player, to).point().unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+        self.set(player, to, 1)?;
+        Ok(self.outcome(hit))
+    }
+
+    /// Get the outcome of a move applied to the board
+    fn outcome(&self, hit: Option<Hit>) -> MoveOutcome {
+        MoveOutcome {
+            hit,
+            bar: (self.raw_board.0.bar, self.raw_board.1.bar),
         }
+    }
+
+    /// Apply all moves of a turn at once.
+    ///
+    /// The turn is atomic: if any move is illegal, or the turn does not lead to the position it
+    /// was played to, an error is returned and the board is left untouched.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// let mut board = Board::new();
+    /// let turn = board.legal_turns(Player::Player0, (6, 5)).unwrap().remove(0);
+    /// board.apply_turn(&turn).unwrap();
+    /// // the turn has already been played from this position
+    /// let after = board.clone();
+    /// assert!(board.apply_turn(&turn).is_err());
+    /// assert_eq!(board, after);
+    ///
+    /// board.undo_turn(&turn).unwrap();
+    /// assert_eq!(board, Board::new());
+    /// ```
+    pub fn apply_turn(&mut self, turn: &Turn) -> Result<(), Error> {
+        let mut board = self.clone();
+        for m in turn.moves() {
+            if board.apply_move(m.checker())?.hit != m.hit() {
+                return Err(Error::MoveInvalid);
+            }
+        }
+        if board.canonical_hash() != turn.position_hash() {
+            return Err(Error::MoveInvalid);
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Undo a turn applied with [`Board::apply_turn`], restoring the position before it. Checkers
+    /// the turn hit return from the bar to their points.
+    ///
+    /// The undo is atomic: if the board is not the position the turn was played to, an error is
+    /// returned and the board is left untouched.
+    pub fn undo_turn(&mut self, turn: &Turn) -> Result<(), Error> {
+        if self.canonical_hash() != turn.position_hash() {
+            return Err(Error::MoveInvalid);
+        }
+        let mut board = self.clone();
+        for m in turn.moves().iter().rev() {
+            board.undo_move(m.checker(), m.hit())?;
+        }
+        *self = board;
         Ok(())
     }
 
+    /// Move a checker back, returning the checker it hit from the bar
+    fn undo_move(&mut self, move_checker: &MoveChecker, hit: Option<Hit>) -> Result<(), Error> {
+        let own = self.get_mut_raw_board_for_player(move_checker.player)?;
+        let count = match move_checker.to {
+            BoardPosition::Field(to) => &mut own.board[to],
+            BoardPosition::Off => &mut own.off,
+            BoardPosition::Bar => return Err(Error::MoveInvalid),
+        };
+        *count = count.checked_sub(1).ok_or(Error::MoveInvalid)?;
+        match move_checker.from {
+            BoardPosition::Field(from) => own.board[from] += 1,
+            BoardPosition::Bar => own.bar += 1,
+            BoardPosition::Off => return Err(Error::MoveInvalid),
+        }
+
+        if let Some(hit) = hit {
+            let field = Location::Point(hit.point)
+                .to_field(hit.player)
+                .ok_or(Error::MoveInvalid)?;
+            let opponent = self.get_mut_raw_board_for_player(hit.player)?;
+            opponent.bar = opponent.bar.checked_sub(1).ok_or(Error::MoveInvalid)?;
+            opponent.board[field] += 1;
+        }
+        Ok(())
+    }
+
+    /// Check if a player may bear off, i.e. all checkers not yet borne off are in the home board
+    /// on the fields 0 to 5
+    pub fn can_bear_off(&self, player: Player) -> Result<bool, Error> {
+        let player_board = self.get_raw_board_for_player(player)?;
+        Ok(player_board.bar == 0 && player_board.board[6..].iter().all(|&x| x == 0))
+    }
+
     /// check if game is finished
     pub fn is_finished(&self) -> bool {
         self.is_winner(Player::Player0) || self.is_winner(Player::Player1)
     }
 
-    /// check if player is winner
+    /// check if player is winner, having borne off all checkers
     pub fn is_winner(&self, player: Player) -> bool {
-        self.get_raw_board_for_player(player).expect("for player").off == 15
+        self.get_raw_board_for_player(player)
+            .expect("for player")
+            .is_borne_off()
+    }
+
+    /// Get the kind of win, if the player has won.
+    ///
+    /// The win is a gammon if the loser has not borne off any checker, and a backgammon if the
+    /// loser in addition still has a checker on the bar or in the winner's home board.
+    pub fn win_kind(&self, player: Player) -> Option<WinKind> {
+        let winner = self.get_raw_board_for_player(player).ok()?;
+        if !winner.is_borne_off() {
+            return None;
+        }
+        let loser = self.get_raw_board_for_opponent(player).ok()?;
+        if loser.off > 0 {
+            Some(WinKind::Single)
+        } else if loser.bar > 0 || loser.board[18..].iter().any(|&x| x > 0) {
+            Some(WinKind::Backgammon)
+        } else {
+            Some(WinKind::Gammon)
+        }
+    }
+
+    /// Get the position with the players exchanged.
+    ///
+    /// The checkers of player 0 become the checkers of player 1 and vice versa. Evaluators can
+    /// use this to always evaluate from the perspective of a single player.
+    pub fn swapped(&self) -> Board {
+        Board {
+            raw_board: (self.raw_board.1.clone(), self.raw_board.0.clone()),
+        }
+    }
+
+    /// Get a key identifying the position regardless of which player is labeled player 0.
+    ///
+    /// A position and its mirror, where the checkers of both players are exchanged, map to the
+    /// same key. This allows opening books, caches, and training datasets to store each position
+    /// only once, as long as the player on turn is tracked separately.
+    pub fn canonical_key(&self) -> [u8; 52] {
+        let (a, b) = (self.raw_board.0.to_bytes(), self.raw_board.1.to_bytes());
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+
+        let mut key = [0; 52];
+        key[..26].copy_from_slice(&first);
+        key[26..].copy_from_slice(&second);
+        key
+    }
+
+    /// Get a hash of the canonical key of the position.
+    ///
+    /// The hash is computed using 64-bit FNV-1a, hence it is stable across program runs, platforms
+    /// and versions of this crate, unlike the hashers of the standard library.
+    pub fn canonical_hash(&self) -> u64 {
+        self.canonical_key()
+            .iter()
+            .fold(0xcbf29ce484222325, |hash, &byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    /// Pack the position into 26 bytes, 13 bytes per player, starting with player 0.
+    ///
+    /// Each byte holds two values of four bits, the higher bits first: the fields 0 to 23 of the
+    /// player, followed by the bar and the off.
+    pub(crate) fn to_packed(&self) -> [u8; 26] {
+        let mut packed = [0; 26];
+        packed[..13].copy_from_slice(&self.raw_board.0.to_packed());
+        packed[13..].copy_from_slice(&self.raw_board.1.to_packed());
+        packed
+    }
+
+    /// Unpack a position packed by [`Board::to_packed`]
+    pub(crate) fn from_packed(packed: &[u8; 26]) -> Board {
+        Board {
+            raw_board: (
+                PlayerBoard::from_packed(&packed[..13]),
+                PlayerBoard::from_packed(&packed[13..]),
+            ),
+        }
     }
 
     /// generate a move from dice roll for player
-    pub fn generate_a_possible_moves(&self, player: Player, dice: usize) -> Result<Vec<MoveChecker>, Error> {
+    ///
+    /// A checker on the bar has to enter first, otherwise every checker that can move the amount
+    /// of the dice to a field not blocked by the opponent is listed. Bearing off is only possible
+    /// once all checkers are in the home board, using a higher dice than needed only if there are
+    /// no checkers on higher fields.
+    pub fn generate_a_possible_moves(
+        &self,
+        player: Player,
+        dice: usize,
+    ) -> Result<Vec<MoveChecker>, Error> {
+        self.generate_variant_moves(player, dice, Variant::Standard)
+    }
+
+    /// Generate the moves of a dice for a player under the rules of a variant, see
+    /// [`Board::generate_a_possible_moves`].
+    ///
+    /// In variants whose checkers enter freely, entering a checker from the bar is listed first,
+    /// followed by the moves of the checkers on the board.
+    ///
+    /// ```
+    /// use backgammon::rules::{Player, Variant};
+    ///
+    /// let board = Variant::AceyDeucey.board();
+    /// let moves = board.generate_variant_moves(Player::Player0, 3, Variant::AceyDeucey).unwrap();
+    /// assert_eq!(moves.len(), 1);
+    /// ```
+    pub fn generate_variant_moves(
+        &self,
+        player: Player,
+        dice: usize,
+        variant: Variant,
+    ) -> Result<Vec<MoveChecker>, Error> {
+        if !(1..=6).contains(&dice) {
+            return Err(Error::DiceInvalid);
+        }
+
         let player_board = self.get_raw_board_for_player(player)?;
-        if player_board.bar > 0 && self.blocked(player, 24 - dice)? {
-            let move_checker = MoveChecker {
-                player,
-                from: BoardPosition::Bar,
-                to: BoardPosition::Field(dice - 1),
-            };
-            if !self.blocked(player, dice - 1)? {
-                return Ok(vec![move_checker]);
-            } else {
-                return Err(Error::MoveInvalid);
+        let mut entering = Vec::new();
+        if player_board.bar > 0 {
+            let to = 24 - dice;
+            if !self.blocked(player, to)? {
+                entering.push(MoveChecker {
+                    player,
+                    from: BoardPosition::Bar,
+                    to: BoardPosition::Field(to),
+                });
             }
-        } else {
-            let all_fields = player_board.board.iter().enumerate().filter(|(_, &x)| x > 0).collect::<Vec<(usize, &u8)>>();
-            let all_moves = all_fields.into_iter().filter_map(|(i, _field)| {
+            if !variant.enters_freely() {
+                return Ok(entering);
+            }
+        }
+
+        let bear_off = self.can_bear_off(player)?;
+        let all_moves = player_board
+            .board
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| x > 0)
+            .filter_map(|(i, _field)| {
                 if let Some(new) = i.checked_sub(dice) {
                     let move_checker = MoveChecker {
                         player,
@@ -202,17 +763,171 @@ impl Board {
                     } else {
                         None
                     }
-                } else {
+                } else if bear_off
+                    && (i + 1 == dice || player_board.board[i + 1..].iter().all(|&x| x == 0))
+                {
                     let move_checker = MoveChecker {
                         player,
                         from: BoardPosition::Field(i),
                         to: BoardPosition::Off,
                     };
                     Some(move_checker)
+                } else {
+                    None
                 }
-            }).collect();
-            return Ok(all_moves);
+            });
+        entering.extend(all_moves);
+        Ok(entering)
+    }
+
+    /// Generate all complete plays for a player with the given dices.
+    ///
+    /// Each play is returned as a turn together with the resulting board. Only plays using as many
+    /// dices as possible are kept and if only one of two different dices can be used, it has to
+    /// be the larger one. Plays leading to the same position are listed once. If the player can
+    /// not move at all, the only play is the empty one.
+    pub(crate) fn generate_plays(
+        &self,
+        player: Player,
+        dices: (u8, u8),
+    ) -> Result<Vec<(Turn, Board)>, Error> {
+        self.generate_variant_plays(player, dices, Variant::Standard)
+    }
+
+    /// Generate all complete plays for a player with the given dices under the rules of a
+    /// variant, see [`Board::generate_plays`]
+    pub(crate) fn generate_variant_plays(
+        &self,
+        player: Player,
+        dices: (u8, u8),
+        variant: Variant,
+    ) -> Result<Vec<(Turn, Board)>, Error> {
+        let mut seen = HashSet::new();
+        let plays: Vec<_> = self
+            .generate_variant_diced_plays(player, dices, variant)?
+            .into_iter()
+            .filter(|(_, board)| seen.insert(board.clone()))
+            .collect();
+        let forced = plays.len() == 1;
+        plays
+            .into_iter()
+            .map(|(play, _)| Turn::from_play(self, player, dices, play, forced))
+            .collect()
+    }
+
+    /// Enumerate all complete legal turns of a player with the given dices
+    ///
+    /// Both dices, or all four on doubles, have to be used if possible. Otherwise as many dices as
+    /// possible have to be used, and the larger one if only one of two different dices can be
+    /// played. Turns leading to the same position are listed once, in the first order found. If
+    /// the player can not move at all, the only turn is the empty one.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// let turns = Board::new().legal_turns(Player::Player0, (3, 1)).unwrap();
+    /// assert_eq!(turns.len(), 16);
+    /// assert!(turns.iter().all(|turn| turn.moves().len() == 2));
+    /// ```
+    pub fn legal_turns(&self, player: Player, dices: (u8, u8)) -> Result<Vec<Turn>, Error> {
+        self.legal_variant_turns(player, dices, Variant::Standard)
+    }
+
+    /// Enumerate all complete legal turns of a player with the given dices under the rules of a
+    /// variant, see [`Board::legal_turns`]
+    ///
+    /// ```
+    /// use backgammon::rules::{Player, Variant};
+    ///
+    /// // entering two checkers, or one checker moving on with the other dice
+    /// let board = Variant::AceyDeucey.board();
+    /// let turns = board.legal_variant_turns(Player::Player0, (3, 1), Variant::AceyDeucey);
+    /// assert_eq!(turns.unwrap().len(), 2);
+    /// ```
+    pub fn legal_variant_turns(
+        &self,
+        player: Player,
+        dices: (u8, u8),
+        variant: Variant,
+    ) -> Result<Vec<Turn>, Error> {
+        if !(1..=6).contains(&dices.0) || !(1..=6).contains(&dices.1) {
+            return Err(Error::DiceInvalid);
+        }
+        Ok(self
+            .generate_variant_plays(player, dices, variant)?
+            .into_iter()
+            .map(|(turn, _)| turn)
+            .collect())
+    }
+
+    /// Generate all legal plays, each as the sequence of moves with the dice used for each move,
+    /// in every order they can be played
+    pub(crate) fn generate_diced_plays(
+        &self,
+        player: Player,
+        dices: (u8, u8),
+    ) -> Result<Vec<DicedPlay>, Error> {
+        self.generate_variant_diced_plays(player, dices, Variant::Standard)
+    }
+
+    /// Generate all legal plays under the rules of a variant, see [`Board::generate_diced_plays`]
+    pub(crate) fn generate_variant_diced_plays(
+        &self,
+        player: Player,
+        dices: (u8, u8),
+        variant: Variant,
+    ) -> Result<Vec<DicedPlay>, Error> {
+        let orders = if dices.0 == dices.1 {
+            vec![vec![dices.0; 4]]
+        } else {
+            vec![vec![dices.0, dices.1], vec![dices.1, dices.0]]
+        };
+
+        let mut plays = Vec::new();
+        for order in orders {
+            self.collect_plays(player, variant, &order, Vec::new(), &mut plays)?;
+        }
+
+        let most = plays.iter().map(|(m, _)| m.len()).max().unwrap_or(0);
+        plays.retain(|(m, _)| m.len() == most);
+        let larger = dices.0.max(dices.1);
+        if most == 1 && dices.0 != dices.1 && plays.iter().any(|(m, _)| m[0].0 == larger) {
+            plays.retain(|(m, _)| m[0].0 == larger);
         }
+        Ok(plays)
+    }
+
+    /// Recursively apply the remaining dices, collecting each sequence of moves together with the
+    /// dice used for each move.
+    fn collect_plays(
+        &self,
+        player: Player,
+        variant: Variant,
+        dices: &[u8],
+        moves: Vec<(u8, MoveChecker)>,
+        plays: &mut Vec<DicedPlay>,
+    ) -> Result<(), Error> {
+        let (dice, rest) = match dices.split_first() {
+            Some((&dice, rest)) => (dice, rest),
+            None => {
+                plays.push((moves, self.clone()));
+                return Ok(());
+            }
+        };
+
+        let candidates = self.generate_variant_moves(player, dice as usize, variant)?;
+        if candidates.is_empty() {
+            plays.push((moves, self.clone()));
+            return Ok(());
+        }
+        for candidate in candidates {
+            let mut board = self.clone();
+            let _ = board.apply_move(&candidate)?;
+            let mut moves = moves.clone();
+            moves.push((dice, candidate));
+            board.collect_plays(player, variant, rest, moves, plays)?;
+        }
+        Ok(())
     }
 
     fn get_raw_board_for_player(&self, player: Player) -> Result<&PlayerBoard, Error> {
@@ -239,24 +954,68 @@ impl Board {
         }
     }
 
-    fn get_mut_raw_board_for_opponent(&mut self, player: Player) -> Result<&mut PlayerBoard, Error> {
+    fn get_mut_raw_board_for_opponent(
+        &mut self,
+        player: Player,
+    ) -> Result<&mut PlayerBoard, Error> {
         match player {
             Player::Player0 => Ok(&mut self.raw_board.1),
             Player::Player1 => Ok(&mut self.raw_board.0),
             Player::Nobody => Err(Error::PlayerInvalid),
         }
     }
-
 }
 
 /// Represents the Backgammon board for one player
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 struct PlayerBoard {
     board: [u8; 24],
     bar: u8,
     off: u8,
 }
 
+impl PlayerBoard {
+    /// Check if all checkers are borne off, whatever their amount in the variant played
+    fn is_borne_off(&self) -> bool {
+        self.off > 0 && self.bar == 0 && self.board.iter().all(|&x| x == 0)
+    }
+
+    /// Serialize the fields, bar and off into bytes
+    fn to_bytes(&self) -> [u8; 26] {
+        let mut bytes = [0; 26];
+        bytes[..24].copy_from_slice(&self.board);
+        bytes[24] = self.bar;
+        bytes[25] = self.off;
+        bytes
+    }
+
+    /// Pack the fields, bar and off into four bits each
+    fn to_packed(&self) -> [u8; 13] {
+        let bytes = self.to_bytes();
+        let mut packed = [0; 13];
+        for (i, pair) in bytes.chunks(2).enumerate() {
+            packed[i] = (pair[0].min(15) << 4) | pair[1].min(15);
+        }
+        packed
+    }
+
+    /// Unpack the fields, bar and off packed by [`PlayerBoard::to_packed`]
+    fn from_packed(packed: &[u8]) -> Self {
+        let mut bytes = [0; 26];
+        for (i, byte) in packed.iter().enumerate() {
+            bytes[2 * i] = byte >> 4;
+            bytes[2 * i + 1] = byte & 0x0f;
+        }
+        let mut board = [0; 24];
+        board.copy_from_slice(&bytes[..24]);
+        PlayerBoard {
+            board,
+            bar: bytes[24],
+            off: bytes[25],
+        }
+    }
+}
+
 impl Default for PlayerBoard {
     fn default() -> Self {
         PlayerBoard {
@@ -269,20 +1028,263 @@ impl Default for PlayerBoard {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+/// Represents the kind of a win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WinKind {
+    /// The loser has borne off at least one checker
+    Single,
+    /// The loser has not borne off any checker
+    Gammon,
+    /// The loser has not borne off any checker and still has a checker on the bar or in the
+    /// winner's home board
+    Backgammon,
+}
+
+impl WinKind {
+    /// Get the multiple of the cube value won: 1, 2 or 3
+    pub fn multiplier(&self) -> u64 {
+        match self {
+            WinKind::Single => 1,
+            WinKind::Gammon => 2,
+            WinKind::Backgammon => 3,
+        }
+    }
+
+    /// Get the points won with the given cube value
+    pub fn points(&self, cube_value: u64) -> u64 {
+        self.multiplier() * cube_value
+    }
+}
+
+/// Represents a checker sent to the bar by a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Hit {
+    /// The player whose checker was hit
+    pub player: Player,
+    /// The point the checker was hit on, in absolute coordinates, see [`Location`]
+    pub point: u8,
+}
+
+/// Represents the outcome of a move applied with [`Board::apply_move`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MoveOutcome {
+    /// The checker of the opponent sent to the bar, if any
+    pub hit: Option<Hit>,
+    /// The checkers on the bar of player 0 and player 1 after the move
+    pub bar: (u8, u8),
+}
+
+/// A sequence of moves, each with the dice used, and the resulting board
+pub(crate) type DicedPlay = (Vec<(u8, MoveChecker)>, Board);
+
+/// Check if plays generated for a position all lead to the same position, i.e. the play is forced
+pub(crate) fn is_forced(plays: &[DicedPlay]) -> bool {
+    plays.windows(2).all(|pair| pair[0].1 == pair[1].1)
+}
+
+/// Represents the move of one checker
+///
+/// Internally, the origin and destination are stored as fields numbered 0 to 23 from the
+/// perspective of the moving player, see [`Board::set`]. All public constructors and accessors,
+/// as well as the serialized form, use absolute [`Location`]s instead, which are the same for
+/// both players and can not be misread across the player boundary.
+///
+/// ```
+/// use backgammon::rules::{Location, MoveChecker, Player};
+///
+/// // player 0 moves from the 8-point to the 5-point, player 1 from the 17-point to the 20-point
+/// let m = MoveChecker::new(Player::Player0, Location::Point(8), Location::Point(5)).unwrap();
+/// assert_eq!(m.to(), Location::Point(5));
+/// assert!(MoveChecker::new(Player::Player1, Location::Point(17), Location::Point(20)).is_ok());
+///
+/// // player 0 can not move backwards
+/// assert!(MoveChecker::new(Player::Player0, Location::Point(5), Location::Point(8)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Hash, Eq, Serialize, Deserialize)]
+#[serde(into = "WireMove", try_from = "WireMove")]
 pub struct MoveChecker {
     player: Player,
     from: BoardPosition,
     to: BoardPosition,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+/// Represents a move in absolute coordinates, as serialized
+#[derive(Serialize, Deserialize)]
+struct WireMove {
+    player: Player,
+    from: Location,
+    to: Location,
+}
+
+impl From<MoveChecker> for WireMove {
+    fn from(m: MoveChecker) -> Self {
+        WireMove {
+            player: m.player,
+            from: m.from(),
+            to: m.to(),
+        }
+    }
+}
+
+impl TryFrom<WireMove> for MoveChecker {
+    type Error = Error;
+
+    fn try_from(wire: WireMove) -> Result<Self, Self::Error> {
+        MoveChecker::new(wire.player, wire.from, wire.to)
+    }
+}
+
+/// Represents a location on the board in absolute coordinates, shared by both players.
+///
+/// The points are numbered from 1 to 24 as seen by player 0, i.e. player 0 moves from the 24-point
+/// down to the 1-point and bears off from points 1 to 6, while player 1 moves from the 1-point up
+/// to the 24-point and bears off from points 19 to 24. These are the same indexes as used by
+/// [`BoardDisplay`], plus one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Location {
+    /// The bar of the moving player
+    Bar,
+    /// A point from 1 to 24
+    Point(u8),
+    /// Borne off
+    Off,
+}
+
+impl Location {
+    /// Get the point, if the location is on the board
+    pub fn point(&self) -> Option<u8> {
+        match self {
+            Location::Point(point) => Some(*point),
+            _ => None,
+        }
+    }
+
+    /// Get the location of a field numbered from the perspective of a player
+    fn from_field(player: Player, field: usize) -> Location {
+        match player {
+            Player::Player1 => Location::Point(24 - field as u8),
+            _ => Location::Point(field as u8 + 1),
+        }
+    }
+
+    /// Get the field numbered from the perspective of a player
+    fn to_field(self, player: Player) -> Option<usize> {
+        match (self, player) {
+            (Location::Point(point @ 1..=24), Player::Player0) => Some(point as usize - 1),
+            (Location::Point(point @ 1..=24), Player::Player1) => Some(24 - point as usize),
+            _ => None,
+        }
+    }
+}
+
+impl MoveChecker {
+    /// Create the move of a checker of a player between two locations.
+    ///
+    /// The move has to go in the direction of the player and cover one to six pips: entering from
+    /// the bar lands in the opponent's home board and bearing off starts from the own home board.
+    pub fn new(player: Player, from: Location, to: Location) -> Result<Self, Error> {
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        let field = |location: Location| location.to_field(player).ok_or(Error::FieldInvalid);
+        let (from, to) = match (from, to) {
+            (Location::Bar, to) => (24, field(to)?),
+            (from, Location::Off) => (field(from)?, usize::MAX),
+            (from, to) => (field(from)?, field(to)?),
+        };
+        let valid = if to == usize::MAX {
+            from < 6
+        } else {
+            from > to && from - to <= 6
+        };
+        if !valid {
+            return Err(Error::MoveInvalid);
+        }
+        let position = |field: usize| match field {
+            24 => BoardPosition::Bar,
+            usize::MAX => BoardPosition::Off,
+            field => BoardPosition::Field(field),
+        };
+        Ok(MoveChecker {
+            player,
+            from: position(from),
+            to: position(to),
+        })
+    }
+
+    /// Get the player moving the checker
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// Get the location the checker moves from
+    pub fn from(&self) -> Location {
+        self.from.location(self.player)
+    }
+
+    /// Get the location the checker moves to
+    pub fn to(&self) -> Location {
+        self.to.location(self.player)
+    }
+
+    /// Encode the move into two bytes, the origin and the destination. Fields are encoded as
+    /// their number 0 to 23, the bar as 24 and the off as 25.
+    pub(crate) fn to_bytes(&self) -> [u8; 2] {
+        [self.from.to_byte(), self.to.to_byte()]
+    }
+
+    /// Decode a move of a player encoded by [`MoveChecker::to_bytes`]
+    pub(crate) fn from_bytes(player: Player, bytes: [u8; 2]) -> Option<MoveChecker> {
+        Some(MoveChecker {
+            player,
+            from: BoardPosition::from_byte(bytes[0])?,
+            to: BoardPosition::from_byte(bytes[1])?,
+        })
+    }
+
+    /// Get the same move played by the other player on a swapped board, see [`Board::swapped`].
+    pub fn swapped(&self) -> MoveChecker {
+        MoveChecker {
+            player: self.player.other(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Eq, Serialize, Deserialize)]
 enum BoardPosition {
     Bar,
     Off,
     Field(usize),
 }
 
+impl BoardPosition {
+    fn location(&self, player: Player) -> Location {
+        match self {
+            BoardPosition::Field(field) => Location::from_field(player, *field),
+            BoardPosition::Bar => Location::Bar,
+            BoardPosition::Off => Location::Off,
+        }
+    }
+
+    fn to_byte(&self) -> u8 {
+        match self {
+            BoardPosition::Field(field) => *field as u8,
+            BoardPosition::Bar => 24,
+            BoardPosition::Off => 25,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0..=23 => Some(BoardPosition::Field(byte as usize)),
+            24 => Some(BoardPosition::Bar),
+            25 => Some(BoardPosition::Off),
+            _ => None,
+        }
+    }
+}
+
 /// Trait to move checkers
 pub trait Move {
     /// Move a checker
@@ -311,6 +1313,65 @@ mod tests {
         assert_eq!(Board::new(), Board::default());
     }
 
+    #[test]
+    fn display_start() {
+        let expected = [
+            "+12-11-10--9--8--7-------6--5--4--3--2--1-+",
+            "| X           O    |   | O              X | +-------+",
+            "| X           O    |   | O              X | | OFF O |",
+            "| X           O    |   | O                | |   0   |",
+            "| X                |   | O                | +-------+",
+            "| X                |   | O                |",
+            "|                  |BAR|                  |",
+            "| O                |   | X                |",
+            "| O                |   | X                | +-------+",
+            "| O           X    |   | X                | | OFF X |",
+            "| O           X    |   | X              O | |   0   |",
+            "| O           X    |   | X              O | +-------+",
+            "+13-14-15-16-17-18------19-20-21-22-23-24-+",
+        ];
+        assert_eq!(format!("{}", Board::new()), expected.join("\n"));
+    }
+
+    #[test]
+    fn display_bar_off_and_flipped() {
+        let mut fields = [0; 24];
+        fields[0] = 7;
+        let board = Board::from_raw((fields, 1, 7), ([0; 24], 0, 15));
+        let expected = [
+            "+12-11-10--9--8--7-------6--5--4--3--2--1-+",
+            "|                  |   |                O | +-------+",
+            "|                  |   |                O | | OFF O |",
+            "|                  |   |                O | |   7   |",
+            "|                  |   |                O | +-------+",
+            "|                  |   |                7 |",
+            "|                  |BAR|                  |",
+            "|                  |   |                  |",
+            "|                  |   |                  | +-------+",
+            "|                  |   |                  | | OFF X |",
+            "|                  |   |                  | |  15   |",
+            "|                  | O |                  | +-------+",
+            "+13-14-15-16-17-18------19-20-21-22-23-24-+",
+        ];
+        assert_eq!(format!("{}", board), expected.join("\n"));
+
+        let flipped = format!("{}", board.get().flipped());
+        let lines: Vec<&str> = flipped.lines().collect();
+        assert_eq!(
+            lines[1],
+            "|                  | X |                  | +-------+"
+        );
+        assert_eq!(
+            lines[3],
+            "|                  |   |                  | |  15   |"
+        );
+        assert_eq!(lines[7], "|                  |   |                7 |");
+        assert_eq!(
+            lines[10],
+            "|                  |   |                X | |   7   |"
+        );
+    }
+
     #[test]
     fn default_player_board() {
         assert_eq!(
@@ -544,18 +1605,329 @@ mod tests {
         let board = Board::new();
         let move_checker = board.generate_a_possible_moves(Player::Player0, 1).unwrap();
         assert_eq!(move_checker.len(), 3);
-        assert_eq!(move_checker, vec![MoveChecker {
-            player: Player::Player0,
-            from: BoardPosition::Field(5),
-            to: BoardPosition::Field(4),
-        }, MoveChecker {
-            player: Player::Player0,
-            from: BoardPosition::Field(7),
-            to: BoardPosition::Field(6),
-        }, MoveChecker {
-            player: Player::Player0,
-            from: BoardPosition::Field(23),
-            to: BoardPosition::Field(22),
-        }]);
+        assert_eq!(
+            move_checker,
+            vec![
+                MoveChecker {
+                    player: Player::Player0,
+                    from: BoardPosition::Field(5),
+                    to: BoardPosition::Field(4),
+                },
+                MoveChecker {
+                    player: Player::Player0,
+                    from: BoardPosition::Field(7),
+                    to: BoardPosition::Field(6),
+                },
+                MoveChecker {
+                    player: Player::Player0,
+                    from: BoardPosition::Field(23),
+                    to: BoardPosition::Field(22),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn canonical_key_mirror() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set(Player::Player0, 20, 1)?;
+        let mut mirror = Board::new();
+        mirror.set(Player::Player1, 23, -1)?;
+        mirror.set(Player::Player1, 20, 1)?;
+
+        assert_ne!(board, mirror);
+        assert_eq!(board.canonical_key(), mirror.canonical_key());
+        assert_eq!(board.canonical_hash(), mirror.canonical_hash());
+        assert_ne!(board.canonical_hash(), Board::new().canonical_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_hash_stable() {
+        assert_eq!(Board::new().canonical_hash(), 0x65e93c10753f2cd5);
+    }
+
+    #[test]
+    fn swapped() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set(Player::Player0, 20, 1)?;
+        board.set_bar(Player::Player1, 1)?;
+        let swapped = board.swapped();
+
+        let display = board.get();
+        let mut mirrored = display.board;
+        mirrored.reverse();
+        mirrored.iter_mut().for_each(|x| *x = -*x);
+        assert_eq!(swapped.get().board, mirrored);
+        assert_eq!(swapped.get().bar, (1, 0));
+        assert_eq!(swapped.swapped(), board);
+        Ok(())
+    }
+
+    #[test]
+    fn swapped_moves() -> Result<(), Error> {
+        let board = Board::new();
+        let moves = board.generate_a_possible_moves(Player::Player0, 3)?;
+        let swapped: Vec<MoveChecker> = moves.iter().map(MoveChecker::swapped).collect();
+        assert_eq!(
+            board
+                .swapped()
+                .generate_a_possible_moves(Player::Player1, 3)?,
+            swapped
+        );
+        for (m, swapped) in moves.iter().zip(swapped) {
+            let mut a = board.clone();
+            let _ = a.apply_move(m)?;
+            let mut b = board.swapped();
+            let _ = b.apply_move(&swapped)?;
+            assert_eq!(a.swapped(), b);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn from_points() -> Result<(), Error> {
+        let start = [
+            (24, Player::Player0, 2),
+            (13, Player::Player0, 5),
+            (8, Player::Player0, 3),
+            (6, Player::Player0, 5),
+            (1, Player::Player1, 2),
+            (12, Player::Player1, 5),
+            (17, Player::Player1, 3),
+            (19, Player::Player1, 5),
+        ];
+        assert_eq!(Board::from_points(&start, (0, 0), (0, 0))?, Board::new());
+
+        // checkers on the bar and off count, and points add up
+        let board = Board::from_points(
+            &[
+                (3, Player::Player0, 7),
+                (3, Player::Player0, 7),
+                (10, Player::Player1, 1),
+            ],
+            (1, 4),
+            (0, 10),
+        )?;
+        assert_eq!(board.raw(Player::Player0)?.0[2], 14);
+        assert_eq!(board.raw(Player::Player1)?.0[14], 1);
+        assert_eq!(Board::from_display(&board.get())?, board);
+
+        for (points, error) in [
+            (vec![(0, Player::Player0, 15)], Error::FieldInvalid),
+            (vec![(25, Player::Player0, 15)], Error::FieldInvalid),
+            (vec![(1, Player::Nobody, 15)], Error::PlayerInvalid),
+            (vec![(1, Player::Player0, 14)], Error::PositionInvalid),
+            (
+                vec![(1, Player::Player0, 255), (1, Player::Player0, 1)],
+                Error::PositionInvalid,
+            ),
+        ] {
+            let result = Board::from_points(&points, (0, 0), (0, 15));
+            assert_eq!(result.map_err(|e| e.to_string()), Err(error.to_string()));
+        }
+        // a point holding checkers of both players
+        let shared = [(5, Player::Player0, 1), (5, Player::Player1, 1)];
+        assert!(Board::from_points(&shared, (0, 0), (14, 14)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_valid() -> Result<(), serde_json::Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1).unwrap();
+        board.set_bar(Player::Player0, 1).unwrap();
+        let json = serde_json::to_string(&board)?;
+        assert_eq!(serde_json::from_str::<Board>(&json)?, board);
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_checker_count() {
+        let mut board = Board::new();
+        board.set_off(Player::Player1, 25).unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        let error = serde_json::from_str::<Board>(&json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Player 1 has 40 checkers instead of 15"));
+    }
+
+    #[test]
+    fn deserialize_field_shared() {
+        let mut board = Board::new();
+        board.raw_board.0.board[0] = 1;
+        board.raw_board.0.off = 0;
+        board.raw_board.0.board[23] = 1;
+        let json = serde_json::to_string(&board).unwrap();
+        let error = serde_json::from_str::<Board>(&json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Both players have checkers on field 0"));
+    }
+
+    #[test]
+    fn legal_turns() -> Result<(), Error> {
+        let board = Board::new();
+        let turns = board.legal_turns(Player::Player1, (4, 4))?;
+        assert!(!turns.is_empty());
+        assert!(turns.iter().all(|turn| turn.moves().len() == 4));
+        assert!(turns.iter().all(|turn| turn.player() == Player::Player1));
+        assert!(board.legal_turns(Player::Player0, (7, 1)).is_err());
+        assert!(board.legal_turns(Player::Nobody, (6, 1)).is_err());
+
+        // player 0 on the bar against a closed board
+        let mut closed = Board::new();
+        closed.set(Player::Player0, 23, -1)?;
+        closed.set_bar(Player::Player0, 1)?;
+        for field in 0..6 {
+            closed.set(Player::Player1, field, 2)?;
+        }
+        let turns = closed.legal_turns(Player::Player0, (3, 3))?;
+        assert_eq!(turns.len(), 1);
+        assert!(turns[0].is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_move_hit() -> Result<(), Error> {
+        // a blot of player 1 on its field 3, which is field 20 for player 0
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set(Player::Player1, 3, 1)?;
+        let moves = board.generate_a_possible_moves(Player::Player0, 3)?;
+        let hitting = moves
+            .iter()
+            .find(|m| m.to_bytes() == [23, 20])
+            .ok_or(Error::MoveInvalid)?;
+        assert_eq!(
+            board.clone().apply_move(hitting)?,
+            MoveOutcome {
+                hit: Some(Hit {
+                    player: Player::Player1,
+                    point: 21
+                }),
+                bar: (0, 1),
+            }
+        );
+        let quiet = moves
+            .iter()
+            .find(|m| m.to_bytes() == [7, 4])
+            .ok_or(Error::MoveInvalid)?;
+        assert_eq!(board.apply_move(quiet)?.hit, None);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_and_undo_turn() -> Result<(), Error> {
+        // player 1 has a blot on the 5-point of player 0, hit with 3-1 from the 8- or 6-point
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set(Player::Player1, 19, 1)?;
+        let before = board.clone();
+        let turns = board.legal_turns(Player::Player0, (3, 1))?;
+        let hit = turns
+            .iter()
+            .find(|turn| turn.hits())
+            .ok_or(Error::MoveInvalid)?;
+        board.apply_turn(hit)?;
+        assert_eq!(board.get().bar, (0, 1));
+        board.undo_turn(hit)?;
+        assert_eq!(board, before);
+
+        // a turn not played from the position leaves the board untouched
+        let other = Board::new().legal_turns(Player::Player1, (6, 5))?.remove(0);
+        assert!(board.apply_turn(&other).is_err());
+        assert!(board.undo_turn(&other).is_err());
+        assert_eq!(board, before);
+        Ok(())
+    }
+
+    #[test]
+    fn bear_off() -> Result<(), Error> {
+        let mut board = Board::new();
+        assert!(!board.can_bear_off(Player::Player0)?);
+        let off = MoveChecker::from_bytes(Player::Player0, [5, 25]).ok_or(Error::MoveInvalid)?;
+        assert!(board.clone().apply_move(&off).is_err());
+
+        // move the remaining checkers of player 0 home
+        board.set(Player::Player0, 7, -3)?;
+        board.set(Player::Player0, 12, -5)?;
+        board.set(Player::Player0, 23, -2)?;
+        board.set(Player::Player0, 4, 10)?;
+        assert!(board.can_bear_off(Player::Player0)?);
+        assert_eq!(board.apply_move(&off)?.hit, None);
+        assert_eq!(board.get().off.0, 1);
+
+        board.set_bar(Player::Player0, 1)?;
+        assert!(!board.can_bear_off(Player::Player0)?);
+        assert!(board.can_bear_off(Player::Nobody).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn win_kind() -> Result<(), Error> {
+        let mut board = Board::new();
+        assert_eq!(board.win_kind(Player::Player0), None);
+        assert_eq!(board.win_kind(Player::Nobody), None);
+
+        // player 0 bears off everything, player 1 still has two checkers in player 0's home board
+        board.raw_board.0 = PlayerBoard {
+            board: [0; 24],
+            bar: 0,
+            off: 15,
+        };
+        assert_eq!(board.win_kind(Player::Player0), Some(WinKind::Backgammon));
+        assert_eq!(board.win_kind(Player::Player1), None);
+
+        board.raw_board.1.board[23] = 0;
+        board.raw_board.1.bar = 2;
+        assert_eq!(board.win_kind(Player::Player0), Some(WinKind::Backgammon));
+        board.raw_board.1.bar = 0;
+        board.raw_board.1.board[12] += 2;
+        assert_eq!(board.win_kind(Player::Player0), Some(WinKind::Gammon));
+        board.raw_board.1.board[12] -= 1;
+        board.raw_board.1.off = 1;
+        assert_eq!(board.win_kind(Player::Player0), Some(WinKind::Single));
+
+        assert_eq!(WinKind::Single.points(4), 4);
+        assert_eq!(WinKind::Gammon.points(2), 4);
+        assert_eq!(WinKind::Backgammon.points(2), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn move_locations() -> Result<(), Error> {
+        let m = MoveChecker::new(Player::Player1, Location::Bar, Location::Point(3))?;
+        assert_eq!(m.from(), Location::Bar);
+        assert_eq!(m.to(), Location::Point(3));
+        assert_eq!(m.to_bytes(), [24, 21]);
+        let m = MoveChecker::new(Player::Player0, Location::Point(6), Location::Off)?;
+        assert_eq!(m.to_bytes(), [5, 25]);
+        assert_eq!(m.swapped().from(), Location::Point(19));
+
+        // entering, bearing off and distances are checked
+        assert!(MoveChecker::new(Player::Player0, Location::Bar, Location::Point(12)).is_err());
+        assert!(MoveChecker::new(Player::Player1, Location::Point(12), Location::Off).is_err());
+        assert!(
+            MoveChecker::new(Player::Player0, Location::Point(20), Location::Point(10)).is_err()
+        );
+        assert!(MoveChecker::new(Player::Player0, Location::Point(0), Location::Off).is_err());
+        assert!(MoveChecker::new(Player::Player0, Location::Off, Location::Bar).is_err());
+        assert!(MoveChecker::new(Player::Nobody, Location::Bar, Location::Point(3)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn move_serialization() -> Result<(), serde_json::Error> {
+        let json = r#"{"player":"Player1","from":"Bar","to":{"Point":3}}"#;
+        let m: MoveChecker = serde_json::from_str(json)?;
+        assert_eq!(m.to_bytes(), [24, 21]);
+        assert_eq!(serde_json::to_string(&m)?, json);
+        let json = r#"{"player":"Player0","from":"Bar","to":{"Point":3}}"#;
+        assert!(serde_json::from_str::<MoveChecker>(json).is_err());
+        Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::error::{Error, InvariantError};
 use crate::rules::Player;
 use serde::{Deserialize, Serialize};
 
@@ -8,12 +8,36 @@ use serde::{Deserialize, Serialize};
 /// the value of the game -- can be offered by any player the first time it is used. After that, it
 /// can only be offered by the player who last took the cube.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedCube")]
 pub struct Cube {
     exponential: u8,
     /// Owner of the cube
     owner: Player,
 }
 
+/// Represents a deserialized cube before its invariants have been validated
+#[derive(Deserialize)]
+struct UncheckedCube {
+    exponential: u8,
+    owner: Player,
+}
+
+impl TryFrom<UncheckedCube> for Cube {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedCube) -> Result<Self, Self::Error> {
+        if unchecked.exponential > 63 {
+            return Err(InvariantError::CubeValue {
+                exponent: unchecked.exponential,
+            });
+        }
+        Ok(Cube {
+            exponential: unchecked.exponential,
+            owner: unchecked.owner,
+        })
+    }
+}
+
 impl Cube {
     /// Returns the value of the cube
     pub fn value(&self) -> u64 {
@@ -31,8 +55,7 @@ impl Cube {
     /// technical limit of 2^64 on the value of the cube, which we believe is a reasonable limit.
     pub fn set(&mut self, value: u64) -> Result<(), Error> {
         if value.is_power_of_two() {
-            let vf = value as f64;
-            self.exponential = vf.sqrt().round() as u8;
+            self.exponential = value.trailing_zeros() as u8;
 
             Ok(())
         } else {
@@ -48,11 +71,23 @@ impl Cube {
     /// Calculate the next value of the cube to offer it to the opponent
     pub fn offer(&self, opponent: Player) -> Result<u64, Error> {
         if self.owner == Player::Nobody || self.owner != opponent {
-            Ok(2u64.pow(1 + self.exponential as u32))
+            2u64.checked_pow(1 + self.exponential as u32)
+                .ok_or(Error::CubeValueInvalid)
         } else {
             Err(Error::DoublingNotPermitted)
         }
     }
+
+    /// Accept the cube offered to a player, doubling its value and passing it to the player
+    pub fn accept(&mut self, player: Player) -> Result<(), Error> {
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        let value = self.offer(player)?;
+        self.set(value)?;
+        self.owner = player;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +132,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn set_large_values() -> Result<(), Error> {
+        let mut cube = Cube::default();
+        for exponent in 0..64 {
+            cube.set(1 << exponent)?;
+            assert_eq!(cube.value(), 1 << exponent);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn accept() -> Result<(), Error> {
+        let mut cube = Cube::default();
+        cube.accept(Player::Player1)?;
+        assert_eq!(cube.value(), 2);
+        assert_eq!(cube.owner(), Player::Player1);
+        // only the owner may redouble
+        assert!(cube.accept(Player::Player1).is_err());
+        cube.accept(Player::Player0)?;
+        assert_eq!(cube.value(), 4);
+        assert_eq!(cube.owner(), Player::Player0);
+        assert!(cube.accept(Player::Nobody).is_err());
+
+        cube.set(1 << 63)?;
+        assert!(cube.accept(Player::Player1).is_err());
+        Ok(())
+    }
+
     #[test]
     fn set_invalidvalue() -> Result<(), Error> {
         let mut cube = Cube::default();
@@ -144,4 +207,11 @@ mod tests {
         assert!(cube.offer(Player::Player1).is_err());
         Ok(())
     }
+
+    #[test]
+    fn deserialize() {
+        let cube: Cube = serde_json::from_str(r#"{"exponential":3,"owner":"Player0"}"#).unwrap();
+        assert_eq!(cube.value(), 8);
+        assert!(serde_json::from_str::<Cube>(r#"{"exponential":64,"owner":"Player0"}"#).is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::Error;
+use crate::{Error, InvariantError};
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
@@ -6,7 +6,6 @@ use serde::{Deserialize, Serialize};
 
 use super::Player;
 
-
 /// Represents a players in the game.
 #[derive(Debug, Clone)]
 pub struct Players {
@@ -17,24 +16,50 @@ pub struct Players {
 }
 
 impl Players {
-    /// Create a new player with a dice
+    /// Create the players with a dice each, and decide the first player with the opening roll.
+    ///
+    /// Each player rolls one dice, and both roll again on a tie. The player with the higher dice
+    /// plays first, with the two dices of the opening roll.
     pub fn new(first_seed: [u8; 32], second_seed: [u8; 32]) -> Self {
+        Players::opening(first_seed, second_seed, 0).0
+    }
+
+    /// Create the players as in [`Players::new`] with automatic doubles: each tie of the opening
+    /// roll doubles the cube, up to `limit` times. Returns the players together with the amount
+    /// of automatic doubles.
+    pub fn with_automatic_doubles(
+        first_seed: [u8; 32],
+        second_seed: [u8; 32],
+        limit: u8,
+    ) -> (Self, u8) {
+        Players::opening(first_seed, second_seed, limit)
+    }
+
+    /// Roll the opening roll, counting the ties up to the limit
+    fn opening(first_seed: [u8; 32], second_seed: [u8; 32], limit: u8) -> (Self, u8) {
         let mut player1 = PlayerWithDice::new(Player::Player0, first_seed);
         let mut player2 = PlayerWithDice::new(Player::Player1, second_seed);
-        let player1_dices = player1.roll();
-        let player2_dices = player2.roll();
-        let current = if player1_dices.values > player2_dices.values {
-            player1.dices = Some(player1_dices);
+        let mut ties = 0;
+        let (dice1, dice2) = loop {
+            let dices = (player1.roll_one(), player2.roll_one());
+            if dices.0 != dices.1 {
+                break dices;
+            }
+            ties = limit.min(ties + 1);
+        };
+        let current = if dice1 > dice2 {
+            player1.dices = Some(Dices::opening(dice1, dice2));
             player1.clone()
         } else {
-            player2.dices = Some(player2_dices);
+            player2.dices = Some(Dices::opening(dice2, dice1));
             player2.clone()
         };
-        Self {
+        let players = Self {
             player1,
             player2,
-            current
-        }
+            current,
+        };
+        (players, ties)
     }
 
     /// Switch the player
@@ -66,7 +91,16 @@ impl PlayerWithDice {
     /// Create a new player with a dice
     pub fn new(player: Player, seed: [u8; 32]) -> Self {
         let rng = StdRng::from_seed(seed);
-        Self { player, rng, dices: None }
+        Self {
+            player,
+            rng,
+            dices: None,
+        }
+    }
+
+    /// Roll one dice, e.g. for the opening roll
+    pub fn roll_one(&mut self) -> u8 {
+        Uniform::new_inclusive(1, 6).sample(&mut self.rng)
     }
 
     /// Roll the dice
@@ -79,10 +113,7 @@ impl PlayerWithDice {
             (false, false, true, true)
         };
 
-        Dices {
-            values,
-            consumed,
-        }
+        Dices { values, consumed }
     }
 }
 
@@ -90,6 +121,7 @@ impl PlayerWithDice {
 ///
 /// Backgammon is always played with two dices.
 #[derive(Debug, Clone, Copy, Serialize, PartialEq, Deserialize, Default)]
+#[serde(try_from = "UncheckedDices")]
 pub struct Dices {
     /// The two dice values
     pub values: (u8, u8),
@@ -99,6 +131,29 @@ pub struct Dices {
     pub consumed: (bool, bool, bool, bool),
 }
 
+/// Represents deserialized dices before their invariants have been validated
+#[derive(Deserialize)]
+struct UncheckedDices {
+    values: (u8, u8),
+    consumed: (bool, bool, bool, bool),
+}
+
+impl TryFrom<UncheckedDices> for Dices {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedDices) -> Result<Self, Self::Error> {
+        let values = unchecked.values;
+        let rolled = (1..=6).contains(&values.0) && (1..=6).contains(&values.1);
+        if values != (0, 0) && !rolled {
+            return Err(InvariantError::DiceValue { values });
+        }
+        Ok(Dices {
+            values,
+            consumed: unchecked.consumed,
+        })
+    }
+}
+
 impl Dices {
     /// Roll the dices which generates two random numbers between 1 and 6, replicating a perfect
     /// dice. We use the operating system's random number generator.
@@ -121,6 +176,48 @@ impl Dices {
             }
         }
     }
+
+    /// Get the dices of the opening roll, with the dice of the player starting first. The dices
+    /// differ, as ties are rolled again.
+    fn opening(own: u8, other: u8) -> Self {
+        Dices {
+            values: (own, other),
+            consumed: (false, false, true, true),
+        }
+    }
+
+    /// Check if a dice value has been rolled and not been consumed yet
+    pub fn is_available(&self, dice: u8) -> bool {
+        let (values, consumed) = (self.values, self.consumed);
+        (dice == values.0 && !consumed.0)
+            || (dice == values.1 && !consumed.1)
+            || (dice == values.1 && !consumed.2)
+            || (dice == values.1 && !consumed.3)
+    }
+
+    /// Check if all dices have been consumed
+    pub fn all_consumed(&self) -> bool {
+        self.consumed == (true, true, true, true)
+    }
+
+    /// Consume one dice of the given value, e.g. after moving a checker with it
+    pub fn consume(&mut self, dice: u8) -> Result<(), Error> {
+        let (values, consumed) = (self.values, &mut self.consumed);
+        if dice == values.0 && !consumed.0 {
+            consumed.0 = true;
+        } else if dice == values.1 && !consumed.1 {
+            consumed.1 = true;
+        } else if dice == values.1 && !consumed.2 {
+            consumed.2 = true;
+        } else if dice == values.1 && !consumed.3 {
+            consumed.3 = true;
+        } else if dice == values.0 || dice == values.1 {
+            return Err(Error::DiceAlreadyConsumed);
+        } else {
+            return Err(Error::DiceInvalid);
+        }
+        Ok(())
+    }
 }
 
 /// Trait to roll the dices
@@ -150,6 +247,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_opening_roll() {
+        let mut ties = 0;
+        for seed in 0..50 {
+            let (players, doubles) = Players::with_automatic_doubles([seed; 32], [seed + 1; 32], 1);
+            let dices = players.current.dices.unwrap_or_default();
+            // the opening dices are played, the higher one belonging to the first player
+            assert!(dices.values.0 > dices.values.1);
+            assert_eq!(dices.consumed, (false, false, true, true));
+            assert!(doubles <= 1);
+            ties += u32::from(doubles);
+
+            let players = Players::new([seed; 32], [seed + 1; 32]);
+            assert_eq!(players.current.dices, Some(dices));
+        }
+        assert!(ties > 0);
+    }
+
+    #[test]
+    fn test_consume() -> Result<(), Error> {
+        let mut dices = Dices {
+            values: (5, 2),
+            consumed: (false, false, true, true),
+        };
+        assert!(dices.is_available(2));
+        dices.consume(2)?;
+        assert!(!dices.is_available(2));
+        assert!(matches!(dices.consume(2), Err(Error::DiceAlreadyConsumed)));
+        assert!(matches!(dices.consume(3), Err(Error::DiceInvalid)));
+        assert!(!dices.all_consumed());
+        dices.consume(5)?;
+        assert!(dices.all_consumed());
+        Ok(())
+    }
+
+    #[test]
+    fn test_consume_doubles() -> Result<(), Error> {
+        let mut dices = Dices {
+            values: (4, 4),
+            consumed: (false, false, false, false),
+        };
+        for _ in 0..4 {
+            dices.consume(4)?;
+        }
+        assert!(dices.all_consumed());
+        assert!(matches!(dices.consume(4), Err(Error::DiceAlreadyConsumed)));
+        Ok(())
+    }
+
     #[test]
     fn test_roll_consumed1() {
         for _i in 0..100 {
@@ -161,4 +307,23 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_deserialize() {
+        let dices: Dices =
+            serde_json::from_str(r#"{"values":[3,1],"consumed":[false,false,true,true]}"#).unwrap();
+        assert_eq!(dices.values, (3, 1));
+        assert!(serde_json::from_str::<Dices>(
+            r#"{"values":[0,0],"consumed":[false,false,false,false]}"#
+        )
+        .is_ok());
+        assert!(serde_json::from_str::<Dices>(
+            r#"{"values":[7,1],"consumed":[false,false,true,true]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Dices>(
+            r#"{"values":[0,1],"consumed":[false,false,true,true]}"#
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,174 @@
+use crate::rules::{Board, Player};
+use crate::Error;
+
+/// The base64 alphabet used by GNU Backgammon
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as base64 without padding
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(6));
+    for chunk in bytes.chunks(3) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let value = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64[(value >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Decode base64 without padding into the given amount of bytes
+pub(crate) fn decode_base64<const N: usize>(encoded: &str) -> Option<[u8; N]> {
+    if encoded.len() != (N * 8).div_ceil(6) {
+        return None;
+    }
+    let mut bits = 0u32;
+    let mut count = 0;
+    let mut bytes = [0; N];
+    let mut i = 0;
+    for c in encoded.bytes() {
+        let value = BASE64.iter().position(|&b| b == c)? as u32;
+        bits = bits << 6 | value;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes[i] = (bits >> count) as u8;
+            i += 1;
+        }
+    }
+    // the unused low bits have to be zero for a canonical encoding
+    (bits & ((1 << count) - 1) == 0).then_some(bytes)
+}
+
+impl Board {
+    /// Get the GNU Backgammon Position ID of the board, with the given player on roll.
+    ///
+    /// The ID encodes the checkers of both players, first those of the opponent of the player on
+    /// roll, then those of the player on roll. For each player the fields are visited from the
+    /// 1-point to the 24-point and then the bar, appending a one bit for each checker followed by
+    /// a zero bit. The 80 bits are stored least significant bit first in ten bytes, which are
+    /// encoded as 14 characters of base64.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// let id = Board::new().to_gnubg_position_id(Player::Player0).unwrap();
+    /// assert_eq!(id, "4HPwATDgc/ABMA");
+    /// ```
+    pub fn to_gnubg_position_id(&self, player: Player) -> Result<String, Error> {
+        let mut key = [0u8; 10];
+        let mut bit = 0;
+        for side in [player.other(), player] {
+            let (fields, bar, _) = self.raw(side)?;
+            for count in fields.into_iter().chain([bar]) {
+                for _ in 0..count {
+                    if bit >= 80 {
+                        return Err(Error::PositionInvalid);
+                    }
+                    key[bit / 8] |= 1 << (bit % 8);
+                    bit += 1;
+                }
+                bit += 1;
+            }
+        }
+        if bit > 80 {
+            return Err(Error::PositionInvalid);
+        }
+        Ok(encode_base64(&key))
+    }
+
+    /// Create a board from a GNU Backgammon Position ID with the given player on roll, see
+    /// [`Board::to_gnubg_position_id`]. Checkers not on the board or the bar are borne off.
+    pub fn from_gnubg_position_id(id: &str, player: Player) -> Result<Board, Error> {
+        if player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        let key: [u8; 10] = decode_base64(id).ok_or(Error::PositionInvalid)?;
+        let mut bits = (0..80).map(|bit| key[bit / 8] >> (bit % 8) & 1 == 1);
+
+        let mut sides = [([0u8; 24], 0u8, 0u8); 2];
+        for side in sides.iter_mut() {
+            let mut counts = [0u8; 25];
+            for count in counts.iter_mut() {
+                while bits.next().ok_or(Error::PositionInvalid)? {
+                    *count += 1;
+                }
+            }
+            let total: u8 = counts
+                .iter()
+                .try_fold(0u8, |sum, &x| sum.checked_add(x))
+                .ok_or(Error::PositionInvalid)?;
+            if total > 15 {
+                return Err(Error::PositionInvalid);
+            }
+            side.0.copy_from_slice(&counts[..24]);
+            side.1 = counts[24];
+            side.2 = 15 - total;
+        }
+        if bits.any(|bit| bit) {
+            return Err(Error::PositionInvalid);
+        }
+
+        let [opponent, on_roll] = sides;
+        let board = match player {
+            Player::Player0 => Board::from_raw(on_roll, opponent),
+            _ => Board::from_raw(opponent, on_roll),
+        };
+        board.validate().map_err(|_| Error::PositionInvalid)?;
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64() {
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE");
+        assert_eq!(decode_base64::<2>("TWE"), Some(*b"Ma"));
+        assert_eq!(decode_base64::<2>("TWF"), None);
+        assert_eq!(decode_base64::<2>("TW"), None);
+        assert_eq!(decode_base64::<2>("TW!"), None);
+    }
+
+    #[test]
+    fn starting_position() -> Result<(), Error> {
+        let board = Board::from_gnubg_position_id("4HPwATDgc/ABMA", Player::Player1)?;
+        assert_eq!(board, Board::new());
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 7, -1)?;
+        board.set(Player::Player0, 4, 1)?;
+        board.set(Player::Player1, 23, -1)?;
+        board.set_bar(Player::Player1, 1)?;
+        board.set(Player::Player1, 12, -2)?;
+        board.set_off(Player::Player1, 2)?;
+        for player in [Player::Player0, Player::Player1] {
+            let id = board.to_gnubg_position_id(player)?;
+            assert_eq!(id.len(), 14);
+            assert_eq!(Board::from_gnubg_position_id(&id, player)?, board);
+        }
+        // the player on roll changes the ID
+        assert_ne!(
+            board.to_gnubg_position_id(Player::Player0)?,
+            board.to_gnubg_position_id(Player::Player1)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Board::from_gnubg_position_id("4HPwATDgc/ABM", Player::Player0).is_err());
+        assert!(Board::from_gnubg_position_id("4HPwATDgc/AB!A", Player::Player0).is_err());
+        assert!(Board::from_gnubg_position_id("////////////AA", Player::Player0).is_err());
+        assert!(Board::from_gnubg_position_id("4HPwATDgc/ABMA", Player::Nobody).is_err());
+        assert!(Board::new().to_gnubg_position_id(Player::Nobody).is_err());
+    }
+}
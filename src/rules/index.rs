@@ -0,0 +1,240 @@
+use crate::rules::{Board, Player};
+use crate::Error;
+
+/// Binomial coefficient n over k
+fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Maps positions of a few checkers to dense integers and back.
+///
+/// A position is given as the amount of checkers of one player on each of `points` points, with
+/// at most `checkers` checkers in total, all others being off the board. The index enumerates
+/// all such positions from 0 to [`PositionIndex::len`] without gaps, so it can address plain
+/// arrays, e.g. bearoff databases or tables of exact dynamic-programming solvers.
+///
+/// The index is the rank of the position in the combinatorial number system: writing the
+/// position as a sequence of checkers and point separators, starting from the last point, the
+/// index is the sum of `binomial(s, i + 1)` over the position `s` of the `i`th separator. The
+/// empty position has index 0, and positions with fewer checkers come first: all positions with
+/// at most `m` checkers have an index below `PositionIndex::new(points, m).len()`. This mapping
+/// is part of the stable API and will not change.
+///
+/// ```
+/// use backgammon::rules::PositionIndex;
+///
+/// let index = PositionIndex::bearoff(15);
+/// assert_eq!(index.len(), 54264);
+/// let position = [2, 3, 0, 0, 0, 1];
+/// let i = index.index(&position).unwrap();
+/// assert_eq!(index.position(i).unwrap(), position);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PositionIndex {
+    points: usize,
+    checkers: u8,
+}
+
+impl PositionIndex {
+    /// Create an index for positions of up to `checkers` checkers on `points` points
+    pub fn new(points: usize, checkers: u8) -> Self {
+        PositionIndex { points, checkers }
+    }
+
+    /// Create an index for bearoff positions, i.e. up to `checkers` checkers in the home board
+    pub fn bearoff(checkers: u8) -> Self {
+        PositionIndex::new(6, checkers)
+    }
+
+    /// Create an index for one side of a Hypergammon position, i.e. up to three checkers on the
+    /// 24 fields and the bar, the bar being the last point.
+    pub fn hypergammon() -> Self {
+        PositionIndex::new(25, 3)
+    }
+
+    /// Get the amount of positions, i.e. the range of the index
+    pub fn len(&self) -> u64 {
+        binomial(
+            self.points as u64 + self.checkers as u64,
+            self.points as u64,
+        )
+    }
+
+    /// Check if the index covers no positions at all, which is never the case
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Get the index of a position. Returns `None` if the position does not have exactly
+    /// `points` entries or holds too many checkers.
+    pub fn index(&self, position: &[u8]) -> Option<u64> {
+        let total: u32 = position.iter().map(|&x| x as u32).sum();
+        if position.len() != self.points || total > self.checkers as u32 {
+            return None;
+        }
+
+        let mut separator = 0;
+        let mut index = 0;
+        for (i, &amount) in position.iter().rev().enumerate() {
+            separator += amount as u64;
+            index += binomial(separator, i as u64 + 1);
+            separator += 1;
+        }
+        Some(index)
+    }
+
+    /// Get the position of an index. Returns `None` if the index is out of range.
+    pub fn position(&self, index: u64) -> Option<Vec<u8>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut separators = vec![0; self.points];
+        let mut rest = index;
+        let mut upper = self.points as u64 + self.checkers as u64;
+        for i in (0..self.points).rev() {
+            let mut s = upper - 1;
+            while binomial(s, i as u64 + 1) > rest {
+                s -= 1;
+            }
+            rest -= binomial(s, i as u64 + 1);
+            separators[i] = s;
+            upper = s;
+        }
+
+        let mut previous = None;
+        let mut position: Vec<u8> = separators
+            .iter()
+            .map(|&s| {
+                let amount = match previous {
+                    Some(p) => s - p - 1,
+                    None => s,
+                };
+                previous = Some(s);
+                amount as u8
+            })
+            .collect();
+        position.reverse();
+        Some(position)
+    }
+
+    /// Get the index of the checkers of a player on a board.
+    ///
+    /// For indexes of up to 24 points, the fields 0 to `points - 1` of the player are used and
+    /// all checkers have to be on these fields or off. For an index of 25 points, the bar is the
+    /// last point. Returns `None` if the checkers do not fit the index.
+    pub fn board_index(&self, board: &Board, player: Player) -> Result<Option<u64>, Error> {
+        let (fields, bar, _) = board.raw(player)?;
+        let mut position: Vec<u8> = fields.to_vec();
+        position.push(bar);
+        if position[self.points.min(25)..].iter().any(|&x| x > 0) {
+            return Ok(None);
+        }
+        position.truncate(self.points);
+        Ok(self.index(&position))
+    }
+
+    /// Get the board holding the positions of the indexes of both players, all other checkers of
+    /// the total of 15 per player being off. Returns `None` if an index is out of range, the
+    /// index has more than 25 points, or both players have checkers on the same field.
+    pub fn board(&self, player0: u64, player1: u64) -> Option<Board> {
+        if self.points > 25 {
+            return None;
+        }
+        let raw = |index: u64| {
+            let position = self.position(index)?;
+            let mut fields = [0; 24];
+            let mut bar = 0;
+            for (i, &amount) in position.iter().enumerate() {
+                if i < 24 {
+                    fields[i] = amount;
+                } else {
+                    bar = amount;
+                }
+            }
+            let on_board: u8 = position.iter().sum();
+            Some((fields, bar, 15u8.checked_sub(on_board)?))
+        };
+
+        let (player0, player1) = (raw(player0)?, raw(player1)?);
+        if (0..24).any(|field| player0.0[field] > 0 && player1.0[23 - field] > 0) {
+            return None;
+        }
+        Some(Board::from_raw(player0, player1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomials() {
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(2, 5), 0);
+        assert_eq!(binomial(21, 6), 54264);
+    }
+
+    #[test]
+    fn round_trip() {
+        let index = PositionIndex::new(6, 3);
+        assert_eq!(index.len(), 84);
+        for i in 0..index.len() {
+            let position = index.position(i).unwrap();
+            assert!(position.iter().sum::<u8>() <= 3);
+            assert_eq!(index.index(&position), Some(i));
+        }
+        assert_eq!(index.position(84), None);
+    }
+
+    #[test]
+    fn fewer_checkers_first() {
+        let small = PositionIndex::bearoff(2);
+        let large = PositionIndex::bearoff(4);
+        for i in 0..small.len() {
+            assert_eq!(small.position(i), large.position(i));
+        }
+    }
+
+    #[test]
+    fn stable_values() {
+        let index = PositionIndex::bearoff(15);
+        assert_eq!(index.index(&[0; 6]), Some(0));
+        assert_eq!(index.index(&[1, 0, 0, 0, 0, 0]), Some(1));
+        assert_eq!(index.index(&[0, 1, 0, 0, 0, 0]), Some(2));
+        assert_eq!(index.index(&[0, 0, 0, 0, 0, 1]), Some(6));
+        assert_eq!(index.index(&[2, 0, 0, 0, 0, 0]), Some(7));
+        assert_eq!(index.index(&[0, 0, 0, 0, 0, 15]), Some(54263));
+        assert_eq!(index.index(&[0, 0, 0, 0, 0, 16]), None);
+        assert_eq!(index.index(&[0; 5]), None);
+    }
+
+    #[test]
+    fn hypergammon_boards() -> Result<(), Error> {
+        let index = PositionIndex::hypergammon();
+        assert_eq!(index.len(), 3276);
+
+        let mut position = vec![0; 25];
+        position[23] = 1;
+        position[22] = 1;
+        position[24] = 1;
+        let i = index.index(&position).unwrap();
+        let board = index.board(i, i).unwrap();
+        assert_eq!(board.get().off, (12, 12));
+        assert_eq!(board.get().bar, (1, 1));
+        assert_eq!(index.board_index(&board, Player::Player0)?, Some(i));
+        assert_eq!(index.board_index(&board, Player::Player1)?, Some(i));
+        assert_eq!(index.board_index(&Board::new(), Player::Player0)?, None);
+
+        position[24] = 0;
+        position[0] = 1;
+        let j = index.index(&position).unwrap();
+        assert_eq!(index.board(j, j), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use crate::rules::{Board, Player};
+use crate::Error;
+use serde::{Deserialize, Serialize};
+
+/// Represents the distribution of the checkers of a player, e.g. as input of race formulas,
+/// tutors, or heuristic evaluators, see [`Board::metrics`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PositionMetrics {
+    /// The crossovers needed to bring all checkers home, see [`Board::crossovers`]
+    pub crossovers: u16,
+    /// The checkers in the four quadrants, from the home board to the opponent's home board
+    pub quadrants: [u8; 4],
+    /// The checkers on the bar
+    pub bar: u8,
+    /// The checkers borne off
+    pub off: u8,
+    /// The checkers stacked beyond the third one on a point, summed over all points
+    pub stacking: u8,
+}
+
+/// Checkers of a player on the fields 0 to 23, numbered from that player's perspective, and on
+/// the bar
+fn checkers(board: &Board, player: Player) -> Result<([u8; 24], u8), Error> {
+    let (fields, bar, _) = board.raw(player)?;
+    Ok((fields, bar))
+}
+
+/// The rearmost checker of a player, 0 to 23 for a field and 24 for the bar
+fn rearmost(board: &Board, player: Player) -> Result<Option<usize>, Error> {
+    let (fields, bar) = checkers(board, player)?;
+    if bar > 0 {
+        return Ok(Some(24));
+    }
+    Ok(fields.iter().rposition(|&x| x > 0))
+}
+
+/// Fields blocked by the opponent, numbered from the player's perspective
+fn blocked_fields(board: &Board, player: Player) -> Result<[bool; 24], Error> {
+    let (opponent, _) = checkers(board, player.other())?;
+    let mut blocked = [false; 24];
+    for (i, field) in blocked.iter_mut().enumerate() {
+        *field = opponent[23 - i] > 1;
+    }
+    Ok(blocked)
+}
+
+/// Check if a checker on field `from` (24 for the bar) can land on a field lower than `target`
+/// with the given roll, only using open fields to land on
+fn escapes_with(blocked: &[bool; 24], from: usize, target: usize, dices: (usize, usize)) -> bool {
+    let open = |field: usize| !blocked[field];
+    let steps: Vec<Vec<usize>> = if dices.0 == dices.1 {
+        vec![vec![dices.0; 4]]
+    } else {
+        vec![vec![dices.0, dices.1], vec![dices.1, dices.0]]
+    };
+    steps.iter().any(|steps| {
+        let mut field = from;
+        for &step in steps {
+            if field < step || !open(field - step) {
+                return false;
+            }
+            field -= step;
+            if field < target {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+impl Board {
+    /// Get the pip count of a player, i.e. the amount of pips the player needs to bear off all
+    /// checkers. A checker on a field needs the field number plus one pips, a checker on the bar
+    /// 25 pips.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// assert_eq!(Board::new().pip_count(Player::Player0).unwrap(), 167);
+    /// ```
+    pub fn pip_count(&self, player: Player) -> Result<u16, Error> {
+        let (fields, bar) = checkers(self, player)?;
+        let on_board: u16 = fields
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (i as u16 + 1) * x as u16)
+            .sum();
+        Ok(on_board + 25 * bar as u16)
+    }
+
+    /// Get the pip counts of player 0 and player 1
+    pub fn pip_counts(&self) -> (u16, u16) {
+        let count = |player| self.pip_count(player).unwrap_or_default();
+        (count(Player::Player0), count(Player::Player1))
+    }
+
+    /// Check if there is still contact, i.e. if a checker of one player still has to pass a
+    /// checker of the other player. Once contact is broken, the game is a pure race.
+    pub fn has_contact(&self) -> bool {
+        self.last_point_of_contact(Player::Player0)
+            .map(|point| point.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Get the last point of contact for a player.
+    ///
+    /// This is the point of the opponent's rearmost checker, which the player's checkers still
+    /// need to pass. The point is numbered from 1 to 24 from the player's perspective, i.e. the
+    /// field number plus one, with 0 standing for the opponent's bar. If there is no contact,
+    /// `None` is returned.
+    pub fn last_point_of_contact(&self, player: Player) -> Result<Option<u8>, Error> {
+        let own = rearmost(self, player)?;
+        let opponent = rearmost(self, player.other())?;
+        match (own, opponent) {
+            (Some(own), Some(opponent)) => {
+                // the opponent's rearmost checker as point of the player, 0 for the bar
+                let point = 24 - opponent as u8;
+                if own as u8 + 1 > point {
+                    Ok(Some(point))
+                } else {
+                    Ok(None)
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Get the amount of crossovers a player needs to bring all checkers home.
+    ///
+    /// A crossover is the move of a checker from one quadrant of the board into the next one. A
+    /// checker in the outer board needs one crossover, a checker in the opponent's home board
+    /// three, and a checker on the bar four.
+    pub fn crossovers(&self, player: Player) -> Result<u16, Error> {
+        let (fields, bar) = checkers(self, player)?;
+        let on_board: u16 = fields
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (i / 6) as u16 * x as u16)
+            .sum();
+        Ok(on_board + 4 * bar as u16)
+    }
+
+    /// Get the metrics of the distribution of the checkers of a player.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player};
+    ///
+    /// let metrics = Board::new().metrics(Player::Player0).unwrap();
+    /// assert_eq!(metrics.crossovers, 19);
+    /// assert_eq!(metrics.quadrants, [5, 3, 5, 2]);
+    /// assert_eq!(metrics.stacking, 4);
+    /// ```
+    pub fn metrics(&self, player: Player) -> Result<PositionMetrics, Error> {
+        let (fields, bar, off) = self.raw(player)?;
+        let mut quadrants = [0; 4];
+        for (i, &x) in fields.iter().enumerate() {
+            quadrants[i / 6] += x;
+        }
+        Ok(PositionMetrics {
+            crossovers: self.crossovers(player)?,
+            quadrants,
+            bar,
+            off,
+            stacking: fields.iter().map(|&x| x.saturating_sub(3)).sum(),
+        })
+    }
+}
+
+impl Board {
+    /// Get the amount of points a player has made in the home board, i.e. fields 0 to 5 holding
+    /// at least two checkers.
+    pub fn home_board_points(&self, player: Player) -> Result<u8, Error> {
+        let (fields, _) = checkers(self, player)?;
+        Ok(fields[..6].iter().filter(|&&x| x > 1).count() as u8)
+    }
+
+    /// Get the amount of rolls, out of 36, with which the opponent can enter a checker from the
+    /// bar against the home board of a player. Against a closed board, this is 0.
+    pub fn entering_rolls(&self, player: Player) -> Result<u8, Error> {
+        let points = self.home_board_points(player)?;
+        Ok(36 - points * points)
+    }
+
+    /// Get the amount of rolls, out of 36, with which the rearmost checker of a player escapes.
+    ///
+    /// The checker escapes, if it can move beyond all points made by the opponent within the
+    /// twelve fields in front of it, landing only on open fields. The more points the opponent
+    /// has made in front of the checker, the better it is contained. A checker with no points in
+    /// front of it, or a player without checkers on the board, escapes with all 36 rolls.
+    pub fn escapes(&self, player: Player) -> Result<u8, Error> {
+        let from = match rearmost(self, player)? {
+            Some(from) => from,
+            None => return Ok(36),
+        };
+        let blocked = blocked_fields(self, player)?;
+        let target = match (from.saturating_sub(12)..from)
+            .filter(|&field| blocked[field])
+            .min()
+        {
+            Some(target) => target,
+            None => return Ok(36),
+        };
+
+        let mut rolls = 0;
+        for first in 1..=6 {
+            for second in 1..=6 {
+                if escapes_with(&blocked, from, target, (first, second)) {
+                    rolls += 1;
+                }
+            }
+        }
+        Ok(rolls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Race position: player 0 on fields 0 to 4, player 1 on its fields 0 to 4
+    fn race() -> Result<Board, Error> {
+        let mut board = Board::new();
+        for player in [Player::Player0, Player::Player1] {
+            for (field, amount) in [(5, 5), (7, 3), (12, 5), (23, 2)] {
+                board.set(player, field, -amount)?;
+            }
+            for field in 1..=4 {
+                board.set(player, field, 3)?;
+            }
+            board.set_off(player, 3)?;
+        }
+        Ok(board)
+    }
+
+    #[test]
+    fn contact_at_start() -> Result<(), Error> {
+        let board = Board::new();
+        assert!(board.has_contact());
+        assert_eq!(board.last_point_of_contact(Player::Player0)?, Some(1));
+        assert_eq!(board.last_point_of_contact(Player::Player1)?, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn no_contact_in_race() -> Result<(), Error> {
+        let board = race()?;
+        assert!(!board.has_contact());
+        assert_eq!(board.last_point_of_contact(Player::Player0)?, None);
+        assert_eq!(board.crossovers(Player::Player0)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn contact_with_bar() -> Result<(), Error> {
+        let mut board = race()?;
+        board.set(Player::Player1, 1, -1)?;
+        board.set_bar(Player::Player1, 1)?;
+        assert!(board.has_contact());
+        assert_eq!(board.last_point_of_contact(Player::Player0)?, Some(0));
+        assert_eq!(board.last_point_of_contact(Player::Player1)?, Some(20));
+        assert_eq!(board.crossovers(Player::Player1)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn pip_count() -> Result<(), Error> {
+        let mut board = Board::new();
+        assert_eq!(board.pip_counts(), (167, 167));
+        board.set(Player::Player1, 23, -1)?;
+        board.set_bar(Player::Player1, 1)?;
+        assert_eq!(board.pip_counts(), (167, 168));
+        assert_eq!(race()?.pip_count(Player::Player0)?, 42);
+        assert!(board.pip_count(Player::Nobody).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn metrics() -> Result<(), Error> {
+        let metrics = race()?.metrics(Player::Player1)?;
+        assert_eq!(metrics.crossovers, 0);
+        assert_eq!(metrics.quadrants, [12, 0, 0, 0]);
+        assert_eq!((metrics.bar, metrics.off), (0, 3));
+        assert_eq!(metrics.stacking, 0);
+
+        let board = position(&[(23, 1), (0, 8)], &[(20, 2)])?;
+        let metrics = board.metrics(Player::Player0)?;
+        assert_eq!(metrics.quadrants, [8, 0, 0, 1]);
+        assert_eq!(metrics.crossovers, 3);
+        assert_eq!(metrics.stacking, 5);
+        assert!(board.metrics(Player::Nobody).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn crossovers_at_start() -> Result<(), Error> {
+        let board = Board::new();
+        assert_eq!(board.crossovers(Player::Player0)?, 19);
+        assert_eq!(board.crossovers(Player::Player1)?, 19);
+        assert!(board.crossovers(Player::Nobody).is_err());
+        Ok(())
+    }
+
+    /// Position with the given checkers on the fields of both players, all others borne off
+    fn position(player0: &[(usize, i8)], player1: &[(usize, i8)]) -> Result<Board, Error> {
+        let mut board = Board::new();
+        for player in [Player::Player0, Player::Player1] {
+            for (field, amount) in [(5, 5), (7, 3), (12, 5), (23, 2)] {
+                board.set(player, field, -amount)?;
+            }
+        }
+        for (player, fields) in [(Player::Player0, player0), (Player::Player1, player1)] {
+            for &(field, amount) in fields {
+                board.set(player, field, amount)?;
+            }
+            let amount: i8 = fields.iter().map(|(_, amount)| amount).sum();
+            board.set_off(player, 15 - amount as u8)?;
+        }
+        Ok(board)
+    }
+
+    #[test]
+    fn home_board() -> Result<(), Error> {
+        let board = Board::new();
+        assert_eq!(board.home_board_points(Player::Player0)?, 1);
+        assert_eq!(board.entering_rolls(Player::Player0)?, 35);
+        assert!(board.home_board_points(Player::Nobody).is_err());
+
+        let fields: Vec<(usize, i8)> = (0..6).map(|field| (field, 2)).collect();
+        let board = position(&[(23, 1)], &fields)?;
+        assert_eq!(board.home_board_points(Player::Player1)?, 6);
+        assert_eq!(board.entering_rolls(Player::Player1)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn escapes() -> Result<(), Error> {
+        assert_eq!(race()?.escapes(Player::Player0)?, 36);
+
+        // behind a point on field 11, the checker on field 17 escapes with all non-doubles of 7 or
+        // more pips and with 4-4 and 5-5, other doubles land on the point
+        let board = position(&[(17, 1), (2, 3)], &[(12, 2), (3, 3)])?;
+        assert_eq!(board.escapes(Player::Player0)?, 20);
+        assert_eq!(board.escapes(Player::Player1)?, 36);
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_full_prime() -> Result<(), Error> {
+        let fields: Vec<(usize, i8)> = (1..7).map(|field| (field, 2)).collect();
+        let board = position(&[(23, 1)], &fields)?;
+        assert_eq!(board.escapes(Player::Player0)?, 0);
+        Ok(())
+    }
+}
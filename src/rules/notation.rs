@@ -0,0 +1,338 @@
+use crate::rules::board::is_forced;
+use crate::rules::{Board, Location, MoveChecker, Player, Turn};
+use crate::Error;
+use std::fmt;
+
+/// The number of the bar in the notation
+const BAR: u8 = 25;
+/// The number of the off in the notation
+const OFF: u8 = 0;
+
+/// Represents a part of a checker's way in the notation, e.g. `24/18*`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    from: u8,
+    to: u8,
+    hit: bool,
+}
+
+/// Get the number of a location from the perspective of a player, from 24 to 1 with the bar as
+/// 25 and the off as 0
+fn number(player: Player, location: Location) -> u8 {
+    match (location, player) {
+        (Location::Bar, _) => BAR,
+        (Location::Off, _) => OFF,
+        (Location::Point(point), Player::Player1) => 25 - point,
+        (Location::Point(point), _) => point,
+    }
+}
+
+/// Get the name of a number in the notation
+fn name(number: u8) -> String {
+    match number {
+        BAR => "bar".to_string(),
+        OFF => "off".to_string(),
+        point => point.to_string(),
+    }
+}
+
+/// Parse a point, the bar or the off, with an optional hit marker
+fn parse_point(s: &str) -> Result<(u8, bool), Error> {
+    let (s, hit) = match s.strip_suffix('*') {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let number = match s.to_ascii_lowercase().as_str() {
+        "bar" => BAR,
+        "off" => OFF,
+        s => match s.parse() {
+            Ok(point @ 1..=24) => point,
+            _ => return Err(Error::NotationInvalid),
+        },
+    };
+    Ok((number, hit))
+}
+
+/// Parse the notation into segments, expanding `a/b/c` into `a/b b/c` and repeating `a/b(n)`
+fn parse_segments(notation: &str) -> Result<Vec<Segment>, Error> {
+    let mut segments = Vec::new();
+    for token in notation.split_whitespace() {
+        let (path, repeat) = match token.strip_suffix(')').and_then(|t| t.split_once('(')) {
+            Some((path, count)) => match count.parse() {
+                Ok(count @ 1..=4) => (path, count),
+                _ => return Err(Error::NotationInvalid),
+            },
+            None => (token, 1),
+        };
+        let points = path
+            .split('/')
+            .map(parse_point)
+            .collect::<Result<Vec<_>, _>>()?;
+        if points.len() < 2 || points[0].1 {
+            return Err(Error::NotationInvalid);
+        }
+        for _ in 0..repeat {
+            for pair in points.windows(2) {
+                let (from, to) = (pair[0].0, pair[1].0);
+                if from <= to || to == BAR || from == OFF {
+                    return Err(Error::NotationInvalid);
+                }
+                segments.push(Segment {
+                    from,
+                    to,
+                    hit: pair[1].1,
+                });
+            }
+        }
+    }
+    Ok(segments)
+}
+
+/// Check if the moves can be joined into the segments, each segment being played by one or more
+/// consecutive moves of a checker
+fn covers(segments: &[Segment], moves: &[(u8, u8)]) -> bool {
+    let Some((first, rest)) = segments.split_first() else {
+        return moves.is_empty();
+    };
+    for (i, &(from, to)) in moves.iter().enumerate() {
+        if from != first.from || to < first.to {
+            continue;
+        }
+        let mut remaining = moves.to_vec();
+        let _ = remaining.remove(i);
+        let mut segments = rest.to_vec();
+        if to != first.to {
+            // the checker continues its way with the next move
+            segments.insert(0, Segment { from: to, ..*first });
+        }
+        if covers(&segments, &remaining) {
+            return true;
+        }
+    }
+    false
+}
+
+impl Turn {
+    /// Parse a turn of a player from standard notation, e.g. `24/18 13/11`, `bar/20*`, `6/off`
+    /// or `13/7(2)`.
+    ///
+    /// Points are numbered from the perspective of the player, from the 24-point to the 1-point,
+    /// and a checker moving several pips may be written as one move, e.g. `24/13`. Hit markers
+    /// are optional and only used to choose between plays passing different points. The turn is
+    /// checked against the board, see [`Turn::new`], and the empty notation is the turn of a
+    /// player who can not move.
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player, Turn};
+    ///
+    /// let board = Board::new();
+    /// let turn = Turn::parse("8/5 6/5", Player::Player0, &board, (3, 1)).unwrap();
+    /// assert_eq!(turn.to_string(), "8/5 6/5");
+    /// ```
+    pub fn parse(
+        notation: &str,
+        player: Player,
+        board: &Board,
+        dices: (u8, u8),
+    ) -> Result<Turn, Error> {
+        if !(1..=6).contains(&dices.0) || !(1..=6).contains(&dices.1) {
+            return Err(Error::DiceInvalid);
+        }
+        let segments = parse_segments(notation)?;
+        let hits: Vec<u8> = segments.iter().filter(|s| s.hit).map(|s| s.to).collect();
+
+        let mut found = None;
+        let plays = board.generate_diced_plays(player, dices)?;
+        let forced = is_forced(&plays);
+        for (play, _) in plays {
+            let moves: Vec<(u8, u8)> = play
+                .iter()
+                .map(|(_, m)| (number(player, m.from()), number(player, m.to())))
+                .collect();
+            if !covers(&segments, &moves) {
+                continue;
+            }
+            let (turn, _) = Turn::from_play(board, player, dices, play, forced)?;
+            let mut turn_hits: Vec<u8> = turn
+                .moves()
+                .iter()
+                .filter(|m| m.is_hit())
+                .map(|m| number(player, m.checker().to()))
+                .collect();
+            turn_hits.sort_unstable();
+            let mut marked = hits.clone();
+            marked.sort_unstable();
+            if turn_hits == marked {
+                return Ok(turn);
+            }
+            if found.is_none() {
+                found = Some(turn);
+            }
+        }
+        found.ok_or(Error::MoveInvalid)
+    }
+}
+
+/// Renders the turn in standard notation from the perspective of the player, see
+/// [`Turn::parse`]. Consecutive moves of a checker are joined unless it hits on the way, and
+/// identical moves are collapsed, e.g. `24/13 8/5*(2)`.
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let player = self.player();
+        // the way of each checker as points passed, with a hit marker for each point
+        let mut paths: Vec<Vec<(u8, bool)>> = Vec::new();
+        for m in self.moves() {
+            let checker = m.checker();
+            let (from, to) = (number(player, checker.from()), number(player, checker.to()));
+            let hit = m.is_hit();
+            // join the move to the way of a checker ending where it starts, or starting where it
+            // ends, keeping the points where a checker was hit
+            if let Some(path) = paths.iter_mut().find(|p| p[p.len() - 1].0 == from) {
+                if !path[path.len() - 1].1 {
+                    let _ = path.pop();
+                }
+                path.push((to, hit));
+            } else if let Some(path) = paths.iter_mut().find(|p| p[0].0 == to) {
+                if hit {
+                    path[0].1 = true;
+                } else {
+                    let _ = path.remove(0);
+                }
+                path.insert(0, (from, false));
+            } else {
+                paths.push(vec![(from, false), (to, hit)]);
+            }
+        }
+        paths.sort_by(|a, b| b.cmp(a));
+
+        let mut parts: Vec<String> = Vec::new();
+        let mut i = 0;
+        while i < paths.len() {
+            let count = paths[i..].iter().take_while(|p| **p == paths[i]).count();
+            let mut part = paths[i]
+                .iter()
+                .map(|&(point, hit)| if hit { name(point) + "*" } else { name(point) })
+                .collect::<Vec<_>>()
+                .join("/");
+            if count > 1 {
+                part.push_str(&format!("({})", count));
+            }
+            parts.push(part);
+            i += count;
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Renders the move in standard notation from the perspective of the player, e.g. `bar/20`
+impl fmt::Display for MoveChecker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let player = self.player();
+        write!(
+            f,
+            "{}/{}",
+            name(number(player, self.from())),
+            name(number(player, self.to()))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() -> Result<(), Error> {
+        let board = Board::new();
+        let turn = Turn::parse("24/18 13/11", Player::Player0, &board, (6, 2))?;
+        assert_eq!(turn.to_string(), "24/18 13/11");
+        let turn = Turn::parse("13/11 24/18", Player::Player1, &board, (2, 6))?;
+        assert_eq!(turn.to_string(), "24/18 13/11");
+
+        let turn = Turn::parse("24/13", Player::Player0, &board, (6, 5))?;
+        assert_eq!(turn.to_string(), "24/13");
+        let turn = Turn::parse("13/7(2) 8/2(2)", Player::Player0, &board, (6, 6))?;
+        assert_eq!(turn.to_string(), "13/7(2) 8/2(2)");
+        let turn = Turn::parse("13/8/3(2)", Player::Player0, &board, (5, 5))?;
+        assert_eq!(turn.to_string(), "13/3(2)");
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_all_plays() -> Result<(), Error> {
+        let board = Board::new();
+        for dices in [(3, 1), (6, 5), (4, 4), (2, 2)] {
+            for turn in board.legal_turns(Player::Player0, dices)? {
+                let parsed = Turn::parse(&turn.to_string(), Player::Player0, &board, dices)?;
+                assert_eq!(parsed.position_hash(), turn.position_hash());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bar_and_off() -> Result<(), Error> {
+        // player 1 has a blot on player 0's 21-point to be hit from the bar with a four
+        let mut fields0 = [0; 24];
+        fields0[0] = 14;
+        let mut fields1 = [0; 24];
+        fields1[3] = 1;
+        fields1[10] = 14;
+        let board = Board::from_raw((fields0, 1, 0), (fields1, 0, 0));
+        let turn = Turn::parse("bar/21*/20", Player::Player0, &board, (4, 1))?;
+        assert_eq!(turn.to_string(), "bar/21*/20");
+        assert!(turn.hits());
+        assert!(matches!(
+            Turn::parse("bar/21* 1/off", Player::Player0, &board, (4, 1)),
+            Err(Error::MoveInvalid)
+        ));
+
+        let mut fields0 = [0; 24];
+        fields0[5] = 14;
+        fields0[0] = 1;
+        let mut fields1 = [0; 24];
+        fields1[10] = 15;
+        let board = Board::from_raw((fields0, 0, 0), (fields1, 0, 0));
+        let turn = Turn::parse("6/off 1/OFF", Player::Player0, &board, (6, 1))?;
+        assert_eq!(turn.to_string(), "6/off 1/off");
+
+        let checker = MoveChecker::new(Player::Player1, Location::Bar, Location::Point(3))?;
+        assert_eq!(checker.to_string(), "bar/22");
+        Ok(())
+    }
+
+    #[test]
+    fn hit_on_the_way() -> Result<(), Error> {
+        // a blot of player 1 on player 0's 7-point, which can be passed or hit with 6-5
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set(Player::Player1, 17, 1)?;
+        let hit = Turn::parse("13/7*/2", Player::Player0, &board, (6, 5))?;
+        assert!(hit.hits());
+        assert_eq!(hit.to_string(), "13/7*/2");
+        let passed = Turn::parse("13/2", Player::Player0, &board, (6, 5))?;
+        assert!(!passed.hits());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        let board = Board::new();
+        for notation in [
+            "24", "24/", "18/24", "off/3", "25/20", "13/7(5)", "24*/18", "x/y",
+        ] {
+            assert!(matches!(
+                Turn::parse(notation, Player::Player0, &board, (6, 1)),
+                Err(Error::NotationInvalid)
+            ));
+        }
+        assert!(matches!(
+            Turn::parse("24/18", Player::Player0, &board, (6, 1)),
+            Err(Error::MoveInvalid)
+        ));
+        assert!(matches!(
+            Turn::parse("", Player::Player0, &board, (0, 1)),
+            Err(Error::DiceInvalid)
+        ));
+    }
+}
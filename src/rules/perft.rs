@@ -0,0 +1,200 @@
+use crate::rules::{Board, Player};
+use crate::Error;
+
+/// All 21 distinct rolls of two dices
+const ROLLS: [(u8, u8); 21] = [
+    (1, 1),
+    (2, 1),
+    (2, 2),
+    (3, 1),
+    (3, 2),
+    (3, 3),
+    (4, 1),
+    (4, 2),
+    (4, 3),
+    (4, 4),
+    (5, 1),
+    (5, 2),
+    (5, 3),
+    (5, 4),
+    (5, 5),
+    (6, 1),
+    (6, 2),
+    (6, 3),
+    (6, 4),
+    (6, 5),
+    (6, 6),
+];
+
+/// Count the distinct legal turn sequences starting from a position.
+///
+/// This is the Backgammon counterpart of the *perft* function known from chess engines and is
+/// used to verify the move generator against reference counts. At depth 1, it returns the amount
+/// of distinct plays `player` has with the given dices, where plays leading to the same position
+/// count once. At higher depths, each play is followed by all 21 distinct rolls of the opponent.
+/// A player who can not move still completes a turn, and a finished game ends the sequence.
+///
+/// ```
+/// use backgammon::rules::{perft, Board, Player};
+///
+/// let board = Board::new();
+/// assert!(perft(&board, Player::Player0, (3, 1), 1).unwrap() > 1);
+/// ```
+pub fn perft(board: &Board, player: Player, dices: (u8, u8), depth: u8) -> Result<u64, Error> {
+    if depth == 0 || board.is_finished() {
+        return Ok(1);
+    }
+
+    let plays = board.generate_plays(player, dices)?;
+    if depth == 1 {
+        return Ok(plays.len() as u64);
+    }
+
+    let mut count = 0;
+    for (_, next) in plays {
+        if next.is_finished() {
+            count += 1;
+            continue;
+        }
+        for roll in ROLLS {
+            count += perft(&next, player.other(), roll, depth - 1)?;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Board where player 0 has only the given checkers left, all others borne off
+    fn board_with(checkers: &[usize]) -> Result<Board, Error> {
+        let mut board = Board::new();
+        for (field, amount) in [(5, 5), (7, 3), (12, 5), (23, 2)] {
+            board.set(Player::Player0, field, -amount)?;
+        }
+        for &field in checkers {
+            board.set(Player::Player0, field, 1)?;
+        }
+        board.set_off(Player::Player0, 15 - checkers.len() as u8)?;
+        Ok(board)
+    }
+
+    #[test]
+    fn depth_zero() -> Result<(), Error> {
+        assert_eq!(perft(&Board::new(), Player::Player0, (6, 5), 0)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_player() {
+        assert!(perft(&Board::new(), Player::Nobody, (6, 5), 1).is_err());
+    }
+
+    #[test]
+    fn distinct_plays() -> Result<(), Error> {
+        // 10/7, 9/6 and 10/8 9/8
+        let board = board_with(&[10, 9])?;
+        assert_eq!(perft(&board, Player::Player0, (2, 1), 1)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn dancing() -> Result<(), Error> {
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set_bar(Player::Player0, 1)?;
+        assert_eq!(perft(&board, Player::Player0, (6, 6), 1)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn larger_dice() -> Result<(), Error> {
+        let mut board = board_with(&[12])?;
+        board.set(Player::Player1, 23, -2)?;
+        board.set(Player::Player1, 22, 2)?;
+        let plays = board.generate_plays(Player::Player0, (6, 5))?;
+        assert_eq!(plays.len(), 1);
+        assert_eq!(plays[0].1.get().board[6], 1);
+        Ok(())
+    }
+
+    #[test]
+    fn finished_game() -> Result<(), Error> {
+        let board = board_with(&[1])?;
+        assert_eq!(perft(&board, Player::Player0, (2, 1), 1)?, 1);
+        assert_eq!(perft(&board, Player::Player0, (2, 1), 2)?, 1);
+        Ok(())
+    }
+
+    /// Sum the counts of all 21 rolls, returning them per roll in the order of [`ROLLS`] as well
+    fn counts(board: &Board, depth: u8) -> Result<(Vec<u64>, u64), Error> {
+        let counts = ROLLS
+            .iter()
+            .map(|&roll| perft(board, Player::Player0, roll, depth))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total = counts.iter().sum();
+        Ok((counts, total))
+    }
+
+    // The reference counts were computed with an independent move generator, and agree with
+    // the 16 plays of an opening 3-1.
+
+    #[test]
+    fn opening_position() -> Result<(), Error> {
+        let (counts, total) = counts(&Board::new(), 1)?;
+        assert_eq!(
+            counts,
+            [42, 15, 75, 16, 17, 73, 14, 18, 17, 52, 8, 8, 9, 9, 4, 10, 14, 14, 14, 7, 11]
+        );
+        assert_eq!(total, 447);
+        for roll in ROLLS {
+            assert_eq!(
+                perft(&Board::new(), Player::Player0, roll, 1)?,
+                perft(&Board::new(), Player::Player1, roll, 1)?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn opening_position_depth_two() -> Result<(), Error> {
+        let (counts, total) = counts(&Board::new(), 2)?;
+        assert_eq!(
+            counts,
+            [
+                18498, 7070, 34136, 7459, 7974, 32063, 6586, 8409, 7988, 22620, 3744, 3691, 4118,
+                4194, 1742, 4701, 6647, 6659, 6613, 3300, 4570
+            ]
+        );
+        assert_eq!(total, 202782);
+        Ok(())
+    }
+
+    #[test]
+    fn bar_entry() -> Result<(), Error> {
+        // a checker of player 0 hit from the 24-point
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set_bar(Player::Player0, 1)?;
+        let (counts, total) = counts(&board, 1)?;
+        assert_eq!(
+            counts,
+            [20, 7, 41, 7, 9, 29, 7, 8, 9, 23, 5, 7, 6, 7, 10, 3, 3, 4, 4, 4, 1]
+        );
+        assert_eq!(total, 214);
+        Ok(())
+    }
+
+    #[test]
+    fn bear_off() -> Result<(), Error> {
+        // one checker of player 0 on each of the 2- to the 6-point, player 1 at the start
+        let (counts, total) = counts(&board_with(&[5, 4, 3, 2, 1])?, 1)?;
+        assert_eq!(
+            counts,
+            [7, 11, 5, 8, 9, 1, 6, 5, 5, 1, 3, 3, 2, 2, 1, 4, 4, 3, 2, 1, 1]
+        );
+        assert_eq!(total, 84);
+        Ok(())
+    }
+}
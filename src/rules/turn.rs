@@ -0,0 +1,365 @@
+use crate::rules::board::is_forced;
+use crate::rules::{Board, Hit, MoveChecker, Player, Variant};
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+
+/// Represents the complete play of a player in one turn
+///
+/// A turn holds the dices rolled and the checker moves in the order they were played, each with
+/// the dice used and whether it hit a checker of the opponent, as well as the hash of the
+/// resulting position. Turns are built from the legal plays of a board. A deserialized turn is
+/// only checked to move the checkers of its player by its dices, as the board it was played on
+/// is not part of it; check it against that board with [`Turn::new`] before trusting it to be
+/// legal, as [`Game::play`](crate::Game::play) does.
+///
+/// ```
+/// use backgammon::rules::{Board, Player, Turn};
+///
+/// let board = Board::new();
+/// let turn = board.legal_turns(Player::Player0, (6, 5)).unwrap()[0].clone();
+/// assert_eq!(turn.moves().len(), 2);
+///
+/// // a turn has to use both dices
+/// let six = board.generate_a_possible_moves(Player::Player0, 6).unwrap();
+/// assert!(Turn::new(&board, Player::Player0, (6, 5), vec![six[1].clone()]).is_err());
+/// ```
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedTurn")]
+pub struct Turn {
+    player: Player,
+    dices: (u8, u8),
+    moves: Vec<TurnMove>,
+    position: u64,
+    /// whether the turn was the only legal play, derived from the position and not compared
+    #[serde(default)]
+    forced: bool,
+}
+
+/// Represents a turn as deserialized, before its moves are checked against its dices
+#[derive(Deserialize)]
+struct UncheckedTurn {
+    player: Player,
+    dices: (u8, u8),
+    moves: Vec<TurnMove>,
+    position: u64,
+    #[serde(default)]
+    forced: bool,
+}
+
+impl TryFrom<UncheckedTurn> for Turn {
+    type Error = Error;
+
+    fn try_from(turn: UncheckedTurn) -> Result<Self, Self::Error> {
+        let (a, b) = turn.dices;
+        if !(1..=6).contains(&a) || !(1..=6).contains(&b) {
+            return Err(Error::DiceInvalid);
+        }
+        if turn.player == Player::Nobody {
+            return Err(Error::PlayerInvalid);
+        }
+        // each dice is used once, or up to four times when doubles were rolled
+        let mut unused = if a == b { vec![a; 4] } else { vec![a, b] };
+        for m in &turn.moves {
+            let used = unused
+                .iter()
+                .position(|&dice| dice == m.dice)
+                .ok_or(Error::DiceInvalid)?;
+            let _ = unused.swap_remove(used);
+            if m.checker.player() != turn.player {
+                return Err(Error::PlayerInvalid);
+            }
+            // a higher dice may only be used to bear off
+            let [from, to] = m.checker.to_bytes();
+            let fits = if to == 25 {
+                from < m.dice
+            } else {
+                from - to == m.dice
+            };
+            if !fits {
+                return Err(Error::MoveInvalid);
+            }
+        }
+        Ok(Turn {
+            player: turn.player,
+            dices: turn.dices,
+            moves: turn.moves,
+            position: turn.position,
+            forced: turn.forced,
+        })
+    }
+}
+
+impl PartialEq for Turn {
+    fn eq(&self, other: &Self) -> bool {
+        (self.player, self.dices, &self.moves, self.position)
+            == (other.player, other.dices, &other.moves, other.position)
+    }
+}
+
+impl Hash for Turn {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.player, self.dices, &self.moves, self.position).hash(state);
+    }
+}
+
+/// Represents one checker move of a [`Turn`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TurnMove {
+    dice: u8,
+    checker: MoveChecker,
+    hit: Option<Hit>,
+}
+
+impl Turn {
+    /// Create the turn of a player playing the given moves, in order, from a board
+    ///
+    /// The moves have to form a legal play for the dices, otherwise an error is returned. A
+    /// player who can not move plays the empty turn.
+    pub fn new(
+        board: &Board,
+        player: Player,
+        dices: (u8, u8),
+        moves: Vec<MoveChecker>,
+    ) -> Result<Self, Error> {
+        Turn::new_variant(board, player, dices, moves, Variant::Standard)
+    }
+
+    /// Create the turn of a player playing the given moves under the rules of a variant, see
+    /// [`Turn::new`]
+    pub fn new_variant(
+        board: &Board,
+        player: Player,
+        dices: (u8, u8),
+        moves: Vec<MoveChecker>,
+        variant: Variant,
+    ) -> Result<Self, Error> {
+        if !(1..=6).contains(&dices.0) || !(1..=6).contains(&dices.1) {
+            return Err(Error::DiceInvalid);
+        }
+        let plays = board.generate_variant_diced_plays(player, dices, variant)?;
+        let forced = is_forced(&plays);
+        let play = plays
+            .into_iter()
+            .map(|(play, _)| play)
+            .find(|play| play.iter().map(|(_, m)| m).eq(moves.iter()))
+            .ok_or(Error::MoveInvalid)?;
+        Ok(Turn::from_play(board, player, dices, play, forced)?.0)
+    }
+
+    /// Create a turn from a legal play, together with the resulting board, forced if it is the
+    /// only legal play
+    pub(crate) fn from_play(
+        board: &Board,
+        player: Player,
+        dices: (u8, u8),
+        play: Vec<(u8, MoveChecker)>,
+        forced: bool,
+    ) -> Result<(Self, Board), Error> {
+        let mut board = board.clone();
+        let mut moves = Vec::with_capacity(play.len());
+        for (dice, checker) in play {
+            let hit = board.apply_move(&checker)?.hit;
+            moves.push(TurnMove { dice, checker, hit });
+        }
+        let turn = Turn {
+            player,
+            dices,
+            moves,
+            position: board.canonical_hash(),
+            forced,
+        };
+        Ok((turn, board))
+    }
+
+    /// Get the player on turn
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// Get the dices rolled
+    pub fn dices(&self) -> (u8, u8) {
+        self.dices
+    }
+
+    /// Get the checker moves in the order they were played
+    pub fn moves(&self) -> &[TurnMove] {
+        &self.moves
+    }
+
+    /// Get the [`canonical hash`](`Board::canonical_hash`) of the position after the turn
+    pub fn position_hash(&self) -> u64 {
+        self.position
+    }
+
+    /// Check if the turn was forced, i.e. the only legal play of the player with the dices,
+    /// including the empty turn of a player who can not move. Forced turns can be played
+    /// automatically.
+    pub fn is_forced(&self) -> bool {
+        self.forced
+    }
+
+    /// Check if the player could not move
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Check if any move of the turn hit a checker of the opponent
+    pub fn hits(&self) -> bool {
+        self.moves.iter().any(TurnMove::is_hit)
+    }
+
+    /// Apply the turn to a board, leaving it untouched if the turn does not fit, see
+    /// [`Board::apply_turn`]
+    pub fn apply(&self, board: &mut Board) -> Result<(), Error> {
+        board.apply_turn(self)
+    }
+}
+
+impl TurnMove {
+    /// Get the dice used for the move
+    pub fn dice(&self) -> u8 {
+        self.dice
+    }
+
+    /// Get the checker move
+    pub fn checker(&self) -> &MoveChecker {
+        &self.checker
+    }
+
+    /// Get the checker of the opponent hit by the move, if any
+    pub fn hit(&self) -> Option<Hit> {
+        self.hit
+    }
+
+    /// Check if the move hit a checker of the opponent
+    pub fn is_hit(&self) -> bool {
+        self.hit.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The moves of a turn as loose checker moves
+    fn checkers(turn: &Turn) -> Vec<MoveChecker> {
+        turn.moves().iter().map(|m| m.checker().clone()).collect()
+    }
+
+    #[test]
+    fn test_new() -> Result<(), Error> {
+        let board = Board::new();
+        for (turn, after) in board.generate_plays(Player::Player0, (3, 1))? {
+            let rebuilt = Turn::new(&board, Player::Player0, (3, 1), checkers(&turn))?;
+            assert_eq!(rebuilt, turn);
+            assert_eq!(turn.position_hash(), after.canonical_hash());
+            assert_eq!(turn.moves().len(), 2);
+
+            let mut replayed = board.clone();
+            turn.apply(&mut replayed)?;
+            assert_eq!(replayed, after);
+        }
+        assert!(Turn::new(&board, Player::Player0, (3, 1), vec![]).is_err());
+        assert!(Turn::new(&board, Player::Player0, (0, 1), vec![]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_doubles() -> Result<(), Error> {
+        let board = Board::new();
+        for (turn, _) in board.generate_plays(Player::Player1, (2, 2))? {
+            assert_eq!(turn.moves().len(), 4);
+            assert!(turn.moves().iter().all(|m| m.dice() == 2));
+            assert_eq!(turn.dices(), (2, 2));
+            assert_eq!(turn.player(), Player::Player1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hit() -> Result<(), Error> {
+        // a blot of player 1 on field 20 from player 0's perspective
+        let mut board = Board::new();
+        board.set(Player::Player1, 23, -1)?;
+        board.set(Player::Player1, 3, 1)?;
+        let plays = board.generate_plays(Player::Player0, (3, 1))?;
+        assert!(plays.iter().any(|(turn, _)| turn.hits()));
+        assert!(plays.iter().any(|(turn, _)| !turn.hits()));
+        for (turn, after) in plays {
+            assert_eq!(turn.hits(), after.raw(Player::Player1)?.1 == 1);
+            for m in turn.moves().iter().filter(|m| m.is_hit()) {
+                assert_eq!(m.checker().to_bytes()[1], 20);
+                assert_eq!(m.hit().map(|hit| hit.point), Some(21));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> Result<(), Error> {
+        // player 0 on the bar against a closed board
+        let mut board = Board::new();
+        board.set(Player::Player0, 23, -1)?;
+        board.set_bar(Player::Player0, 1)?;
+        for field in 18..24 {
+            board.set(Player::Player1, 23 - field, 2)?;
+        }
+        let turn = Turn::new(&board, Player::Player0, (6, 5), vec![])?;
+        assert!(turn.is_empty());
+        assert!(!turn.hits());
+        assert!(turn.is_forced());
+        Ok(())
+    }
+
+    #[test]
+    fn test_forced() -> Result<(), Error> {
+        assert!(Board::new()
+            .legal_turns(Player::Player0, (3, 1))?
+            .iter()
+            .all(|turn| !turn.is_forced()));
+
+        // the last checker of player 0 is borne off with either dice
+        let mut fields = [0; 24];
+        fields[2] = 1;
+        let board = Board::from_raw((fields, 0, 14), Board::new().raw(Player::Player1)?);
+        let turns = board.legal_turns(Player::Player0, (6, 5))?;
+        assert_eq!(turns.len(), 1);
+        assert!(turns[0].is_forced());
+        let moves = checkers(&turns[0]);
+        let turn = Turn::new(&board, Player::Player0, (6, 5), moves)?;
+        assert!(turn.is_forced());
+
+        // the indicator is derived, so turns serialized without it are still equal
+        let mut unmarked = turn.clone();
+        unmarked.forced = false;
+        assert_eq!(unmarked, turn);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize() -> Result<(), Box<dyn std::error::Error>> {
+        let board = Board::new();
+        let turn = board.legal_turns(Player::Player0, (6, 5))?.remove(0);
+        let json = serde_json::to_value(&turn)?;
+        assert_eq!(serde_json::from_value::<Turn>(json.clone())?, turn);
+
+        // the same moves claimed with other dices
+        let mut tampered = json.clone();
+        tampered["dices"] = serde_json::json!([6, 6]);
+        assert!(serde_json::from_value::<Turn>(tampered).is_err());
+        let mut tampered = json.clone();
+        tampered["dices"] = serde_json::json!([4, 3]);
+        assert!(serde_json::from_value::<Turn>(tampered).is_err());
+        // a move not covering the pips of its dice
+        let mut tampered = json.clone();
+        tampered["moves"][0]["dice"] = tampered["moves"][1]["dice"].clone();
+        tampered["moves"][1]["dice"] = json["moves"][0]["dice"].clone();
+        assert!(serde_json::from_value::<Turn>(tampered).is_err());
+        // moves of the opponent
+        let mut tampered = json;
+        tampered["player"] = serde_json::to_value(Player::Player1)?;
+        assert!(serde_json::from_value::<Turn>(tampered).is_err());
+        Ok(())
+    }
+}
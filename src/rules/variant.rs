@@ -0,0 +1,230 @@
+use crate::rules::Board;
+use serde::{Deserialize, Serialize};
+
+/// Represents a variant of Backgammon, played with the same move rules from another starting
+/// position
+#[derive(
+    Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash, Serialize, Deserialize, Default,
+)]
+pub enum Variant {
+    /// Standard Backgammon
+    #[default]
+    Standard,
+    /// Nackgammon, in which each player has two checkers on the 24-point and two on the 23-point,
+    /// taken from the stacks on the 13-point and the 6-point
+    Nackgammon,
+    /// Hypergammon, in which each player has only 3 checkers, one each on the 24-point, the
+    /// 23-point and the 22-point
+    Hypergammon,
+    /// Acey-deucey, in which all checkers start off the board, kept on the bar, and enter in the
+    /// opponent's home board, also while other checkers of the player are on the board. After a
+    /// roll of 1-2 the player chooses doubles to play as well and rolls again, see
+    /// [`Game::choose_doubles`](crate::Game::choose_doubles).
+    AceyDeucey,
+}
+
+impl Variant {
+    /// All variants
+    pub const ALL: [Variant; 4] = [
+        Variant::Standard,
+        Variant::Nackgammon,
+        Variant::Hypergammon,
+        Variant::AceyDeucey,
+    ];
+
+    /// Get the amount of checkers of each player in the variant, all of which have to be borne
+    /// off to win
+    ///
+    /// ```
+    /// use backgammon::rules::Variant;
+    ///
+    /// assert_eq!(Variant::Nackgammon.checkers(), 15);
+    /// assert_eq!(Variant::Hypergammon.checkers(), 3);
+    /// ```
+    pub fn checkers(self) -> u8 {
+        match self {
+            Variant::Standard | Variant::Nackgammon | Variant::AceyDeucey => 15,
+            Variant::Hypergammon => 3,
+        }
+    }
+
+    /// Check if checkers on the bar may wait while the player moves other checkers, instead of
+    /// having to enter first
+    pub fn enters_freely(self) -> bool {
+        self == Variant::AceyDeucey
+    }
+
+    /// Check if the dices grant the bonus of choosing doubles and rolling again, i.e. a roll of
+    /// 1-2 in Acey-deucey
+    pub fn is_bonus_roll(self, dices: (u8, u8)) -> bool {
+        self == Variant::AceyDeucey && (dices == (1, 2) || dices == (2, 1))
+    }
+
+    /// Get the starting position of the variant
+    ///
+    /// ```
+    /// use backgammon::rules::{Board, Player, Variant};
+    ///
+    /// assert_eq!(Variant::Standard.board(), Board::new());
+    /// let board = Variant::Nackgammon.board();
+    /// assert_eq!(board.pip_count(Player::Player0).unwrap(), 194);
+    /// ```
+    pub fn board(self) -> Board {
+        match self {
+            Variant::Standard => Board::new(),
+            Variant::Nackgammon => {
+                let mut fields = [0; 24];
+                for (field, amount) in [(5, 4), (7, 3), (12, 4), (22, 2), (23, 2)] {
+                    fields[field] = amount;
+                }
+                Board::from_raw((fields, 0, 0), (fields, 0, 0))
+            }
+            Variant::Hypergammon => {
+                let mut fields = [0; 24];
+                fields[21..].fill(1);
+                Board::from_raw((fields, 0, 0), (fields, 0, 0))
+            }
+            Variant::AceyDeucey => Board::from_raw(([0; 24], 15, 0), ([0; 24], 15, 0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Location, Player, WinKind};
+    use crate::{Error, InvariantError};
+
+    #[test]
+    fn nackgammon() {
+        let board = Variant::Nackgammon.board();
+        assert!(board.validate().is_ok());
+        assert_eq!(board.pip_counts(), (194, 194));
+        let display = board.get();
+        assert_eq!(display.board[22], 2);
+        assert_eq!(display.board[1], -2);
+        assert!(board.has_contact());
+        assert_eq!(board.home_board_points(Player::Player1).ok(), Some(1));
+    }
+
+    #[test]
+    fn acey_deucey() -> Result<(), Error> {
+        let board = Variant::AceyDeucey.board();
+        assert!(board.validate().is_ok());
+        assert_eq!(board.get().bar, (15, 15));
+        assert!(!board.is_finished());
+
+        // a checker entered may move on while others still wait on the bar
+        let variant = Variant::AceyDeucey;
+        let mut entered = board.clone();
+        entered.set_bar(Player::Player0, -1)?;
+        entered.set(Player::Player0, 20, 1)?;
+        let moves = entered.generate_variant_moves(Player::Player0, 4, variant)?;
+        let froms: Vec<_> = moves.iter().map(|m| m.from()).collect();
+        assert_eq!(froms, [Location::Bar, Location::Point(21)]);
+        assert_eq!(
+            entered.generate_a_possible_moves(Player::Player0, 4)?.len(),
+            1
+        );
+        assert!(!entered.can_bear_off(Player::Player0)?);
+
+        assert!(variant.is_bonus_roll((2, 1)));
+        assert!(!variant.is_bonus_roll((1, 1)));
+        assert!(!Variant::Standard.is_bonus_roll((1, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn starting_positions() {
+        for variant in Variant::ALL {
+            let board = variant.board();
+            assert!(board.validate_variant(variant).is_ok());
+            for other in Variant::ALL {
+                let valid = board.validate_variant(other).is_ok();
+                assert_eq!(valid, variant.checkers() == other.checkers());
+            }
+        }
+        assert!(matches!(
+            Board::new().validate_variant(Variant::Hypergammon),
+            Err(InvariantError::CheckerCount {
+                player: Player::Player0,
+                count: 15,
+                expected: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn starting_structures() -> Result<(), Error> {
+        for variant in Variant::ALL {
+            for other in Variant::ALL {
+                let valid = variant.board().validate_start(other).is_ok();
+                assert_eq!(valid, variant == other);
+            }
+        }
+        let standard = Variant::Standard.board();
+        let nackgammon = Variant::Nackgammon.board();
+        assert!(matches!(
+            standard.validate_start(Variant::Nackgammon),
+            Err(InvariantError::StartPosition {
+                variant: Variant::Nackgammon
+            })
+        ));
+        assert!(matches!(
+            nackgammon.validate_start(Variant::Standard),
+            Err(InvariantError::StartPosition {
+                variant: Variant::Standard
+            })
+        ));
+
+        // three checkers of Hypergammon laid out elsewhere
+        let mut board = Variant::Hypergammon.board();
+        board.set(Player::Player0, 21, -1)?;
+        board.set(Player::Player0, 20, 1)?;
+        assert!(board.validate_variant(Variant::Hypergammon).is_ok());
+        assert!(board.validate_start(Variant::Hypergammon).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_before_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let board = Variant::AceyDeucey.board();
+        let json = serde_json::to_string(&board)?;
+        assert_eq!(serde_json::from_str::<Board>(&json)?, board);
+
+        let game = crate::Game::new_variant(Variant::AceyDeucey);
+        let json = serde_json::to_string(&game)?;
+        let stored: crate::Game = serde_json::from_str(&json)?;
+        assert_eq!(stored.board, board);
+        assert_eq!(stored.variant(), Variant::AceyDeucey);
+        Ok(())
+    }
+
+    #[test]
+    fn hypergammon() -> Result<(), Error> {
+        let mut board = Variant::Hypergammon.board();
+        assert!(board.validate().is_ok());
+        assert_eq!(board.pip_counts(), (69, 69));
+        assert_eq!(board.get().board[21..], [1, 1, 1]);
+
+        // the game is won with the 3 checkers borne off
+        board = Board::from_raw(([0; 24], 0, 3), board.raw(Player::Player1)?);
+        assert!(board.validate().is_ok());
+        assert!(board.is_winner(Player::Player0));
+        assert_eq!(board.win_kind(Player::Player0), Some(WinKind::Backgammon));
+        assert!(!board.is_winner(Player::Player1));
+
+        // both players play with the same amount of checkers
+        let standard = Board::new().raw(Player::Player1)?;
+        let mixed = Board::from_raw(([0; 24], 0, 3), standard);
+        assert!(matches!(
+            mixed.validate(),
+            Err(InvariantError::CheckerCount {
+                player: Player::Player1,
+                count: 15,
+                expected: 3
+            })
+        ));
+        Ok(())
+    }
+}
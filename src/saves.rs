@@ -0,0 +1,302 @@
+use crate::rules::{Rules, Variant};
+use crate::{Error, Game};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File extension of saved games
+const EXTENSION: &str = "bgsave";
+
+/// Represents the metadata stored with a saved game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    /// The names of player 0 and player 1
+    pub players: (String, String),
+    /// The time the game was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+    /// The score of the match before the game
+    pub score: (u64, u64),
+    /// The rules the game is played with
+    pub rules: Rules,
+    /// The variant the game is played in
+    pub variant: Variant,
+}
+
+impl SaveMetadata {
+    /// Create the metadata for saving a game now, with the rules and the variant of the game
+    pub fn new(players: (String, String), score: (u64, u64), game: &Game) -> Self {
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        SaveMetadata {
+            players,
+            saved_at,
+            score,
+            rules: game.rules,
+            variant: game.variant(),
+        }
+    }
+}
+
+/// Represents the contents of a save slot
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    metadata: SaveMetadata,
+    game: Game,
+}
+
+/// Represents named save slots for games, stored as one file per slot in a directory.
+///
+/// Each file holds the metadata and the game as JSON, with the complete state of the game
+/// including its history, so games can be saved at any time, also in the middle of a turn or
+/// after the game ended. Loading validates the game as its deserialization does, see [`Game`].
+///
+/// ```
+/// use backgammon::{Game, SaveMetadata, SaveSlots};
+///
+/// let dir = std::env::temp_dir().join(format!("backgammon-doc-{}", std::process::id()));
+/// let slots = SaveSlots::new(&dir);
+/// let game = Game::new();
+/// let players = ("Alice".to_string(), "Bob".to_string());
+/// let metadata = SaveMetadata::new(players, (0, 0), &game);
+/// slots.save("first game", &game, &metadata).unwrap();
+///
+/// assert_eq!(slots.list().unwrap()[0].0, "first game");
+/// let (_game, loaded) = slots.load("first game").unwrap();
+/// assert_eq!(loaded, metadata);
+/// slots.delete("first game").unwrap();
+/// # std::fs::remove_dir(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaveSlots {
+    dir: PathBuf,
+}
+
+impl SaveSlots {
+    /// Create the save slots in a directory, which is created when saving the first game
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        SaveSlots { dir: dir.into() }
+    }
+
+    /// Get the path of a slot, checking its name.
+    ///
+    /// Names may consist of up to 64 letters, digits, spaces, dashes and underscores.
+    fn path(&self, name: &str) -> io::Result<PathBuf> {
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_');
+        if !valid || name.trim().is_empty() || name.len() > 64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                Error::SaveNameInvalid,
+            ));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, EXTENSION)))
+    }
+
+    /// Save a game into a slot, replacing the game saved there before. The rules and the
+    /// variant of the metadata have to be those of the game.
+    pub fn save(&self, name: &str, game: &Game, metadata: &SaveMetadata) -> io::Result<()> {
+        let path = self.path(name)?;
+        if metadata.rules != game.rules || metadata.variant != game.variant() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                Error::SaveInvalid,
+            ));
+        }
+        let file = SaveFile {
+            metadata: metadata.clone(),
+            game: game.clone(),
+        };
+        let contents = serde_json::to_string(&file).map_err(io::Error::from)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, contents)
+    }
+
+    /// Load the game and its metadata from a slot
+    pub fn load(&self, name: &str) -> io::Result<(Game, SaveMetadata)> {
+        let contents = fs::read_to_string(self.path(name)?)?;
+        parse(&contents).ok_or_else(|| invalid(Error::SaveInvalid))
+    }
+
+    /// Delete the game saved in a slot
+    pub fn delete(&self, name: &str) -> io::Result<()> {
+        fs::remove_file(self.path(name)?)
+    }
+
+    /// List the names and metadata of all saved games, sorted by name. Files in the directory
+    /// which are no saved games are skipped.
+    pub fn list(&self) -> io::Result<Vec<(String, SaveMetadata)>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut saves = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != EXTENSION)
+            {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if let Some((_, metadata)) = fs::read_to_string(&path).ok().and_then(|c| parse(&c)) {
+                saves.push((name.to_string(), metadata));
+            }
+        }
+        saves.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(saves)
+    }
+}
+
+/// Parse the contents of a saved game, checking that the metadata describes the game
+fn parse(contents: &str) -> Option<(Game, SaveMetadata)> {
+    let file: SaveFile = serde_json::from_str(contents).ok()?;
+    let (game, metadata) = (file.game, file.metadata);
+    if metadata.rules != game.rules || metadata.variant != game.variant() {
+        return None;
+    }
+    Some((game, metadata))
+}
+
+/// Wrap an error of a saved game into an I/O error
+fn invalid(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, Player, Roll};
+    use crate::{GamePhase, PlayerAction};
+
+    /// Get save slots in a fresh temporary directory
+    fn slots(test: &str) -> SaveSlots {
+        let dir =
+            std::env::temp_dir().join(format!("backgammon-saves-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        SaveSlots::new(dir)
+    }
+
+    fn metadata(score: (u64, u64), game: &Game) -> SaveMetadata {
+        SaveMetadata::new(("Alice".to_string(), "Bob".to_string()), score, game)
+    }
+
+    #[test]
+    fn save_load_list_delete() -> Result<(), Box<dyn std::error::Error>> {
+        let slots = slots("cycle");
+        assert!(slots.list()?.is_empty());
+
+        let mut game = Game::resume(Board::new(), Cube::default(), Player::Player1, false)?;
+        game.rules.jacoby = true;
+        game.submit(Player::Player1, PlayerAction::OfferDouble)?;
+        let saved = metadata((2, 3), &game);
+        slots.save("match_1", &game, &saved)?;
+        slots.save(
+            "another game",
+            &Game::new(),
+            &metadata((0, 0), &Game::new()),
+        )?;
+
+        let names: Vec<String> = slots.list()?.into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["another game", "match_1"]);
+
+        let (loaded, metadata) = slots.load("match_1")?;
+        assert_eq!(metadata, saved);
+        assert!(loaded.rules.jacoby);
+        assert_eq!(loaded.phase(), GamePhase::DoubleOffered);
+        assert_eq!(loaded.board, game.board);
+
+        slots.delete("match_1")?;
+        slots.delete("another game")?;
+        assert!(slots.list()?.is_empty());
+        assert!(slots.load("match_1").is_err());
+        fs::remove_dir(&slots.dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_variants() -> Result<(), Box<dyn std::error::Error>> {
+        let slots = slots("variants");
+        for variant in Variant::ALL {
+            let mut game = Game::new_variant(variant);
+            while game.phase() == GamePhase::OpeningRoll {
+                let _ = game.roll()?;
+            }
+            let saved = metadata((0, 0), &game);
+            slots.save("variant", &game, &saved)?;
+            let (loaded, metadata) = slots.load("variant")?;
+            assert_eq!(metadata.variant, variant);
+            assert_eq!(loaded.variant(), variant);
+            assert_eq!(loaded.board, game.board);
+            assert_eq!(loaded.dices, game.dices);
+            assert_eq!(loaded.who_plays, game.who_plays);
+        }
+        fs::remove_dir_all(&slots.dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_mid_turn_and_finished() -> Result<(), Box<dyn std::error::Error>> {
+        let slots = slots("mid-turn");
+        // player 1 has played the 5 of a 5-2
+        let (mut game, score) = Game::from_match_id("QYkqASAAIAAA", Board::new())?;
+        game.submit(Player::Player1, PlayerAction::Move { dice: 5, from: 12 })?;
+        let players = ("Carriage\rreturn".to_string(), "Line\nfeed\r\n".to_string());
+        let saved = SaveMetadata::new(players, score, &game);
+        slots.save("started", &game, &saved)?;
+        let (mut loaded, stored) = slots.load("started")?;
+        assert_eq!(stored, saved);
+        assert_eq!(loaded.board, game.board);
+        assert_eq!(loaded.dices, game.dices);
+        assert_eq!(loaded.phase(), GamePhase::Moving);
+        // the rest of the turn is played after loading, and the history is kept
+        assert!(loaded.dices.is_available(2));
+        assert_eq!(loaded.history().len(), 1);
+        assert!(loaded.undo().is_some());
+
+        game.submit(Player::Player0, PlayerAction::Resign)?;
+        slots.save("finished", &game, &metadata(score, &game))?;
+        let (loaded, _) = slots.load("finished")?;
+        assert_eq!(loaded.result(), game.result());
+        fs::remove_dir_all(&slots.dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn refuse_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        let slots = slots("invalid");
+        let game = Game::new();
+        let saved = metadata((0, 0), &game);
+        for name in [
+            "",
+            "   ",
+            "../escape",
+            "a/b",
+            "dots.bgsave",
+            "line\nfeed",
+            "carriage\r",
+        ] {
+            let err = slots.save(name, &game, &saved).err();
+            assert_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+        }
+        // the metadata has to describe the game
+        let other = metadata((0, 0), &Game::new_variant(Variant::Nackgammon));
+        let err = slots.save("other", &game, &other).err();
+        assert_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+
+        fs::create_dir_all(&slots.dir)?;
+        fs::write(slots.dir.join("broken.bgsave"), "player0: Alice\n")?;
+        let err = slots.load("broken").err();
+        assert_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+        assert!(slots.list()?.is_empty());
+        fs::remove_dir_all(&slots.dir)?;
+        Ok(())
+    }
+}
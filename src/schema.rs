@@ -0,0 +1,280 @@
+use crate::error::InvariantError;
+use serde::{Deserialize, Serialize};
+
+/// The version of the JSON schema of messages exchanged with clients.
+///
+/// The version is increased whenever the serialized shape of a message changes in a way that
+/// existing clients can not read.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Represents a message tagged with the schema version it was written with.
+///
+/// The payload is flattened into the message, so a versioned action reads
+/// `{"schema_version":1,"action":"roll"}`. Deserializing a message of any other schema version
+/// fails. The schema covers the [`PlayerAction`](crate::PlayerAction) sent by clients and the
+/// [`BoardDisplay`](crate::rules::BoardDisplay), [`HistoryEvent`](crate::HistoryEvent) and
+/// [`ReplayStep`](crate::ReplayStep) sent to them.
+///
+/// ```
+/// use backgammon::{PlayerAction, Versioned};
+///
+/// let message = Versioned::new(PlayerAction::Roll);
+/// assert_eq!(message.payload, PlayerAction::Roll);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "UncheckedVersioned<T>",
+    bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>")
+)]
+pub struct Versioned<T> {
+    /// The schema version of the message
+    pub schema_version: u32,
+    /// The content of the message
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tag a payload with the current schema version
+    pub fn new(payload: T) -> Self {
+        Versioned {
+            schema_version: SCHEMA_VERSION,
+            payload,
+        }
+    }
+}
+
+/// Represents a deserialized message before its schema version has been validated
+#[derive(Deserialize)]
+struct UncheckedVersioned<T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    payload: T,
+}
+
+impl<T> TryFrom<UncheckedVersioned<T>> for Versioned<T> {
+    type Error = InvariantError;
+
+    fn try_from(unchecked: UncheckedVersioned<T>) -> Result<Self, Self::Error> {
+        if unchecked.schema_version != SCHEMA_VERSION {
+            return Err(InvariantError::SchemaVersion {
+                version: unchecked.schema_version,
+            });
+        }
+        Ok(Versioned {
+            schema_version: unchecked.schema_version,
+            payload: unchecked.payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, BoardDisplay, Cube, Hit, Location, MoveChecker, Player, Turn};
+    use crate::{Game, HistoryEvent, PlayerAction};
+
+    /// The lover's leap 24/18/13 of player 0 from the starting position
+    fn lovers_leap() -> Result<Turn, crate::Error> {
+        let player = Player::Player0;
+        let moves = vec![
+            MoveChecker::new(player, Location::Point(24), Location::Point(18))?,
+            MoveChecker::new(player, Location::Point(18), Location::Point(13))?,
+        ];
+        Turn::new(&Board::new(), player, (6, 5), moves)
+    }
+
+    /// The serialized form of the starting position in schema version 1
+    const START: &str = concat!(
+        r#""board":[-2,0,0,0,0,5,0,3,0,0,0,-5,5,0,0,0,-3,0,-5,0,0,0,0,2],"#,
+        r#""bar":[0,0],"off":[0,0]"#
+    );
+
+    /// The serialized form of the moves of [`lovers_leap`] in schema version 1
+    const LOVERS_LEAP: &str = concat!(
+        r#""player":"Player0","dices":[6,5],"#,
+        r#""moves":[{"dice":6,"checker":{"player":"Player0","from":{"Point":24},"#,
+        r#""to":{"Point":18}},"hit":null},{"dice":5,"checker":{"player":"Player0","#,
+        r#""from":{"Point":18},"to":{"Point":13}},"hit":null}],"#,
+        r#""position":6407283755644164127,"forced":false"#
+    );
+
+    /// The serialized form of every action in schema version 1. Changing any of these breaks
+    /// clients and requires a new schema version.
+    const ACTIONS: [(PlayerAction, &str); 11] = [
+        (
+            PlayerAction::Roll,
+            r#"{"schema_version":1,"action":"roll"}"#,
+        ),
+        (
+            PlayerAction::Move { dice: 3, from: 12 },
+            r#"{"schema_version":1,"action":"move","dice":3,"from":12}"#,
+        ),
+        (
+            PlayerAction::MoveFromBar { dice: 5 },
+            r#"{"schema_version":1,"action":"move_from_bar","dice":5}"#,
+        ),
+        (
+            PlayerAction::OfferDouble,
+            r#"{"schema_version":1,"action":"offer_double"}"#,
+        ),
+        (
+            PlayerAction::Take,
+            r#"{"schema_version":1,"action":"take"}"#,
+        ),
+        (
+            PlayerAction::Drop,
+            r#"{"schema_version":1,"action":"drop"}"#,
+        ),
+        (
+            PlayerAction::Resign,
+            r#"{"schema_version":1,"action":"resign"}"#,
+        ),
+        (
+            PlayerAction::ChooseDoubles { dice: 4 },
+            r#"{"schema_version":1,"action":"choose_doubles","dice":4}"#,
+        ),
+        (
+            PlayerAction::RequestTakeback,
+            r#"{"schema_version":1,"action":"request_takeback"}"#,
+        ),
+        (
+            PlayerAction::AcceptTakeback,
+            r#"{"schema_version":1,"action":"accept_takeback"}"#,
+        ),
+        (
+            PlayerAction::DeclineTakeback,
+            r#"{"schema_version":1,"action":"decline_takeback"}"#,
+        ),
+    ];
+
+    #[test]
+    fn test_action_schema() -> Result<(), serde_json::Error> {
+        for (action, json) in ACTIONS {
+            assert_eq!(
+                serde_json::to_string(&Versioned::new(action.clone()))?,
+                json
+            );
+            let message: Versioned<PlayerAction> = serde_json::from_str(json)?;
+            assert_eq!(message.payload, action);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_play_turn_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let action = PlayerAction::PlayTurn(lovers_leap()?);
+        let json = format!(r#"{{"schema_version":1,"action":"play_turn",{LOVERS_LEAP}}}"#);
+        assert_eq!(
+            serde_json::to_string(&Versioned::new(action.clone()))?,
+            json
+        );
+        let message: Versioned<PlayerAction> = serde_json::from_str(&json)?;
+        assert_eq!(message.payload, action);
+        Ok(())
+    }
+
+    #[test]
+    fn test_board_display_schema() -> Result<(), serde_json::Error> {
+        let json = format!(r#"{{"schema_version":1,{START}}}"#);
+        assert_eq!(
+            serde_json::to_string(&Versioned::new(Board::new().get()))?,
+            json
+        );
+        let message: Versioned<BoardDisplay> = serde_json::from_str(&json)?;
+        assert_eq!(message.payload, Board::new().get());
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_event_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let player = Player::Player0;
+        let hit = Hit {
+            player: Player::Player1,
+            point: 21,
+        };
+        let checker = MoveChecker::new(player, Location::Point(24), Location::Point(21))?;
+        let events = [
+            (
+                HistoryEvent::Roll {
+                    player: Player::Nobody,
+                    dices: (3, 1),
+                },
+                r#""event":"roll","player":"Nobody","dices":[3,1]"#.to_string(),
+            ),
+            (
+                HistoryEvent::Move {
+                    checker,
+                    hit: Some(hit),
+                },
+                concat!(
+                    r#""event":"move","checker":{"player":"Player0","from":{"Point":24},"#,
+                    r#""to":{"Point":21}},"hit":{"player":"Player1","point":21}"#
+                )
+                .to_string(),
+            ),
+            (
+                HistoryEvent::Turn(lovers_leap()?),
+                format!(r#""event":"turn",{LOVERS_LEAP}"#),
+            ),
+            (
+                HistoryEvent::Pass { player },
+                r#""event":"pass","player":"Player0""#.to_string(),
+            ),
+            (
+                HistoryEvent::OfferDouble { player },
+                r#""event":"offer_double","player":"Player0""#.to_string(),
+            ),
+            (
+                HistoryEvent::Take { player },
+                r#""event":"take","player":"Player0""#.to_string(),
+            ),
+            (
+                HistoryEvent::Drop { player },
+                r#""event":"drop","player":"Player0""#.to_string(),
+            ),
+            (
+                HistoryEvent::Resign { player },
+                r#""event":"resign","player":"Player0""#.to_string(),
+            ),
+            (
+                HistoryEvent::ChooseDoubles { player, dice: 4 },
+                r#""event":"choose_doubles","player":"Player0","dice":4"#.to_string(),
+            ),
+            (HistoryEvent::Void, r#""event":"void""#.to_string()),
+        ];
+        for (event, fields) in events {
+            let json = format!(r#"{{"schema_version":1,{fields}}}"#);
+            assert_eq!(serde_json::to_string(&Versioned::new(&event))?, json);
+            let message: Versioned<HistoryEvent> = serde_json::from_str(&json)?;
+            assert_eq!(message.payload, event);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_step_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let player = Player::Player0;
+        let mut game = Game::resume(Board::new(), Cube::default(), player, false)?;
+        game.submit(player, PlayerAction::OfferDouble)?;
+        let step = game.replay_iter().next().ok_or(crate::Error::MoveInvalid)?;
+        let json = format!(
+            concat!(
+                r#"{{"schema_version":1,"event":{{"event":"offer_double","player":"Player0"}},"#,
+                r#""before":{{{start}}},"after":{{{start}}},"#,
+                r#""who_plays":"Player0","dices":[0,0],"cube_value":1}}"#
+            ),
+            start = START
+        );
+        assert_eq!(serde_json::to_string(&Versioned::new(step))?, json);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = r#"{"schema_version":2,"action":"roll"}"#;
+        assert!(serde_json::from_str::<Versioned<PlayerAction>>(json).is_err());
+        let json = r#"{"action":"roll"}"#;
+        assert!(serde_json::from_str::<Versioned<PlayerAction>>(json).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::record::GameRecord;
+use crate::rules::{Board, Player};
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Plays games against itself, choosing uniformly among the legal plays.
+///
+/// Self-play is a lazy, endless iterator over finished games with their full history, so games
+/// can be processed as they complete and consumers decide when to stop. All games are derived
+/// from a single seed, hence the same seed always yields the same games.
+///
+/// ```
+/// use backgammon::SelfPlay;
+///
+/// for game in SelfPlay::new([0; 32]).take(2) {
+///     assert!(!game.turns.is_empty());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SelfPlay {
+    rng: StdRng,
+}
+
+impl SelfPlay {
+    /// Create a new self-play iterator from a seed
+    pub fn new(seed: [u8; 32]) -> Self {
+        SelfPlay {
+            rng: StdRng::from_seed(seed),
+        }
+    }
+
+    /// Roll one dice
+    fn roll(&mut self) -> u8 {
+        Uniform::new_inclusive(1, 6).sample(&mut self.rng)
+    }
+
+    /// Play one game to its end
+    fn play(&mut self) -> GameRecord {
+        // opening roll: each player rolls one dice, the higher one plays both
+        let mut dices = (self.roll(), self.roll());
+        while dices.0 == dices.1 {
+            dices = (self.roll(), self.roll());
+        }
+        let mut player = if dices.0 > dices.1 {
+            Player::Player0
+        } else {
+            Player::Player1
+        };
+
+        let mut board = Board::new();
+        let mut record = GameRecord::default();
+        loop {
+            let plays = board.generate_plays(player, dices).expect("for player");
+            let (turn, next) = plays
+                .choose(&mut self.rng)
+                .cloned()
+                .expect("at least the empty play");
+            record.turns.push(turn);
+            board = next;
+
+            if board.is_finished() {
+                record.winner = player;
+                record.points = board.win_kind(player).map_or(1, |kind| kind.points(1));
+                return record;
+            }
+            player = player.other();
+            dices = (self.roll(), self.roll());
+        }
+    }
+}
+
+impl Iterator for SelfPlay {
+    type Item = GameRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.play())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn finished_games() -> Result<(), Error> {
+        for game in SelfPlay::new([1; 32]).take(5) {
+            let boards = game.boards()?;
+            assert_eq!(boards.len(), game.turns.len() + 1);
+            assert!(boards[boards.len() - 1].is_winner(game.winner));
+            assert_eq!(game.turns[game.turns.len() - 1].player(), game.winner);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reproducible() {
+        let a: Vec<GameRecord> = SelfPlay::new([2; 32]).take(2).collect();
+        let b: Vec<GameRecord> = SelfPlay::new([2; 32]).take(2).collect();
+        assert_eq!(a, b);
+        assert_ne!(a[0], a[1]);
+    }
+}
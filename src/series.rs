@@ -0,0 +1,166 @@
+use crate::rules::{Player, Rules};
+use crate::{Error, Match};
+
+/// Represents a series of matches won by the player first winning the majority of them, e.g.
+/// the best of 3 matches to 7 points
+///
+/// ```
+/// use backgammon::{Match, PlayerAction, Series};
+/// use backgammon::rules::{MatchRules, Player};
+///
+/// let rules = Match::new().with_points(1).rules;
+/// let mut series = Series::best_of(3, rules).unwrap();
+/// while series.winner().is_none() {
+///     let game = series.current_match().unwrap().current_game().unwrap();
+///     while game.who_plays == Player::Nobody {
+///         game.submit(Player::Player0, PlayerAction::Roll).unwrap();
+///     }
+///     game.submit(Player::Player1, PlayerAction::Resign).unwrap();
+/// }
+/// assert_eq!(series.winner(), Some(Player::Player0));
+/// assert_eq!(series.wins(), (2, 0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Series {
+    /// The rules of every match of the series
+    pub rules: Rules,
+    /// The maximum amount of matches played
+    pub best_of: u32,
+    /// The matches played in the series
+    pub matches: Vec<Match>,
+}
+
+/// Represents the aggregate statistics of a series, see [`Series::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeriesStats {
+    /// The matches won by each player
+    pub matches: (u32, u32),
+    /// The games won by each player
+    pub games: (u32, u32),
+    /// The points won by each player in all games, without points given as handicap
+    pub points: (u64, u64),
+}
+
+impl Series {
+    /// Start a series of at most the given amount of matches, which has to be odd
+    pub fn best_of(best_of: u32, rules: Rules) -> Result<Self, Error> {
+        if best_of.is_multiple_of(2) || rules.points == 0 {
+            return Err(Error::MatchLengthInvalid);
+        }
+        Ok(Series {
+            rules,
+            best_of,
+            matches: Vec::new(),
+        })
+    }
+
+    /// Get the match currently played, starting a new match once the previous one has a winner.
+    ///
+    /// Returns an error once the series has a winner.
+    pub fn current_match(&mut self) -> Result<&mut Match, Error> {
+        if self.winner().is_some() {
+            return Err(Error::GameEnded);
+        }
+        if self.matches.last().is_none_or(|m| m.winner().is_some()) {
+            let mut next = Match::new();
+            next.rules = self.rules;
+            self.matches.push(next);
+        }
+        self.matches.last_mut().ok_or(Error::GameEnded)
+    }
+
+    /// Get the matches won by each player
+    pub fn wins(&self) -> (u32, u32) {
+        self.stats().matches
+    }
+
+    /// Get the winner of the series, once a player has won the majority of the matches
+    pub fn winner(&self) -> Option<Player> {
+        let needed = self.best_of / 2 + 1;
+        let wins = self.wins();
+        if wins.0 >= needed {
+            Some(Player::Player0)
+        } else if wins.1 >= needed {
+            Some(Player::Player1)
+        } else {
+            None
+        }
+    }
+
+    /// Get the matches, games and points won by each player over the whole series
+    pub fn stats(&self) -> SeriesStats {
+        let mut stats = SeriesStats::default();
+        for m in &self.matches {
+            match m.winner() {
+                Some(Player::Player0) => stats.matches.0 += 1,
+                Some(Player::Player1) => stats.matches.1 += 1,
+                _ => {}
+            }
+            for game in &m.games {
+                match game.result() {
+                    Some((Player::Player0, points)) => {
+                        stats.games.0 += 1;
+                        stats.points.0 += points;
+                    }
+                    Some((Player::Player1, points)) => {
+                        stats.games.1 += 1;
+                        stats.points.1 += points;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::MatchRules;
+    use crate::PlayerAction;
+
+    /// Let the given player win the current game of the series by resignation of the opponent
+    fn win_game(series: &mut Series, winner: Player) -> Result<(), Error> {
+        let game = series.current_match()?.current_game()?;
+        while game.who_plays == Player::Nobody {
+            game.submit(winner, PlayerAction::Roll)?;
+        }
+        game.submit(winner.other(), PlayerAction::Resign)
+    }
+
+    #[test]
+    fn best_of_three() -> Result<(), Error> {
+        let rules = Match::new().with_points(2).rules;
+        let mut series = Series::best_of(3, rules)?;
+        assert_eq!(series.winner(), None);
+
+        // player 1 wins the first match 2-1, player 0 the next two matches
+        for winner in [Player::Player1, Player::Player0, Player::Player1] {
+            win_game(&mut series, winner)?;
+        }
+        assert_eq!(series.wins(), (0, 1));
+        for _ in 0..4 {
+            win_game(&mut series, Player::Player0)?;
+        }
+        assert_eq!(series.winner(), Some(Player::Player0));
+        assert!(matches!(series.current_match(), Err(Error::GameEnded)));
+
+        let stats = series.stats();
+        assert_eq!(stats.matches, (2, 1));
+        assert_eq!(stats.games, (5, 2));
+        assert_eq!(stats.points, (5, 2));
+        assert_eq!(series.matches.len(), 3);
+        assert!(series.matches.iter().all(|m| m.rules.points == 2));
+        Ok(())
+    }
+
+    #[test]
+    fn even_series_invalid() {
+        assert!(matches!(
+            Series::best_of(2, Rules::default()),
+            Err(Error::MatchLengthInvalid)
+        ));
+        assert!(Series::best_of(1, Rules::default()).is_ok());
+    }
+}
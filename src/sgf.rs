@@ -0,0 +1,357 @@
+use crate::record::{Double, GameRecord};
+use crate::rules::{Board, Location, MoveChecker, Player, Turn};
+use crate::Error;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A node of a game tree, as its properties with their values
+pub(crate) type Node = Vec<(String, Vec<String>)>;
+
+/// Get the letter of a location in a move, which is the same for both players: `a` to `x` for
+/// the 24-point to the 1-point of player 0, `y` for the bar and `z` for the off
+fn letter(location: Location) -> char {
+    match location {
+        Location::Point(point) => char::from(b'y' - point),
+        Location::Bar => 'y',
+        Location::Off => 'z',
+    }
+}
+
+/// Get the location of a letter in a move, see [`letter`]
+pub(crate) fn location(letter: u8) -> Result<Location, Error> {
+    match letter {
+        b'a'..=b'x' => Ok(Location::Point(b'y' - letter)),
+        b'y' => Ok(Location::Bar),
+        b'z' => Ok(Location::Off),
+        _ => Err(Error::SgfInvalid),
+    }
+}
+
+/// Get the colour of a player, player 0 being white and player 1 black as in GNU Backgammon
+fn colour(player: Player) -> Result<char, Error> {
+    match player {
+        Player::Player0 => Ok('W'),
+        Player::Player1 => Ok('B'),
+        Player::Nobody => Err(Error::PlayerInvalid),
+    }
+}
+
+/// Parse the nodes of a single game tree without variations
+fn parse_nodes(sgf: &str) -> Result<Vec<Node>, Error> {
+    let mut chars = sgf.trim().chars().peekable();
+    let nodes = parse_tree(&mut chars)?;
+    if chars.any(|c| !c.is_whitespace()) {
+        return Err(Error::SgfInvalid);
+    }
+    Ok(nodes)
+}
+
+/// Parse the nodes of each game tree of a collection, e.g. the games of a match
+pub(crate) fn parse_trees(sgf: &str) -> Result<Vec<Vec<Node>>, Error> {
+    let mut chars = sgf.trim().chars().peekable();
+    let mut trees = vec![parse_tree(&mut chars)?];
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(trees);
+        }
+        trees.push(parse_tree(&mut chars)?);
+    }
+}
+
+/// Parse the nodes of the next game tree, which has no variations
+fn parse_tree(chars: &mut Peekable<Chars<'_>>) -> Result<Vec<Node>, Error> {
+    if chars.next() != Some('(') {
+        return Err(Error::SgfInvalid);
+    }
+    let mut nodes: Vec<Node> = Vec::new();
+    loop {
+        match chars.next() {
+            Some(';') => nodes.push(Vec::new()),
+            Some(')') => break,
+            Some(c) if c.is_whitespace() => continue,
+            Some(c) if c.is_ascii_uppercase() => {
+                let node = nodes.last_mut().ok_or(Error::SgfInvalid)?;
+                let mut id = c.to_string();
+                while let Some(c) = chars.next_if(char::is_ascii_uppercase) {
+                    id.push(c);
+                }
+                let mut values = Vec::new();
+                loop {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    if chars.next_if_eq(&'[').is_none() {
+                        break;
+                    }
+                    let mut value = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some('\\') => value.push(chars.next().ok_or(Error::SgfInvalid)?),
+                            Some(c) => value.push(c),
+                            None => return Err(Error::SgfInvalid),
+                        }
+                    }
+                    values.push(value);
+                }
+                if values.is_empty() {
+                    return Err(Error::SgfInvalid);
+                }
+                node.push((id, values));
+            }
+            _ => return Err(Error::SgfInvalid),
+        }
+    }
+    Ok(nodes)
+}
+
+impl GameRecord {
+    /// Write the game as SGF file in the backgammon variant used by GNU Backgammon.
+    ///
+    /// Player 0 is white and player 1 black. Each turn is a node with the dices followed by the
+    /// moves as pairs of letters, e.g. `;W[31qtst]` for 8/5 6/5 of player 0, and doubles are
+    /// written as `double`, `take` and `drop`. The result gives the winner with the points won,
+    /// marked with `R` for a resignation.
+    ///
+    /// ```
+    /// use backgammon::{GameRecord, SelfPlay};
+    ///
+    /// let record = SelfPlay::new([0; 32]).next().unwrap();
+    /// let sgf = record.to_sgf().unwrap();
+    /// assert!(sgf.starts_with("(;FF[4]GM[6]"));
+    /// assert_eq!(GameRecord::from_sgf(&sgf).unwrap(), record);
+    /// ```
+    pub fn to_sgf(&self) -> Result<String, Error> {
+        let boards = self.boards()?;
+        let mut sgf = String::from("(;FF[4]GM[6]CA[UTF-8]AP[backgammon]");
+        if self.winner != Player::Nobody {
+            let dropped = self
+                .doubles
+                .last()
+                .is_some_and(|double| !double.taken && double.player == self.winner);
+            let finished = boards.last().is_some_and(Board::is_finished);
+            let resigned = if dropped || finished { "" } else { "R" };
+            sgf.push_str(&format!(
+                "RE[{}+{}{}]",
+                colour(self.winner)?,
+                self.points,
+                resigned
+            ));
+        }
+        for played in 0..=self.turns.len() {
+            for double in self.doubles.iter().filter(|double| double.turn == played) {
+                sgf.push_str(&format!("\n;{}[double]", colour(double.player)?));
+                let response = match double.taken {
+                    true => "take",
+                    false if double.player == self.winner => "drop",
+                    false => continue,
+                };
+                let opponent = colour(double.player.other())?;
+                sgf.push_str(&format!("\n;{}[{}]", opponent, response));
+            }
+            if let Some(turn) = self.turns.get(played) {
+                let dices = turn.dices();
+                sgf.push_str(&format!(
+                    "\n;{}[{}{}",
+                    colour(turn.player())?,
+                    dices.0,
+                    dices.1
+                ));
+                for m in turn.moves() {
+                    sgf.push(letter(m.checker().from()));
+                    sgf.push(letter(m.checker().to()));
+                }
+                sgf.push(']');
+            }
+        }
+        sgf.push_str(")\n");
+        Ok(sgf)
+    }
+
+    /// Parse a game from an SGF file written by [`GameRecord::to_sgf`] or GNU Backgammon.
+    ///
+    /// The file has to hold a single game tree starting from the starting position. Turns are
+    /// checked to be legal plays, and doubles to be offered by the player on turn owning the cube.
+    /// Properties other than the moves and the result, e.g. comments, are ignored; read the
+    /// analysis of GNU Backgammon with [`AnnotatedMatch::from_sgf`](crate::AnnotatedMatch::from_sgf).
+    pub fn from_sgf(sgf: &str) -> Result<GameRecord, Error> {
+        GameRecord::from_nodes(&parse_nodes(sgf)?)
+    }
+
+    /// Parse a game from the nodes of its game tree, see [`GameRecord::from_sgf`]
+    pub(crate) fn from_nodes(nodes: &[Node]) -> Result<GameRecord, Error> {
+        let (root, nodes) = nodes.split_first().ok_or(Error::SgfInvalid)?;
+        let property = |id: &str| {
+            root.iter()
+                .find(|(property, _)| property == id)
+                .map(|(_, values)| values[0].as_str())
+        };
+        if property("GM") != Some("6") {
+            return Err(Error::SgfInvalid);
+        }
+
+        let mut record = GameRecord::default();
+        if let Some(result) = property("RE") {
+            let result = result.strip_suffix('R').unwrap_or(result);
+            let (winner, points) = match result.split_once('+') {
+                Some(("W", points)) => (Player::Player0, points),
+                Some(("B", points)) => (Player::Player1, points),
+                _ if ["", "?", "0", "Void"].contains(&result) => (Player::Nobody, "0"),
+                _ => return Err(Error::SgfInvalid),
+            };
+            record.winner = winner;
+            record.points = points.parse().map_err(|_| Error::SgfInvalid)?;
+            if (winner == Player::Nobody) != (record.points == 0) {
+                return Err(Error::SgfInvalid);
+            }
+        }
+
+        let mut board = Board::new();
+        // the player to act next, nobody before the first action
+        let mut on_turn = Player::Nobody;
+        let mut owner = Player::Nobody;
+        let mut offered = false;
+        let mut dropped = false;
+        for node in nodes {
+            let mut actions = node.iter().filter(|(id, _)| id == "W" || id == "B");
+            let (player, value) = match (actions.next(), actions.next()) {
+                (Some((id, values)), None) if values.len() == 1 => match id.as_str() {
+                    "W" => (Player::Player0, values[0].as_str()),
+                    _ => (Player::Player1, values[0].as_str()),
+                },
+                (None, None) => continue,
+                _ => return Err(Error::SgfInvalid),
+            };
+            let out_of_turn = on_turn != Player::Nobody && on_turn != player;
+            if dropped {
+                return Err(Error::SgfInvalid);
+            }
+            match value {
+                "double" => {
+                    if offered || out_of_turn || owner == player.other() {
+                        return Err(Error::SgfInvalid);
+                    }
+                    record.doubles.push(Double {
+                        turn: record.turns.len(),
+                        player,
+                        taken: false,
+                    });
+                    offered = true;
+                    on_turn = player;
+                }
+                "take" | "drop" => {
+                    let double = record.doubles.last_mut().ok_or(Error::SgfInvalid)?;
+                    if !offered || double.player != player.other() {
+                        return Err(Error::SgfInvalid);
+                    }
+                    offered = false;
+                    if value == "take" {
+                        double.taken = true;
+                        owner = player;
+                    } else {
+                        dropped = true;
+                    }
+                }
+                play => {
+                    let bytes = play.as_bytes();
+                    if offered || out_of_turn || bytes.len() < 2 || bytes.len() % 2 != 0 {
+                        return Err(Error::SgfInvalid);
+                    }
+                    let dice = |byte: u8| match byte {
+                        b'1'..=b'6' => Ok(byte - b'0'),
+                        _ => Err(Error::SgfInvalid),
+                    };
+                    let dices = (dice(bytes[0])?, dice(bytes[1])?);
+                    let moves = bytes[2..]
+                        .chunks(2)
+                        .map(|pair| {
+                            MoveChecker::new(player, location(pair[0])?, location(pair[1])?)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let turn = Turn::new(&board, player, dices, moves)?;
+                    turn.apply(&mut board)?;
+                    record.turns.push(turn);
+                    on_turn = player.other();
+                }
+            }
+        }
+        let offerer = record.doubles.last().map(|double| double.player);
+        if dropped && offerer != Some(record.winner) {
+            return Err(Error::SgfInvalid);
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SelfPlay;
+
+    #[test]
+    fn round_trip_self_play() -> Result<(), Error> {
+        for record in SelfPlay::new([3; 32]).take(5) {
+            let parsed = GameRecord::from_sgf(&record.to_sgf()?)?;
+            assert_eq!(parsed, record);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn doubles() -> Result<(), Error> {
+        let sgf = "(;FF[4]GM[6]C[a comment]RE[W+2]
+                   ;W[31qtst]
+                   ;B[double]
+                   ;W[take]
+                   ;B[64xrmi]
+                   ;W[double]C[too good?]
+                   ;B[drop])";
+        let record = GameRecord::from_sgf(sgf)?;
+        assert_eq!(record.turns.len(), 2);
+        assert_eq!(record.turns[0].to_string(), "8/5 6/5");
+        assert_eq!(record.turns[1].to_string(), "24/18 13/9");
+        let doubles = vec![
+            Double {
+                turn: 1,
+                player: Player::Player1,
+                taken: true,
+            },
+            Double {
+                turn: 2,
+                player: Player::Player0,
+                taken: false,
+            },
+        ];
+        assert_eq!(record.doubles, doubles);
+        assert_eq!((record.winner, record.points), (Player::Player0, 2));
+        assert_eq!(GameRecord::from_sgf(&record.to_sgf()?)?, record);
+        Ok(())
+    }
+
+    #[test]
+    fn resigned() -> Result<(), Error> {
+        let record = GameRecord::from_sgf("(;GM[6]RE[B+1R];W[31qtst])")?;
+        assert_eq!(record.winner, Player::Player1);
+        assert!(record.to_sgf()?.contains("RE[B+1R]"));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for sgf in [
+            "",
+            "(;GM[1];W[31qtst])",
+            "(;GM[6];W[31qtst]",
+            "(;GM[6];W[31qtst])(;GM[6])",
+            "(;GM[6];W[31qt])",
+            "(;GM[6];W[71qtst])",
+            "(;GM[6];W[31qtst];W[64xrmi])",
+            "(;GM[6];W[31qtst];W[double])",
+            "(;GM[6];W[double];W[take])",
+            "(;GM[6];W[double];B[drop];W[31qtst])",
+            "(;GM[6]RE[B+1];W[double];B[drop])",
+            "(;GM[6]RE[W+0])",
+        ] {
+            assert!(GameRecord::from_sgf(sgf).is_err(), "{}", sgf);
+        }
+    }
+}
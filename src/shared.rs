@@ -0,0 +1,112 @@
+use crate::Game;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Represents a game shared between threads
+///
+/// All types of this crate are `Send` and `Sync`, hence a [`Game`] can be moved to or read from
+/// any thread. To share one game between several request handlers, `SharedGame` wraps it in an
+/// `Arc<RwLock<_>>`: clones refer to the same game, many readers can access it in parallel, and
+/// writers get exclusive access. If a thread panics while holding the lock, the game is still
+/// accessible to the others, as all game actions either complete or leave the game untouched.
+///
+/// ```
+/// use backgammon::{Game, SharedGame};
+/// use std::thread;
+///
+/// let shared = SharedGame::new(Game::new());
+/// let handler = shared.clone();
+/// thread::spawn(move || handler.write(|game| game.rules.beaver = true))
+///     .join()
+///     .unwrap();
+///
+/// assert!(shared.read(|game| game.rules.beaver));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedGame {
+    inner: Arc<RwLock<Game>>,
+}
+
+impl SharedGame {
+    /// Share a game
+    pub fn new(game: Game) -> Self {
+        SharedGame {
+            inner: Arc::new(RwLock::new(game)),
+        }
+    }
+
+    /// Read the game, allowing other readers at the same time
+    pub fn read<T>(&self, f: impl FnOnce(&Game) -> T) -> T {
+        f(&self.inner.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Modify the game with exclusive access
+    pub fn write<T>(&self, f: impl FnOnce(&mut Game) -> T) -> T {
+        f(&mut self.inner.write().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Get a copy of the current state of the game
+    pub fn snapshot(&self) -> Game {
+        self.read(Game::clone)
+    }
+}
+
+impl From<Game> for SharedGame {
+    fn from(game: Game) -> Self {
+        SharedGame::new(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, Dices, Player, Players, Rules};
+    use crate::{GameHub, GameRecord, GameWithPlayerDices, Match, SelfPlay};
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<Game>();
+        assert_send_sync::<GameWithPlayerDices>();
+        assert_send_sync::<Match>();
+        assert_send_sync::<Board>();
+        assert_send_sync::<Cube>();
+        assert_send_sync::<Dices>();
+        assert_send_sync::<Player>();
+        assert_send_sync::<Players>();
+        assert_send_sync::<Rules>();
+        assert_send_sync::<GameRecord>();
+        assert_send_sync::<SelfPlay>();
+        assert_send_sync::<SharedGame>();
+        assert_send_sync::<GameHub>();
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let shared = SharedGame::new(Game::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || shared.write(|game| game.rules.points += 1))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(shared.snapshot().rules.points, Rules::default().points + 4);
+        assert_eq!(shared.read(|game| game.who_plays), Player::Nobody);
+    }
+
+    #[test]
+    fn poisoned() {
+        let shared = SharedGame::from(Game::new());
+        let handler = shared.clone();
+        assert!(
+            thread::spawn(move || handler.write(|_| panic!("handler failed")))
+                .join()
+                .is_err()
+        );
+        assert_eq!(shared.read(|game| game.rules.points), 7);
+    }
+}
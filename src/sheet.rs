@@ -0,0 +1,257 @@
+use crate::rules::Player;
+use crate::{Match, TimingStats};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents the result of one game on a [`ScoreSheet`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSheetRow {
+    /// The number of the game in the match, starting at 1
+    pub game: usize,
+    /// The winner of the game
+    pub winner: Player,
+    /// The points won
+    pub points: u64,
+    /// The value of the cube at the end of the game
+    pub cube: u64,
+    /// Whether the game was the Crawford game
+    pub crawford: bool,
+    /// The score of the match after the game
+    pub score: (u64, u64),
+    /// The time player 0 and player 1 took for their decisions in the game, in milliseconds,
+    /// see [`History::timing`](crate::History::timing)
+    pub thinking: (u64, u64),
+}
+
+/// Represents the score sheet of a match, listing the finished games with the running score.
+///
+/// The sheet is structured data, and its [`Display`](fmt::Display) implementation renders it as
+/// plain text for printing:
+///
+/// ```
+/// use backgammon::{Match, PlayerAction};
+/// use backgammon::rules::Player;
+///
+/// let mut m = Match::preset(3).unwrap();
+/// let game = m.current_game().unwrap();
+/// while game.who_plays == Player::Nobody {
+///     game.submit(Player::Player0, PlayerAction::Roll).unwrap();
+/// }
+/// let loser = game.who_plays;
+/// game.submit(loser, PlayerAction::Resign).unwrap();
+///
+/// let sheet = m.score_sheet();
+/// assert_eq!(sheet.rows[0].winner, loser.other());
+/// assert_eq!(sheet.rows[0].points, 1);
+/// assert!(sheet.to_string().starts_with("3 point match"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSheet {
+    /// The length of the match
+    pub points: u32,
+    /// The score before the first game, if the match was resumed or points were given as handicap
+    pub start: (u64, u64),
+    /// The finished games, in the order they were played
+    pub rows: Vec<ScoreSheetRow>,
+    /// The winner of the match, once it is over
+    pub winner: Option<Player>,
+    /// The time player 0 and player 1 took for their decisions in the match, see
+    /// [`Match::timing`]
+    pub timing: (TimingStats, TimingStats),
+}
+
+impl Match {
+    /// Get the score sheet of the match
+    pub fn score_sheet(&self) -> ScoreSheet {
+        let results: Vec<_> = self
+            .games
+            .iter()
+            .enumerate()
+            .filter_map(|(i, game)| game.result().map(|result| (i, game, result)))
+            .collect();
+        let won = |player: Player| {
+            results
+                .iter()
+                .filter(|(_, _, (winner, _))| *winner == player)
+                .map(|(_, _, (_, points))| points)
+                .sum::<u64>()
+        };
+        let score = self.score();
+        let start = (
+            score.0 - won(Player::Player0),
+            score.1 - won(Player::Player1),
+        );
+
+        let mut running = start;
+        let rows = results
+            .into_iter()
+            .map(|(i, game, (winner, points))| {
+                match winner {
+                    Player::Player0 => running.0 += points,
+                    _ => running.1 += points,
+                }
+                let (player0, player1) = game.history().timing();
+                ScoreSheetRow {
+                    game: i + 1,
+                    winner,
+                    points,
+                    cube: game.cube.value(),
+                    crawford: game.is_crawford(),
+                    score: running,
+                    thinking: (player0.total, player1.total),
+                }
+            })
+            .collect();
+        ScoreSheet {
+            points: self.rules.points,
+            start,
+            rows,
+            winner: self.winner(),
+            timing: self.timing(),
+        }
+    }
+}
+
+impl fmt::Display for ScoreSheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} point match", self.points)?;
+        write!(f, "Game  Winner    Cube  Points  Score")?;
+        if self.start != (0, 0) {
+            write!(
+                f,
+                "\nStart {:>29}",
+                format!("{}-{}", self.start.0, self.start.1)
+            )?;
+        }
+        for row in &self.rows {
+            write!(
+                f,
+                "\n{:>4}  {:<8}  {:>4}  {:>6}  {:>5}",
+                row.game,
+                row.winner.to_string(),
+                row.cube,
+                row.points,
+                format!("{}-{}", row.score.0, row.score.1)
+            )?;
+            if row.crawford {
+                write!(f, "  Crawford")?;
+            }
+        }
+        if let Some(winner) = self.winner {
+            write!(f, "\nWinner: {}", winner)?;
+        }
+        let (player0, player1) = self.timing;
+        if player0.decisions + player1.decisions > 0 {
+            write!(
+                f,
+                "\nThinking time: {} {}, {} {}",
+                Player::Player0,
+                Seconds(player0),
+                Player::Player1,
+                Seconds(player1)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the time taken for decisions in seconds, with the average per decision
+struct Seconds(TimingStats);
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = |millis: u64| format!("{}.{}s", millis / 1000, millis % 1000 / 100);
+        write!(f, "{}", seconds(self.0.total))?;
+        if let Some(average) = self.0.average() {
+            write!(f, " ({} per decision)", seconds(average))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, MatchRules, Rules};
+    use crate::{Error, Game, PlayerAction};
+
+    /// Let the player win the current game of the match by resignation, optionally after the
+    /// cube has been doubled
+    fn win(m: &mut Match, player: Player, double: bool) -> Result<(), Error> {
+        let game = m.current_game()?;
+        let crawford = game.is_crawford();
+        *game = Game::resume(Board::new(), Cube::default(), player, crawford)?;
+        if double {
+            game.submit(player, PlayerAction::OfferDouble)?;
+            game.submit(player.other(), PlayerAction::Take)?;
+        }
+        game.submit(player.other(), PlayerAction::Resign)
+    }
+
+    #[test]
+    fn sheet() -> Result<(), Error> {
+        let mut m = Match::preset(5)?;
+        win(&mut m, Player::Player0, true)?;
+        win(&mut m, Player::Player1, false)?;
+        win(&mut m, Player::Player0, true)?;
+        win(&mut m, Player::Player0, false)?;
+
+        let sheet = m.score_sheet();
+        assert_eq!(sheet.rows.len(), 4);
+        assert_eq!(sheet.rows[0].cube, 2);
+        assert_eq!(sheet.rows[2].score, (4, 1));
+        assert!(sheet.rows[3].crawford);
+        assert_eq!(sheet.winner, Some(Player::Player0));
+        let expected = [
+            "5 point match",
+            "Game  Winner    Cube  Points  Score",
+            "   1  Player 0     2       2    2-0",
+            "   2  Player 1     1       1    2-1",
+            "   3  Player 0     2       2    4-1",
+            "   4  Player 0     1       1    5-1  Crawford",
+            "Winner: Player 0",
+        ];
+        assert_eq!(sheet.to_string(), expected.join("\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn resumed() -> Result<(), Error> {
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player1, false)?;
+        let mut m = Match::resume(Rules::default().with_points(9), (5, 3), game)?;
+        win(&mut m, Player::Player1, false)?;
+        let sheet = m.score_sheet();
+        assert_eq!(sheet.start, (5, 3));
+        assert_eq!(sheet.rows[0].score, (5, 4));
+        assert_eq!(sheet.winner, None);
+        assert!(sheet
+            .to_string()
+            .contains("\nStart                           5-3\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn thinking_time() -> Result<(), Error> {
+        let mut m = Match::preset(3)?;
+        let game = m.current_game()?;
+        *game = Game::resume(Board::new(), Cube::default(), Player::Player0, false)?;
+        game.submit_at(Player::Player0, PlayerAction::OfferDouble, 10_000)?;
+        game.submit_at(Player::Player1, PlayerAction::Take, 14_500)?;
+        game.submit_at(Player::Player0, PlayerAction::Resign, 16_000)?;
+        win(&mut m, Player::Player0, false)?;
+
+        let sheet = m.score_sheet();
+        assert_eq!(sheet.rows[0].thinking, (1_500, 4_500));
+        assert_eq!(sheet.rows[1].thinking, (0, 0));
+        let (player0, player1) = sheet.timing;
+        assert_eq!(
+            (player0.decisions, player0.total, player0.longest),
+            (1, 1_500, 1_500)
+        );
+        assert_eq!((player1.decisions, player1.average()), (1, Some(4_500)));
+        assert!(sheet.to_string().ends_with(
+            "\nThinking time: Player 0 1.5s (1.5s per decision), Player 1 4.5s (4.5s per decision)"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use crate::rules::{Board, Player, PositionIndex, Turn, Variant};
+use crate::Error;
+
+/// The largest change of an equity in a sweep at which the solver stops
+const TOLERANCE: f64 = 1e-12;
+
+/// Get the 21 different rolls of two dices, each with its probability
+fn rolls() -> impl Iterator<Item = ((u8, u8), f64)> {
+    (1..=6).flat_map(|first| {
+        (first..=6).map(move |second| {
+            let ways = if first == second { 1.0 } else { 2.0 };
+            ((second, first), ways / 36.0)
+        })
+    })
+}
+
+/// Computes the exact cubeless money equities of Hypergammon positions by retrograde analysis.
+///
+/// The equity of a position is the points the player on roll wins on average, from -3 to 3,
+/// playing the best turn for each roll against an opponent doing the same; gammons and
+/// backgammons count double and triple. The solver covers all positions of at most `checkers`
+/// checkers of each player on the board, the others of the three checkers of Hypergammon being
+/// borne off, and iterates the equities of all of them until they no longer change.
+///
+/// Positions are numbered with [`PositionIndex::hypergammon`]. With three checkers, i.e. all
+/// Hypergammon positions, a sweep runs over about 10.7 million positions and the solve takes
+/// hours, so store [`HypergammonSolver::equities`] once solved and load them with
+/// [`HypergammonSolver::from_equities`].
+///
+/// ```
+/// use backgammon::HypergammonSolver;
+/// use backgammon::rules::{Board, BoardDisplay, Player};
+///
+/// // the last checkers of both players, on their 2-points
+/// let mut fields = [0; 24];
+/// fields[1] = 1;
+/// fields[22] = -1;
+/// let display = BoardDisplay {
+///     board: fields,
+///     bar: (0, 0),
+///     off: (2, 2),
+/// };
+/// let board = Board::from_display(&display).unwrap();
+/// let solver = HypergammonSolver::solve(1).unwrap();
+/// let equity = solver.equity(&board, Player::Player0).unwrap().unwrap();
+/// assert!((equity - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HypergammonSolver {
+    index: PositionIndex,
+    /// the fields, bar and off of one player for each index of the checkers
+    sides: Vec<([u8; 24], u8, u8)>,
+    /// the equity of the player on roll for each index of their checkers and the opponent's
+    equities: Vec<f64>,
+}
+
+impl HypergammonSolver {
+    /// Solve all positions of at most 1 to 3 checkers of each player on the board
+    pub fn solve(checkers: u8) -> Result<Self, Error> {
+        let mut solver = HypergammonSolver::new(checkers)?;
+        loop {
+            let mut change: f64 = 0.0;
+            for state in 0..solver.equities.len() {
+                let Some(board) = solver.board(state) else {
+                    continue;
+                };
+                let mut equity = 0.0;
+                for (dices, probability) in rolls() {
+                    equity += probability * solver.best(&board, Player::Player0, dices)?.1;
+                }
+                change = change.max((equity - solver.equities[state]).abs());
+                solver.equities[state] = equity;
+            }
+            if change < TOLERANCE {
+                return Ok(solver);
+            }
+        }
+    }
+
+    /// Load the equities solved before, see [`HypergammonSolver::equities`]
+    pub fn from_equities(checkers: u8, equities: Vec<f64>) -> Result<Self, Error> {
+        let mut solver = HypergammonSolver::new(checkers)?;
+        if equities.len() != solver.equities.len() {
+            return Err(Error::PositionInvalid);
+        }
+        solver.equities = equities;
+        Ok(solver)
+    }
+
+    /// Get the equities of all positions, numbered by the index of the checkers of the player on
+    /// roll times [`PositionIndex::len`] plus the index of the checkers of the opponent.
+    /// Positions which can not occur in a game in progress have an equity of 0.
+    pub fn equities(&self) -> &[f64] {
+        &self.equities
+    }
+
+    /// Get the equity of the player on roll, or `None` if the position is not covered by the
+    /// solver or the game is over
+    pub fn equity(&self, board: &Board, player: Player) -> Result<Option<f64>, Error> {
+        if board.validate_variant(Variant::Hypergammon).is_err() {
+            return Err(Error::PositionInvalid);
+        }
+        if board.is_finished() {
+            return Ok(None);
+        }
+        self.lookup(board, player)
+    }
+
+    /// Get the equity of the player on roll in a position of a game in progress
+    fn lookup(&self, board: &Board, player: Player) -> Result<Option<f64>, Error> {
+        let (roller, opponent) = (
+            self.index.board_index(board, player)?,
+            self.index.board_index(board, player.other())?,
+        );
+        Ok(roller.zip(opponent).map(|(roller, opponent)| {
+            self.equities[(roller * self.index.len() + opponent) as usize]
+        }))
+    }
+
+    /// Get the best turn of the player with the dices rolled, together with its equity
+    pub fn best_turn(
+        &self,
+        board: &Board,
+        player: Player,
+        dices: (u8, u8),
+    ) -> Result<(Turn, f64), Error> {
+        if self.equity(board, player)?.is_none() {
+            return Err(Error::PositionInvalid);
+        }
+        self.best(board, player, dices)
+    }
+
+    /// Create a solver with all equities unknown
+    fn new(checkers: u8) -> Result<Self, Error> {
+        if !(1..=3).contains(&checkers) {
+            return Err(Error::PositionInvalid);
+        }
+        let index = PositionIndex::new(25, checkers);
+        let sides = (0..index.len())
+            .map(|i| {
+                let position = index.position(i).ok_or(Error::PositionInvalid)?;
+                let mut fields = [0; 24];
+                fields.copy_from_slice(&position[..24]);
+                Ok((fields, position[24], 3 - position.iter().sum::<u8>()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let positions = sides.len();
+        Ok(HypergammonSolver {
+            index,
+            sides,
+            equities: vec![0.0; positions * positions],
+        })
+    }
+
+    /// Get the board of a position with player 0 on roll, unless it can not occur in a game in
+    /// progress
+    fn board(&self, state: usize) -> Option<Board> {
+        let positions = self.sides.len();
+        let (roller, opponent) = (self.sides[state / positions], self.sides[state % positions]);
+        if roller.2 == 3 || opponent.2 == 3 {
+            return None;
+        }
+        if (0..24).any(|field| roller.0[field] > 0 && opponent.0[23 - field] > 0) {
+            return None;
+        }
+        Some(Board::from_raw(roller, opponent))
+    }
+
+    /// Get the best turn of the player with the dices rolled with the equities known so far
+    fn best(&self, board: &Board, player: Player, dices: (u8, u8)) -> Result<(Turn, f64), Error> {
+        let mut best: Option<(Turn, f64)> = None;
+        for (turn, after) in board.generate_variant_plays(player, dices, Variant::Hypergammon)? {
+            let equity = match after.win_kind(player) {
+                Some(kind) => kind.multiplier() as f64,
+                None => -self
+                    .lookup(&after, player.other())?
+                    .ok_or(Error::PositionInvalid)?,
+            };
+            if best.as_ref().is_none_or(|(_, e)| equity > *e) {
+                best = Some((turn, equity));
+            }
+        }
+        best.ok_or(Error::MoveInvalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Get the board of the last checker of each player on the given points, i.e. fields + 1
+    fn last_checkers(player0: usize, player1: usize) -> Board {
+        let raw = |point: usize| {
+            let mut fields = [0; 24];
+            fields[point - 1] = 1;
+            (fields, 0, 2)
+        };
+        Board::from_raw(raw(player0), raw(player1))
+    }
+
+    /// Get the probability to win the race of two single checkers for the player on roll,
+    /// computed from the pip counts only
+    fn race(roller: u32, opponent: u32, memo: &mut HashMap<(u32, u32), f64>) -> f64 {
+        if let Some(&p) = memo.get(&(roller, opponent)) {
+            return p;
+        }
+        let mut p = 0.0;
+        for first in 1..=6 {
+            for second in 1..=6 {
+                let pips = match first == second {
+                    true => 4 * first,
+                    false => first + second,
+                };
+                p += match pips >= roller {
+                    true => 1.0,
+                    false => 1.0 - race(opponent, roller - pips, memo),
+                } / 36.0;
+            }
+        }
+        let _ = memo.insert((roller, opponent), p);
+        p
+    }
+
+    #[test]
+    fn races() -> Result<(), Error> {
+        let solver = HypergammonSolver::solve(1)?;
+        let mut memo = HashMap::new();
+        for (roller, opponent) in [(1, 1), (6, 6), (5, 8), (12, 7), (20, 4), (9, 15)] {
+            let board = last_checkers(roller, opponent);
+            let equity = solver
+                .equity(&board, Player::Player0)?
+                .ok_or(Error::MoveInvalid)?;
+            let p = race(roller as u32, opponent as u32, &mut memo);
+            assert!(
+                (equity - (2.0 * p - 1.0)).abs() < 1e-9,
+                "{:?}",
+                (roller, opponent)
+            );
+            let swapped = solver.equity(&board.swapped(), Player::Player1)?;
+            assert_eq!(swapped, Some(equity));
+        }
+        // from the 7-point against the 1-point, player 0 wins with the 18 rolls of at least 7
+        // pips which are not doubles and the 5 doubles but 1-1
+        let equity = solver.equity(&last_checkers(7, 1), Player::Player0)?;
+        assert!(equity.is_some_and(|equity| (equity - 10.0 / 36.0).abs() < 1e-12));
+        Ok(())
+    }
+
+    #[test]
+    fn contact() -> Result<(), Error> {
+        let solver = HypergammonSolver::solve(1)?;
+        // player 0 on the 24-point hits player 1, about to bear off from the 2-point, with 3-1
+        let board = last_checkers(24, 2);
+        let (turn, equity) = solver.best_turn(&board, Player::Player0, (3, 1))?;
+        let mut after = board.clone();
+        turn.apply(&mut after)?;
+        assert_eq!(after.raw(Player::Player1)?.1, 1);
+        assert!(equity > 0.0);
+        // the loser has always borne off a checker
+        for equity in solver.equities() {
+            assert!(equity.abs() < 1.0 + 1e-12, "{}", equity);
+        }
+
+        let loaded = HypergammonSolver::from_equities(1, solver.equities().to_vec())?;
+        assert_eq!(
+            loaded.equity(&board, Player::Player1)?,
+            solver.equity(&board, Player::Player1)?
+        );
+        assert!(HypergammonSolver::from_equities(2, solver.equities().to_vec()).is_err());
+        assert!(HypergammonSolver::solve(4).is_err());
+        // positions of two checkers are not covered
+        let board = Variant::Hypergammon.board();
+        assert!(matches!(
+            solver.best_turn(&board, Player::Player0, (3, 1)),
+            Err(Error::PositionInvalid)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{Game, GameHub, SaveMetadata, SaveSlots};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+/// Trait for persistence backends holding games by ID, e.g. the games of a [`GameHub`].
+///
+/// Implementations outside the crate can back the store with any database. Loading or deleting
+/// an ID not stored fails with [`io::ErrorKind::NotFound`].
+pub trait GameStore {
+    /// Save a game under an ID, replacing the game saved there before
+    fn save(&self, id: u64, game: &Game) -> io::Result<()>;
+
+    /// Load the game saved under an ID
+    fn load(&self, id: u64) -> io::Result<Game>;
+
+    /// List the IDs of all saved games in ascending order
+    fn list(&self) -> io::Result<Vec<u64>>;
+
+    /// Delete the game saved under an ID
+    fn delete(&self, id: u64) -> io::Result<()>;
+}
+
+/// Get the error for an ID not stored
+fn not_found() -> io::Error {
+    io::Error::from(io::ErrorKind::NotFound)
+}
+
+/// Represents a store keeping the games in memory, e.g. for tests
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    games: Mutex<BTreeMap<u64, Game>>,
+}
+
+impl MemoryStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl GameStore for MemoryStore {
+    fn save(&self, id: u64, game: &Game) -> io::Result<()> {
+        let _ = self
+            .games
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, game.clone());
+        Ok(())
+    }
+
+    fn load(&self, id: u64) -> io::Result<Game> {
+        self.games
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&id)
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    fn list(&self) -> io::Result<Vec<u64>> {
+        Ok(self
+            .games
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .copied()
+            .collect())
+    }
+
+    fn delete(&self, id: u64) -> io::Result<()> {
+        self.games
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(not_found)
+    }
+}
+
+/// Represents a store keeping one file per game in a directory, in the format of [`SaveSlots`]
+/// named by the ID. As with save slots, the complete game is stored, so games can be saved at
+/// any time and keep their history.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    slots: SaveSlots,
+}
+
+impl FileStore {
+    /// Create the store in a directory, which is created when saving the first game
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileStore {
+            slots: SaveSlots::new(dir),
+        }
+    }
+}
+
+impl GameStore for FileStore {
+    fn save(&self, id: u64, game: &Game) -> io::Result<()> {
+        let metadata = SaveMetadata::new((String::new(), String::new()), (0, 0), game);
+        self.slots.save(&id.to_string(), game, &metadata)
+    }
+
+    fn load(&self, id: u64) -> io::Result<Game> {
+        self.slots.load(&id.to_string()).map(|(game, _)| game)
+    }
+
+    fn list(&self) -> io::Result<Vec<u64>> {
+        let mut ids: Vec<u64> = self
+            .slots
+            .list()?
+            .into_iter()
+            .filter_map(|(name, _)| name.parse().ok())
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn delete(&self, id: u64) -> io::Result<()> {
+        self.slots.delete(&id.to_string())
+    }
+}
+
+impl GameHub {
+    /// Save all games of the hub into a store under their IDs
+    pub fn save_to(&self, store: &dyn GameStore) -> io::Result<()> {
+        for (id, game) in self.snapshot() {
+            store.save(id, &game)?;
+        }
+        Ok(())
+    }
+
+    /// Create a hub from all games saved in a store, see [`GameHub::restore`]
+    ///
+    /// ```
+    /// use backgammon::{Game, GameHub, GameStore, MemoryStore};
+    ///
+    /// let store = MemoryStore::new();
+    /// let hub = GameHub::new();
+    /// let id = hub.create(Game::new()).unwrap();
+    /// hub.save_to(&store).unwrap();
+    ///
+    /// let restored = GameHub::load_from(&store).unwrap();
+    /// assert!(restored.get(id).is_some());
+    /// assert_eq!(store.list().unwrap(), vec![id]);
+    /// ```
+    pub fn load_from(store: &dyn GameStore) -> io::Result<GameHub> {
+        let mut snapshot = BTreeMap::new();
+        for id in store.list()? {
+            let _ = snapshot.insert(id, store.load(id)?);
+        }
+        GameHub::restore(snapshot).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Cube, Player, Roll, Variant};
+    use crate::{GamePhase, PlayerAction};
+    use std::fs;
+
+    /// Save, load, list and delete games in a store
+    fn exercise(store: &dyn GameStore) -> io::Result<()> {
+        assert!(store.list()?.is_empty());
+        let game = Game::resume(Board::new(), Cube::default(), Player::Player1, false)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        store.save(7, &game)?;
+        store.save(3, &Game::new())?;
+        assert_eq!(store.list()?, vec![3, 7]);
+        assert_eq!(store.load(7)?.who_plays, Player::Player1);
+
+        store.delete(7)?;
+        assert_eq!(
+            store.load(7).err().map(|e| e.kind()),
+            Some(io::ErrorKind::NotFound)
+        );
+        assert_eq!(
+            store.delete(7).err().map(|e| e.kind()),
+            Some(io::ErrorKind::NotFound)
+        );
+        store.delete(3)?;
+        assert!(store.list()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn memory_store() -> io::Result<()> {
+        exercise(&MemoryStore::new())
+    }
+
+    #[test]
+    fn file_store() -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("backgammon-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        exercise(&FileStore::new(&dir))?;
+
+        let hub = GameHub::new();
+        let id = hub.create(Game::new()).unwrap();
+        let store = FileStore::new(&dir);
+        hub.save_to(&store)?;
+        let restored = GameHub::load_from(&store)?;
+        assert_eq!(restored.len(), 1);
+        assert!(restored.get(id).is_some());
+        fs::remove_dir_all(&dir)
+    }
+
+    #[test]
+    fn file_store_mid_turn() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = std::env::temp_dir().join(format!("backgammon-mid-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        // a Nackgammon game with the first checker of a turn moved
+        let mut game = Game::new_variant(Variant::Nackgammon);
+        while game.phase() == GamePhase::OpeningRoll {
+            let _ = game.roll()?;
+        }
+        let (player, dice) = (game.who_plays, game.dices.values.0);
+        let moved = (0..24).any(|from| {
+            game.submit(player, PlayerAction::Move { dice, from })
+                .is_ok()
+        });
+        assert!(moved);
+        assert_eq!(game.phase(), GamePhase::Moving);
+
+        let hub = GameHub::new();
+        let id = hub.create(game.clone())?;
+        let store = FileStore::new(&dir);
+        hub.save_to(&store)?;
+        let restored = GameHub::load_from(&store)?.get(id).unwrap().snapshot();
+        assert_eq!(restored.board, game.board);
+        assert_eq!(restored.dices, game.dices);
+        assert_eq!(restored.variant(), Variant::Nackgammon);
+        assert_eq!(restored.who_plays, player);
+        assert_eq!(restored.history().len(), game.history().len());
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}